@@ -0,0 +1,10 @@
+/// Compiles the protobuf definitions of the gRPC services, with the vendored `protoc`.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/todo/v1/todo.proto"], &["proto"])?;
+
+    Ok(())
+}
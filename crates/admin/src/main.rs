@@ -0,0 +1,354 @@
+mod output;
+mod seed;
+
+use clap::{Parser, Subcommand};
+use database::entities::user;
+use migration::{Migrator, MigratorTrait};
+use output::{Format, Table};
+use sea_orm::{Database, DbConn};
+use serde::Serialize;
+use services::error::ApiError;
+use services::user::mutations::user::UserMutations;
+use services::user::queries::user::UserQueries;
+use std::error::Error;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Administration of the TODO API, working on the database of `DATABASE_URL`.
+#[derive(Parser)]
+#[command(name = "todo-admin", version)]
+struct Cli {
+    /// How to print the results.
+    #[arg(long, value_enum, default_value_t, global = true)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage the accounts.
+    #[command(subcommand)]
+    Users(UsersCommand),
+    /// Delete for good the accounts whose deletion grace period is over, with all of their data.
+    PurgeTrash,
+    /// Apply or list the database migrations.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Create a demo account with a few tasks.
+    Seed {
+        /// The username of the demo account.
+        #[arg(long, default_value = "demo-user")]
+        username: String,
+        /// The password of the demo account.
+        #[arg(long, default_value = "demo-password")]
+        password: String,
+    },
+    /// Write every piece of data of a user to a zip archive, like `GET /auth/me/export`.
+    Export {
+        /// The username of the user.
+        username: String,
+        /// The archive to write. Defaults to `todo-export-<username>.zip`.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum UsersCommand {
+    /// List the accounts with the number of their tasks.
+    List,
+    /// Create an account, the password being read from the standard input.
+    Create {
+        /// The username of the new account.
+        username: String,
+    },
+    /// Replace the password of an account, read from the standard input, revoking its auth tokens.
+    ResetPassword {
+        /// The username of the account.
+        username: String,
+    },
+    /// Lock an account until it is activated again, revoking its auth tokens.
+    Deactivate {
+        /// The username of the account.
+        username: String,
+    },
+    /// Unlock a deactivated account.
+    Activate {
+        /// The username of the account.
+        username: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Apply the pending migrations.
+    Up,
+    /// List the migrations and whether they are applied.
+    Status,
+}
+
+/// The lock state of an account, printed after changing it.
+#[derive(Serialize)]
+struct AccountState {
+    id: i32,
+    username: String,
+    deactivated_at: Option<chrono::DateTime<chrono::Utc>>,
+    token_generation: i32,
+}
+
+impl From<user::Model> for AccountState {
+    fn from(user: user::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            deactivated_at: user.deactivated_at,
+            token_generation: user.token_generation,
+        }
+    }
+}
+
+/// A migration and whether it is applied.
+#[derive(Serialize)]
+struct MigrationState {
+    name: String,
+    status: String,
+}
+
+/// Reads a password from the standard input, prompting for it on a terminal.
+///
+/// # Returns
+///
+/// A `Result` containing the password, or an error if it cannot be read or is too short.
+fn read_password() -> Result<String, Box<dyn Error>> {
+    if io::stdin().is_terminal() {
+        eprint!("Password: ");
+        io::stderr().flush()?;
+    }
+
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password)?;
+    let password = password.trim_end_matches(['\r', '\n']).to_string();
+
+    // The same rule as the sign-up payload
+    if password.chars().count() < 5 {
+        return Err(ApiError::Validation(
+            "The password must be at least 5 characters long.".to_string(),
+        )
+        .into());
+    }
+
+    Ok(password)
+}
+
+/// Prints the lock state of an account.
+fn print_account(format: Format, user: user::Model) {
+    let account = AccountState::from(user);
+
+    let mut table = Table::new(&["ID", "USERNAME", "DEACTIVATED AT", "TOKEN GENERATION"]);
+    table.row(vec![
+        account.id.to_string(),
+        account.username.clone(),
+        output::timestamp(account.deactivated_at),
+        account.token_generation.to_string(),
+    ]);
+
+    output::print(format, &account, table);
+}
+
+/// Runs a command of the `users` group.
+async fn users(command: UsersCommand, format: Format, db: &DbConn) -> Result<(), Box<dyn Error>> {
+    match command {
+        UsersCommand::List => {
+            let accounts = UserQueries::get_account_summaries(db).await?;
+
+            let mut table = Table::new(&[
+                "ID",
+                "USERNAME",
+                "TASKS",
+                "CREATED AT",
+                "DEACTIVATED AT",
+                "DELETION AT",
+            ]);
+            for account in &accounts {
+                table.row(vec![
+                    account.id.to_string(),
+                    account.username.clone(),
+                    account.tasks.to_string(),
+                    output::timestamp(Some(account.created_at)),
+                    output::timestamp(account.deactivated_at),
+                    output::timestamp(account.deletion_scheduled_at),
+                ]);
+            }
+
+            output::print(format, &accounts, table);
+        }
+        UsersCommand::Create { username } => {
+            // The same rule as the sign-up payload
+            if username.chars().count() < 5 {
+                return Err(ApiError::Validation(
+                    "The username must be at least 5 characters long.".to_string(),
+                )
+                .into());
+            }
+
+            let password = read_password()?;
+            let user = UserMutations::create(username, password, db).await?;
+
+            let mut table = Table::new(&["ID", "USERNAME", "CREATED AT"]);
+            table.row(vec![
+                user.id.to_string(),
+                user.username.clone(),
+                output::timestamp(Some(user.created_at)),
+            ]);
+
+            output::print(format, &user, table);
+        }
+        UsersCommand::ResetPassword { username } => {
+            // Fail on an unknown user before asking for the password
+            UserQueries::get_user_by_username(&username, db).await?;
+
+            let password = read_password()?;
+            let user = UserMutations::reset_password(&username, password, db).await?;
+
+            print_account(format, user);
+        }
+        UsersCommand::Deactivate { username } => {
+            let user = UserMutations::deactivate(&username, db).await?;
+
+            print_account(format, user);
+        }
+        UsersCommand::Activate { username } => {
+            let user = UserMutations::activate(&username, db).await?;
+
+            print_account(format, user);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a command of the `migrate` group.
+async fn migrate(
+    command: MigrateCommand,
+    format: Format,
+    db: &DbConn,
+) -> Result<(), Box<dyn Error>> {
+    let migrations = match command {
+        MigrateCommand::Up => {
+            // Same lock as the server, so a migration never runs twice at once
+            Migrator::up_locked(db)
+                .await?
+                .into_iter()
+                .map(|name| MigrationState {
+                    name,
+                    status: "Applied".to_string(),
+                })
+                .collect::<Vec<_>>()
+        }
+        MigrateCommand::Status => Migrator::get_migration_with_status(db)
+            .await?
+            .iter()
+            .map(|migration| MigrationState {
+                name: migration.name().to_string(),
+                status: migration.status().to_string(),
+            })
+            .collect(),
+    };
+
+    let mut table = Table::new(&["MIGRATION", "STATUS"]);
+    for migration in &migrations {
+        table.row(vec![migration.name.clone(), migration.status.clone()]);
+    }
+
+    output::print(format, &migrations, table);
+
+    Ok(())
+}
+
+/// Runs a command.
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    // Same configuration as the API server
+    let url = std::env::var("DATABASE_URL").map_err(|_| "Database URL not found")?;
+    let db = Database::connect(url).await?;
+    let format = cli.format;
+
+    match cli.command {
+        Command::Users(command) => users(command, format, &db).await?,
+        Command::Migrate(command) => migrate(command, format, &db).await?,
+        Command::PurgeTrash => {
+            let deleted = UserMutations::purge_deleted(&db).await?;
+
+            let mut table = Table::new(&["DELETED ACCOUNTS"]);
+            table.row(vec![deleted.to_string()]);
+
+            output::print(
+                format,
+                &serde_json::json!({ "deleted_accounts": deleted }),
+                table,
+            );
+        }
+        Command::Seed { username, password } => {
+            let (user, tasks) = seed::seed(username, password, &db).await?;
+
+            let mut table = Table::new(&["USER", "TASK ID", "NAME", "DONE", "DUE DATE"]);
+            for task in &tasks {
+                table.row(vec![
+                    user.username.clone(),
+                    task.id.to_string(),
+                    task.name.clone(),
+                    task.is_active.to_string(),
+                    output::date(task.due_date),
+                ]);
+            }
+
+            output::print(
+                format,
+                &serde_json::json!({ "user": user, "tasks": tasks }),
+                table,
+            );
+        }
+        Command::Export { username, output } => {
+            let user = UserQueries::get_user_by_username(&username, &db).await?;
+            let archive = UserQueries::get_account_export(user.id, &db)
+                .await?
+                .into_archive()?;
+
+            let path =
+                output.unwrap_or_else(|| PathBuf::from(format!("todo-export-{username}.zip")));
+            std::fs::write(&path, &archive)?;
+
+            let mut table = Table::new(&["USERNAME", "FILE", "BYTES"]);
+            table.row(vec![
+                username.clone(),
+                path.display().to_string(),
+                archive.len().to_string(),
+            ]);
+
+            output::print(
+                format,
+                &serde_json::json!({ "username": username, "file": path, "bytes": archive.len() }),
+                table,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Load environment variables from .env file, like the API server
+    let _ = dotenvy::dotenv();
+
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// How the results of a command are printed.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum Format {
+    /// Aligned columns, for people.
+    #[default]
+    Table,
+    /// Indented JSON, for scripts.
+    Json,
+}
+
+/// Rows of text printed as aligned columns.
+pub struct Table {
+    /// The names of the columns.
+    headers: Vec<&'static str>,
+    /// The cells of each row, one per column.
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Starts a table without rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The names of the columns.
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row to the table.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The cells of the row, one per column.
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Writes the table, each column as wide as its widest cell.
+    ///
+    /// # Returns
+    ///
+    /// The lines of the table, the headers first.
+    fn render(&self) -> String {
+        let mut widths = self
+            .headers
+            .iter()
+            .map(|header| header.chars().count())
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![line(self.headers.clone())];
+        lines.extend(
+            self.rows
+                .iter()
+                .map(|row| line(row.iter().map(String::as_str).collect())),
+        );
+
+        lines.join("\n")
+    }
+}
+
+/// Prints the result of a command.
+///
+/// # Arguments
+///
+/// * `format` - How to print the result.
+/// * `value` - The result, printed as JSON.
+/// * `table` - The result, printed as a table.
+pub fn print<T: Serialize + ?Sized>(format: Format, value: &T, table: Table) {
+    let text = match format {
+        Format::Table => table.render(),
+        Format::Json => serde_json::to_string_pretty(value).expect("results are valid JSON"),
+    };
+
+    // A closed pipe, such as `| head`, is not an error of the command
+    let _ = writeln!(io::stdout().lock(), "{text}");
+}
+
+/// Formats an optional timestamp for a table cell, `-` when unset.
+pub fn timestamp(value: Option<DateTime<Utc>>) -> String {
+    value
+        .map(|value| value.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Formats an optional date for a table cell, `-` when unset.
+pub fn date(value: Option<NaiveDate>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_aligned_on_the_widest_cell() {
+        let mut table = Table::new(&["ID", "USERNAME", "TASKS"]);
+        table.row(vec!["1".to_string(), "bobby".to_string(), "12".to_string()]);
+        table.row(vec!["10".to_string(), "zoé".to_string(), String::new()]);
+
+        assert_eq!(
+            table.render(),
+            "ID  USERNAME  TASKS\n1   bobby     12\n10  zoé"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::{Duration, Utc};
+use database::entities::{task, user};
+use sea_orm::DbConn;
+use services::error::ApiError;
+use services::task::mutations::task::{TaskMutation, TaskPayload};
+use services::user::mutations::user::UserMutations;
+
+/// The tasks of the demo account: name, description, done, and due in that many days.
+const DEMO_TASKS: [(&str, &str, bool, Option<i64>); 6] = [
+    (
+        "Buy groceries",
+        "Milk, eggs, bread and coffee beans",
+        false,
+        Some(1),
+    ),
+    (
+        "Pay the rent",
+        "Transfer before the first of the month",
+        false,
+        Some(5),
+    ),
+    (
+        "Book dentist",
+        "Yearly check-up, mornings work best",
+        false,
+        Some(14),
+    ),
+    (
+        "Water the plants",
+        "The fern needs more than the cactus",
+        true,
+        Some(-1),
+    ),
+    (
+        "Read a book",
+        "Finish the novel started last month",
+        false,
+        None,
+    ),
+    (
+        "Call grandma",
+        "Ask about the recipe of the apple pie",
+        true,
+        None,
+    ),
+];
+
+/// Creates a demo account with a few tasks, some done and most due in the coming days.
+///
+/// # Arguments
+///
+/// * `username` - The username of the demo account.
+/// * `password` - The password of the demo account.
+/// * `db` - SeaORM database connection (`&DbConn`).
+///
+/// # Returns
+///
+/// A `Result` containing the account and its tasks on success, `ApiError::Conflict` if the username is taken,
+/// or another `ApiError` on failure.
+pub async fn seed(
+    username: String,
+    password: String,
+    db: &DbConn,
+) -> Result<(user::Model, Vec<task::Model>), ApiError> {
+    let user = UserMutations::create(username, password, db).await?;
+    let today = Utc::now().date_naive();
+
+    let mut tasks = Vec::with_capacity(DEMO_TASKS.len());
+    for (name, description, done, due_in) in DEMO_TASKS {
+        let task = TaskMutation::create(
+            TaskPayload {
+                name: name.to_string(),
+                description: description.to_string(),
+                is_active: done,
+                due_date: due_in.map(|days| today + Duration::days(days)),
+                priority: None,
+                tags: None,
+                project: None,
+                user_id: user.id,
+                request_id: None,
+                if_match: None,
+            },
+            db,
+        )
+        .await?;
+        tasks.push(task);
+    }
+
+    Ok((user, tasks))
+}
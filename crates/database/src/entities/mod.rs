@@ -1,2 +1,11 @@
+pub mod app_password;
+pub mod caldav_object;
+pub mod calendar_feed;
+pub mod idempotency_key;
+pub mod saved_filter;
 pub mod task;
+pub mod task_event;
+pub mod task_tombstone;
 pub mod user;
+pub mod webhook;
+pub mod webhook_delivery;
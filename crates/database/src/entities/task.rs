@@ -1,8 +1,12 @@
+use rocket::FromFormField;
 use sea_orm::entity::prelude::*;
+use sea_orm::Set;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "tasks")]
+#[schema(as = Task)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
@@ -11,6 +15,68 @@ pub struct Model {
     #[sea_orm(default_value = false)]
     pub is_active: bool,
     pub user_id: i32,
+    pub due_date: Option<Date>,
+    pub priority: TaskPriority,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    #[sea_orm(default_value = 1)]
+    pub version: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTimeUtc,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTimeUtc,
+    // Positions in the change feed, maintained by the `task_track_change` trigger
+    #[serde(skip)]
+    pub change_seq: i64,
+    #[serde(skip)]
+    pub created_seq: i64,
+}
+
+/// Priority of a task, stored as its rank so priorities can be compared.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    FromFormField,
+)]
+#[sea_orm(rs_type = "i32", db_type = "Integer")]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    #[sea_orm(num_value = 1)]
+    #[field(value = "low")]
+    Low,
+    #[default]
+    #[sea_orm(num_value = 2)]
+    #[field(value = "normal")]
+    Normal,
+    #[sea_orm(num_value = 3)]
+    #[field(value = "high")]
+    High,
+    #[sea_orm(num_value = 4)]
+    #[field(value = "urgent")]
+    Urgent,
+}
+
+impl TaskPriority {
+    /// Returns the name of the priority, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskPriority::Low => "low",
+            TaskPriority::Normal => "normal",
+            TaskPriority::High => "high",
+            TaskPriority::Urgent => "urgent",
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -29,4 +95,20 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+/// Maintains the `created_at` and `updated_at` timestamps on every save.
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = chrono::Utc::now();
+
+        if insert && self.created_at.is_not_set() {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
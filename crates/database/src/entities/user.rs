@@ -1,20 +1,41 @@
 use sea_orm::entity::prelude::*;
+use sea_orm::Set;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
 #[sea_orm(table_name = "users")]
+#[schema(as = User)]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
     pub id: i32,
     pub username: String,
     #[serde(skip_serializing)]
     pub password: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTimeUtc,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTimeUtc,
+    // Only the auth tokens issued in this generation are honoured, every revocation starts a new one
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub token_generation: i32,
+    // The account is deleted for good at this instant, unless restored before
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub deletion_scheduled_at: Option<DateTimeUtc>,
+    // The account is locked since this instant, until an administrator activates it again
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub deactivated_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::task::Entity")]
     Tasks,
+    #[sea_orm(has_many = "super::saved_filter::Entity")]
+    SavedFilters,
 }
 
 impl Related<super::task::Entity> for Entity {
@@ -23,4 +44,26 @@ impl Related<super::task::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+impl Related<super::saved_filter::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SavedFilters.def()
+    }
+}
+
+/// Maintains the `created_at` and `updated_at` timestamps on every save.
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = chrono::Utc::now();
+
+        if insert && self.created_at.is_not_set() {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
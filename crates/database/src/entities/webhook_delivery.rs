@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[sea_orm(table_name = "webhook_deliveries")]
+#[schema(as = WebhookDelivery)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    // Kept as sent, so the signature of a redelivery matches the original body
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub next_attempt_at: DateTimeUtc,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub last_attempt_at: Option<DateTimeUtc>,
+    pub response_status: Option<i16>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    pub redelivery_of: Option<i32>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook::Entity",
+        from = "Column::WebhookId",
+        to = "super::webhook::Column::Id"
+    )]
+    Webhook,
+}
+
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
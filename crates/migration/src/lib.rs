@@ -1,9 +1,23 @@
 pub use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{DatabaseConnection, DbBackend, Statement, TransactionTrait};
 
 mod m20240624_213509_task_create;
 mod m20240625_184538_user_create;
 mod m20240625_193356_task_add_user_id;
 mod m20240625_200306_user_task_relations;
+mod m20261018_090000_task_search_vector;
+mod m20261018_100000_task_add_filter_fields;
+mod m20261018_100100_saved_filter_create;
+mod m20261018_110000_task_event_create;
+mod m20261018_120000_task_add_version;
+mod m20261018_130000_idempotency_key_create;
+mod m20261018_140000_add_timestamps;
+mod m20261018_150000_task_change_feed;
+mod m20261018_160000_webhook_create;
+mod m20261018_170000_caldav_create;
+mod m20261018_180000_calendar_feed_create;
+mod m20261018_190000_user_account_deletion;
+mod m20261018_200000_user_deactivation;
 
 pub struct Migrator;
 
@@ -15,6 +29,61 @@ impl MigratorTrait for Migrator {
             Box::new(m20240625_184538_user_create::Migration),
             Box::new(m20240625_193356_task_add_user_id::Migration),
             Box::new(m20240625_200306_user_task_relations::Migration),
+            Box::new(m20261018_090000_task_search_vector::Migration),
+            Box::new(m20261018_100000_task_add_filter_fields::Migration),
+            Box::new(m20261018_100100_saved_filter_create::Migration),
+            Box::new(m20261018_110000_task_event_create::Migration),
+            Box::new(m20261018_120000_task_add_version::Migration),
+            Box::new(m20261018_130000_idempotency_key_create::Migration),
+            Box::new(m20261018_140000_add_timestamps::Migration),
+            Box::new(m20261018_150000_task_change_feed::Migration),
+            Box::new(m20261018_160000_webhook_create::Migration),
+            Box::new(m20261018_170000_caldav_create::Migration),
+            Box::new(m20261018_180000_calendar_feed_create::Migration),
+            Box::new(m20261018_190000_user_account_deletion::Migration),
+            Box::new(m20261018_200000_user_deactivation::Migration),
         ]
     }
 }
+
+/// Key of the Postgres advisory lock held while migrating, the ASCII of `todomigr`.
+const MIGRATION_LOCK_KEY: i64 = 0x746f_646f_6d69_6772;
+
+impl Migrator {
+    /// Applies the pending migrations, one process at a time.
+    ///
+    /// The transaction-level advisory lock makes the other server instances and admin commands migrating
+    /// at the same time wait until the migrations are committed, finding none pending once they get it.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - SeaORM database connection (`&DatabaseConnection`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the names of the applied migrations, or a `DbErr` on failure.
+    pub async fn up_locked(conn: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+        let txn = conn.begin().await?;
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT pg_advisory_xact_lock($1)",
+            [MIGRATION_LOCK_KEY.into()],
+        ))
+        .await?;
+
+        let pending = Self::get_pending_migrations(&txn)
+            .await?
+            .iter()
+            .map(|migration| migration.name().to_string())
+            .collect::<Vec<_>>();
+        if !pending.is_empty() {
+            Self::up(&txn, None).await?;
+        }
+
+        // Committing releases the lock
+        txn.commit().await?;
+
+        Ok(pending)
+    }
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Generated column, names weigh more than descriptions when ranking. Tasks have no comments
+        // column, so the name and the description are all there is to search
+        db.execute_unprepared(
+            "ALTER TABLE tasks ADD COLUMN IF NOT EXISTS search_vector tsvector \
+             GENERATED ALWAYS AS ( \
+                 setweight(to_tsvector('simple', coalesce(name, '')), 'A') || \
+                 setweight(to_tsvector('simple', coalesce(description, '')), 'B') \
+             ) STORED",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_search_vector ON tasks USING GIN (search_vector)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_search_vector")
+                    .table(Task::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .drop_column(Task::SearchVector)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    #[sea_orm(iden = "tasks")]
+    Table,
+    SearchVector,
+}
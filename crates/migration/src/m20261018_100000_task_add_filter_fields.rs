@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Fields of the filter language: priorities go from 1 (low) to 4 (urgent), 2 being normal
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Task::DueDate).date().null())
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Task::Priority)
+                            .integer()
+                            .not_null()
+                            .default(2),
+                    )
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Task::Tags)
+                            .array(ColumnType::Text)
+                            .not_null()
+                            .default(Expr::cust("'{}'")),
+                    )
+                    .add_column_if_not_exists(ColumnDef::new(Task::Project).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX IF NOT EXISTS idx_tasks_tags ON tasks USING GIN (tags)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_tags")
+                    .table(Task::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .drop_column(Task::DueDate)
+                    .drop_column(Task::Priority)
+                    .drop_column(Task::Tags)
+                    .drop_column(Task::Project)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    #[sea_orm(iden = "tasks")]
+    Table,
+    #[sea_orm(iden = "due_date")]
+    DueDate,
+    Priority,
+    Tags,
+    Project,
+}
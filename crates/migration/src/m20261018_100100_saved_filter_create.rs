@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedFilter::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SavedFilter::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SavedFilter::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(SavedFilter::Filters)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SavedFilter::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_saved_filter_user")
+                            .from(SavedFilter::Table, SavedFilter::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SavedFilter::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum SavedFilter {
+    #[sea_orm(iden = "saved_filters")]
+    Table,
+    Id,
+    Name,
+    Filters,
+    UserId,
+}
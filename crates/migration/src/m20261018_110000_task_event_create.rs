@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Events outlive their task, so `task_id` is not a foreign key
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TaskEvent::TaskId).integer().not_null())
+                    .col(ColumnDef::new(TaskEvent::UserId).integer().not_null())
+                    .col(ColumnDef::new(TaskEvent::Action).string().not_null())
+                    .col(ColumnDef::new(TaskEvent::Changes).json_binary().not_null())
+                    .col(ColumnDef::new(TaskEvent::RequestId).string().null())
+                    .col(
+                        ColumnDef::new(TaskEvent::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_event_user")
+                            .from(TaskEvent::Table, TaskEvent::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_events_task_id")
+                    .table(TaskEvent::Table)
+                    .col(TaskEvent::TaskId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum TaskEvent {
+    #[sea_orm(iden = "task_events")]
+    Table,
+    Id,
+    TaskId,
+    UserId,
+    Action,
+    Changes,
+    RequestId,
+    CreatedAt,
+}
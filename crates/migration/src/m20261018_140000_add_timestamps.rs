@@ -0,0 +1,124 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Add the columns as nullable first, so existing rows can be backfilled
+        for table in ["tasks", "users"] {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} \
+                 ADD COLUMN IF NOT EXISTS created_at timestamptz, \
+                 ADD COLUMN IF NOT EXISTS updated_at timestamptz"
+            ))
+            .await?;
+        }
+
+        // Tasks take their dates from their history, tasks older than the history
+        // predate its first event
+        db.execute_unprepared(
+            "UPDATE tasks t SET created_at = coalesce( \
+                 (SELECT min(e.created_at) FROM task_events e \
+                  WHERE e.task_id = t.id AND e.action = 'created'), \
+                 (SELECT min(e.created_at) FROM task_events e), \
+                 now()) \
+             WHERE t.created_at IS NULL",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "UPDATE tasks t SET updated_at = coalesce( \
+                 (SELECT max(e.created_at) FROM task_events e WHERE e.task_id = t.id), \
+                 t.created_at) \
+             WHERE t.updated_at IS NULL",
+        )
+        .await?;
+
+        // A user signed up before creating their first task
+        db.execute_unprepared(
+            "UPDATE users u SET created_at = coalesce( \
+                 (SELECT min(t.created_at) FROM tasks t WHERE t.user_id = u.id), \
+                 now()) \
+             WHERE u.created_at IS NULL",
+        )
+        .await?;
+
+        db.execute_unprepared("UPDATE users SET updated_at = created_at WHERE updated_at IS NULL")
+            .await?;
+
+        for table in ["tasks", "users"] {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} \
+                 ALTER COLUMN created_at SET NOT NULL, \
+                 ALTER COLUMN created_at SET DEFAULT now(), \
+                 ALTER COLUMN updated_at SET NOT NULL, \
+                 ALTER COLUMN updated_at SET DEFAULT now()"
+            ))
+            .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_user_updated_at")
+                    .table(Task::Table)
+                    .col(Task::UserId)
+                    .col(Task::UpdatedAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_user_updated_at")
+                    .table(Task::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .drop_column(Task::CreatedAt)
+                    .drop_column(Task::UpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::CreatedAt)
+                    .drop_column(User::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    #[sea_orm(iden = "tasks")]
+    Table,
+    UserId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,218 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("CREATE SEQUENCE IF NOT EXISTS task_change_seq")
+            .await?;
+
+        // Number the existing tasks in the order they were last changed
+        db.execute_unprepared(
+            "ALTER TABLE tasks \
+             ADD COLUMN IF NOT EXISTS change_seq bigint, \
+             ADD COLUMN IF NOT EXISTS created_seq bigint",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "WITH ordered AS ( \
+                 SELECT id, row_number() OVER (ORDER BY updated_at, id) AS seq FROM tasks \
+             ) \
+             UPDATE tasks t SET change_seq = o.seq, created_seq = o.seq \
+             FROM ordered o WHERE t.id = o.id",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "SELECT setval('task_change_seq', coalesce((SELECT max(change_seq) FROM tasks), 0) + 1, false)",
+        )
+        .await?;
+
+        db.execute_unprepared(
+            "ALTER TABLE tasks \
+             ALTER COLUMN change_seq SET NOT NULL, \
+             ALTER COLUMN change_seq SET DEFAULT nextval('task_change_seq'), \
+             ALTER COLUMN created_seq SET NOT NULL, \
+             ALTER COLUMN created_seq SET DEFAULT nextval('task_change_seq')",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tasks_user_change_seq")
+                    .table(Task::Table)
+                    .col(Task::UserId)
+                    .col(Task::ChangeSeq)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Deleted tasks leave a tombstone, so clients learn about the deletion. It keeps the last
+        // version of the task, so a revert restores it above every ETag it had
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskTombstone::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TaskTombstone::TaskId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TaskTombstone::UserId).integer().not_null())
+                    .col(
+                        ColumnDef::new(TaskTombstone::ChangeSeq)
+                            .big_integer()
+                            .not_null()
+                            .default(Expr::cust("nextval('task_change_seq')")),
+                    )
+                    .col(
+                        ColumnDef::new(TaskTombstone::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(TaskTombstone::Version)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_task_tombstone_user")
+                            .from(TaskTombstone::Table, TaskTombstone::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_task_tombstones_user_change_seq")
+                    .table(TaskTombstone::Table)
+                    .col(TaskTombstone::UserId)
+                    .col(TaskTombstone::ChangeSeq)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every write takes the next number of the sequence. Writers of the same user wait for
+        // each other until commit, so a client never sees a number before a smaller one is committed.
+        db.execute_unprepared(
+            "CREATE OR REPLACE FUNCTION task_track_change() RETURNS trigger AS $$ \
+             BEGIN \
+                 IF TG_OP = 'DELETE' THEN \
+                     PERFORM pg_advisory_xact_lock(hashtext('task_changes'), OLD.user_id); \
+                     IF EXISTS (SELECT 1 FROM users WHERE id = OLD.user_id) THEN \
+                         INSERT INTO task_tombstones (task_id, user_id, change_seq, deleted_at, version) \
+                         VALUES (OLD.id, OLD.user_id, nextval('task_change_seq'), now(), OLD.version) \
+                         ON CONFLICT (task_id) DO UPDATE SET \
+                             user_id = EXCLUDED.user_id, \
+                             change_seq = EXCLUDED.change_seq, \
+                             deleted_at = EXCLUDED.deleted_at, \
+                             version = EXCLUDED.version; \
+                     END IF; \
+                     RETURN OLD; \
+                 END IF; \
+                 PERFORM pg_advisory_xact_lock(hashtext('task_changes'), NEW.user_id); \
+                 NEW.change_seq := nextval('task_change_seq'); \
+                 IF TG_OP = 'INSERT' THEN \
+                     NEW.created_seq := NEW.change_seq; \
+                     DELETE FROM task_tombstones WHERE task_id = NEW.id; \
+                 END IF; \
+                 RETURN NEW; \
+             END; \
+             $$ LANGUAGE plpgsql",
+        )
+        .await?;
+
+        db.execute_unprepared("DROP TRIGGER IF EXISTS task_track_change ON tasks")
+            .await?;
+        db.execute_unprepared(
+            "CREATE TRIGGER task_track_change \
+             BEFORE INSERT OR UPDATE OR DELETE ON tasks \
+             FOR EACH ROW EXECUTE FUNCTION task_track_change()",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP TRIGGER IF EXISTS task_track_change ON tasks")
+            .await?;
+        db.execute_unprepared("DROP FUNCTION IF EXISTS task_track_change()")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(TaskTombstone::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tasks_user_change_seq")
+                    .table(Task::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .drop_column(Task::ChangeSeq)
+                    .drop_column(Task::CreatedSeq)
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared("DROP SEQUENCE IF EXISTS task_change_seq")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    #[sea_orm(iden = "tasks")]
+    Table,
+    UserId,
+    ChangeSeq,
+    CreatedSeq,
+}
+
+#[derive(DeriveIden)]
+enum TaskTombstone {
+    #[sea_orm(iden = "task_tombstones")]
+    Table,
+    TaskId,
+    UserId,
+    ChangeSeq,
+    DeletedAt,
+    Version,
+}
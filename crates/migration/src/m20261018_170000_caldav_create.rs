@@ -0,0 +1,137 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Passwords for the clients authenticating with HTTP Basic, such as CalDAV clients
+        manager
+            .create_table(
+                Table::create()
+                    .table(AppPassword::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AppPassword::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AppPassword::UserId).integer().not_null())
+                    .col(ColumnDef::new(AppPassword::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(AppPassword::PasswordHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AppPassword::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(AppPassword::LastUsedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_app_password_user")
+                            .from(AppPassword::Table, AppPassword::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_app_passwords_user_id")
+                    .table(AppPassword::Table)
+                    .col(AppPassword::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Names and UIDs chosen by CalDAV clients for the tasks they created. The rows outlive
+        // their task, so deletions are reported under the name the client knows.
+        manager
+            .create_table(
+                Table::create()
+                    .table(CaldavObject::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CaldavObject::TaskId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CaldavObject::UserId).integer().not_null())
+                    .col(ColumnDef::new(CaldavObject::Name).string().not_null())
+                    .col(ColumnDef::new(CaldavObject::Uid).string().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_caldav_object_user")
+                            .from(CaldavObject::Table, CaldavObject::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_caldav_objects_user_id_name")
+                    .table(CaldavObject::Table)
+                    .col(CaldavObject::UserId)
+                    .col(CaldavObject::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CaldavObject::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AppPassword::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum AppPassword {
+    #[sea_orm(iden = "app_passwords")]
+    Table,
+    Id,
+    UserId,
+    Name,
+    PasswordHash,
+    CreatedAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum CaldavObject {
+    #[sea_orm(iden = "caldav_objects")]
+    Table,
+    TaskId,
+    UserId,
+    Name,
+    Uid,
+}
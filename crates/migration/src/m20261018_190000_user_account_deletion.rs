@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    // Auth tokens carry the generation they were issued in, every revocation starts a new one
+                    .add_column_if_not_exists(
+                        ColumnDef::new(User::TokenGeneration)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    // The account is deleted for good at this instant, unless restored before
+                    .add_column_if_not_exists(
+                        ColumnDef::new(User::DeletionScheduledAt).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_deletion_scheduled_at")
+                    .table(User::Table)
+                    .col(User::DeletionScheduledAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_users_deletion_scheduled_at")
+                    .table(User::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::DeletionScheduledAt)
+                    .drop_column(User::TokenGeneration)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "users")]
+    Table,
+    #[sea_orm(iden = "token_generation")]
+    TokenGeneration,
+    #[sea_orm(iden = "deletion_scheduled_at")]
+    DeletionScheduledAt,
+}
@@ -0,0 +1,13 @@
+use database::entities::app_password::Model as AppPasswordEntity;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A newly created app password, the only time the password itself is returned.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct NewAppPassword {
+    /// The stored app password.
+    #[serde(flatten)]
+    pub app_password: AppPasswordEntity,
+    /// The password to enter in the client, in place of the account password.
+    pub password: String,
+}
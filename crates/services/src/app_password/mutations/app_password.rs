@@ -0,0 +1,101 @@
+use crate::app_password::models::app_password::NewAppPassword;
+use crate::error::ApiError;
+use bcrypt::DEFAULT_COST;
+use chrono::Utc;
+use database::entities::app_password::{ActiveModel, Column, Entity, Model};
+use sea_orm::*;
+use uuid::Uuid;
+
+/// Struct for handling mutations related to app passwords.
+pub struct AppPasswordMutation;
+
+impl AppPasswordMutation {
+    /// Asynchronously creates an app password with a random password.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the app password, such as the client using it.
+    /// * `user_id` - The ID of the user owning the app password.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the app password along with the generated password on success,
+    /// or an `ApiError` on failure.
+    pub async fn create(
+        name: String,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<NewAppPassword, ApiError> {
+        let name = name.trim().to_string();
+
+        if !(1..=100).contains(&name.chars().count()) {
+            return Err(ApiError::Validation(
+                "The name must be between 1 to 100 characters long.".to_string(),
+            ));
+        }
+
+        // 122 random bits, only the hash is stored
+        let password = Uuid::new_v4().simple().to_string();
+        let password_hash = bcrypt::hash(&password, DEFAULT_COST).map_err(|_| {
+            ApiError::Internal("An error occurred when hashing the password.".to_string())
+        })?;
+
+        let app_password = ActiveModel {
+            user_id: Set(user_id),
+            name: Set(name),
+            password_hash: Set(password_hash),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(NewAppPassword {
+            app_password,
+            password,
+        })
+    }
+
+    /// Asynchronously revokes an app password.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the app password.
+    /// * `user_id` - The ID of the user owning the app password.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion result on success, or an `ApiError::NotFound` if the user has no such app password.
+    pub async fn delete(id: i32, user_id: i32, db: &DbConn) -> Result<DeleteResult, ApiError> {
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound(String::from("App password not found.")));
+        }
+
+        Ok(result)
+    }
+
+    /// Asynchronously records that an app password was used to authenticate.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_password` - The app password.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated app password on success, or an `ApiError` on failure.
+    pub async fn touch(app_password: Model, db: &DbConn) -> Result<Model, ApiError> {
+        let mut app_password: ActiveModel = app_password.into();
+        app_password.last_used_at = Set(Some(Utc::now()));
+
+        Ok(app_password.update(db).await?)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::error::ApiError;
+use database::entities::app_password::{Column, Entity, Model};
+use sea_orm::*;
+
+/// Struct for handling queries related to app passwords.
+pub struct AppPasswordQueries;
+
+impl AppPasswordQueries {
+    /// Asynchronously fetches the app passwords of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the app passwords.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the app passwords ordered by ID on success, or an `ApiError` on failure.
+    pub async fn get_app_passwords(user_id: i32, db: &DbConn) -> Result<Vec<Model>, ApiError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::app_password::mutations::app_password::AppPasswordMutation;
+use crate::error::ApiError;
+use crate::user::queries::user::UserQueries;
+use base64::prelude::*;
+use database::entities::{app_password, user};
+use sea_orm::*;
+
+/// Credentials sent with HTTP Basic authentication, as by CalDAV clients.
+pub struct BasicAuth {
+    /// The username.
+    pub username: String,
+    /// The account password or an app password of the user.
+    pub password: String,
+}
+
+impl BasicAuth {
+    /// Parses the value of an `Authorization: Basic <base64(username:password)>` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header value.
+    ///
+    /// # Returns
+    ///
+    /// The credentials, or `None` if the header is not valid Basic authentication.
+    pub fn parse(header: &str) -> Option<Self> {
+        let (scheme, credentials) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("Basic") {
+            return None;
+        }
+
+        let credentials = BASE64_STANDARD.decode(credentials.trim()).ok()?;
+        let credentials = String::from_utf8(credentials).ok()?;
+        let (username, password) = credentials.split_once(':')?;
+
+        Some(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    /// Asynchronously checks the credentials against the account password, then the app passwords of the user.
+    ///
+    /// Accounts scheduled for deletion or deactivated are rejected like invalid credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the authenticated user on success, or an `ApiError::Unauthorized` if the credentials are invalid.
+    pub async fn authenticate(&self, db: &DbConn) -> Result<user::Model, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid credentials.".to_string());
+
+        let user = UserQueries::find_by_username(&self.username)
+            .one(db)
+            .await?
+            // Accounts scheduled for deletion are locked until restored, deactivated ones until activated
+            .filter(|user| user.deletion_scheduled_at.is_none() && user.deactivated_at.is_none())
+            .ok_or_else(invalid)?;
+
+        if bcrypt::verify(&self.password, &user.password).unwrap_or(false) {
+            return Ok(user);
+        }
+
+        let app_passwords = app_password::Entity::find()
+            .filter(app_password::Column::UserId.eq(user.id))
+            .all(db)
+            .await?;
+
+        let app_password = app_passwords
+            .into_iter()
+            .find(|app_password| {
+                bcrypt::verify(&self.password, &app_password.password_hash).unwrap_or(false)
+            })
+            .ok_or_else(invalid)?;
+
+        AppPasswordMutation::touch(app_password, db).await?;
+
+        Ok(user)
+    }
+}
@@ -1,7 +1,11 @@
+use crate::error::{ApiError, GuardError};
+use crate::user::queries::user::UserQueries;
+use database::Db;
 use jsonwebtoken::errors::{Error, ErrorKind};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use rocket::request::{FromRequest, Outcome};
 use rocket::Request;
+use sea_orm_rocket::Database;
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::time::{Duration, SystemTime};
@@ -13,12 +17,18 @@ pub const JWT_ALGORITHM: Algorithm = Algorithm::HS512;
 pub const TOKEN_DURATION_SECS: u64 = 3600;
 
 /// Struct representing the claims contained within the JWT token.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Claims {
     /// Subject identifier within the JWT claims.
     pub sub: i32,
     /// Expiration time of the JWT token in UNIX timestamp (seconds since epoch).
     pub exp: usize,
+    /// Issue time of the JWT token in UNIX timestamp (seconds since epoch).
+    #[serde(default)]
+    pub iat: usize,
+    /// Token generation of the user when the token was issued, checked against the revocations.
+    #[serde(default)]
+    pub gen: i32,
 }
 
 /// Struct representing a decoded JWT token.
@@ -34,6 +44,7 @@ impl JWT {
     /// # Arguments
     ///
     /// * `id` - The user ID to include in the JWT claims.
+    /// * `generation` - The current token generation of the user.
     ///
     /// # Returns
     ///
@@ -45,7 +56,7 @@ impl JWT {
     /// - Loading the `.env` file fails.
     /// - Retrieving the JWT secret from environment variables fails.
     /// - Calculating the token expiration time fails.
-    pub fn encode(id: &i32) -> Result<String, Error> {
+    pub fn encode(id: &i32, generation: i32) -> Result<String, Error> {
         // Load environment variables from `.env` file.
         if dotenvy::dotenv().is_err() {
             println!("Error loading .env file!");
@@ -55,18 +66,23 @@ impl JWT {
         let secret = env::var("JWT_SECRET")
             .expect("Error creating auth token: JWT_SECRET environment variable is missing");
 
-        // Calculate token expiration time.
-        let exp = SystemTime::now()
+        // Calculate token issue and expiration times.
+        let iat = SystemTime::now();
+        let exp = iat
             .checked_add(Duration::from_secs(TOKEN_DURATION_SECS))
             .expect("Failed to calculate token expiration time");
+        let timestamp = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as usize
+        };
 
         // Construct JWT claims.
         let claims = Claims {
             sub: *id,
-            exp: exp
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs() as usize,
+            exp: timestamp(exp),
+            iat: timestamp(iat),
+            gen: generation,
         };
 
         // Construct JWT header with specified algorithm.
@@ -127,6 +143,9 @@ impl<'r> FromRequest<'r> for JWT {
 
     /// Performs authentication and authorization checks for incoming requests.
     ///
+    /// Besides the signature and the expiration, the token is checked against its user,
+    /// which must still exist, must not be scheduled for deletion and must not have revoked it.
+    ///
     /// # Arguments
     ///
     /// * `request` - The incoming Rocket request.
@@ -136,30 +155,39 @@ impl<'r> FromRequest<'r> for JWT {
     /// An `Outcome` containing either a validated JWT instance or an error message.
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         // Check if Authorization header is present in the request.
-        match request.headers().get_one("Authorization") {
-            None => Outcome::Error((
-                rocket::http::Status::Unauthorized,
-                "No auth token provided".to_string(),
-            )),
+        let result = match request.headers().get_one("Authorization") {
+            None => Err(ApiError::Unauthorized("No auth token provided".to_string())),
             Some(v) => match JWT::decode(v.to_string()) {
-                Ok(token) => Outcome::Success(JWT {
+                Ok(token) => Ok(JWT {
                     claims: token.claims,
                 }),
-                Err(e) => match &e {
-                    ErrorKind::ExpiredSignature => Outcome::Error((
-                        rocket::http::Status::Unauthorized,
-                        "Token has expired".to_string(),
-                    )),
-                    ErrorKind::InvalidToken => Outcome::Error((
-                        rocket::http::Status::Unauthorized,
-                        "Invalid user auth token.".to_string(),
-                    )),
-                    _ => Outcome::Error((
-                        rocket::http::Status::Unauthorized,
-                        "Ah error occurred when received the auth token.".to_string(),
-                    )),
-                },
+                Err(e) => Err(ApiError::Unauthorized(match &e {
+                    ErrorKind::ExpiredSignature => "Token has expired".to_string(),
+                    ErrorKind::InvalidToken => "Invalid user auth token.".to_string(),
+                    _ => "Ah error occurred when received the auth token.".to_string(),
+                })),
             },
+        };
+
+        // Check the token against its user
+        let result = match (result, Db::fetch(request.rocket())) {
+            (Ok(jwt), Some(db)) => UserQueries::get_token_user(&jwt.claims, &db.conn)
+                .await
+                .map(|_| jwt),
+            (Ok(_), None) => Err(ApiError::Internal(
+                "The database is unavailable.".to_string(),
+            )),
+            (Err(e), _) => Err(e),
+        };
+
+        match result {
+            Ok(jwt) => Outcome::Success(jwt),
+            Err(e) => {
+                // Keep the message around so the catcher can render it.
+                let message = e.to_string();
+                GuardError::cache(request, message.clone(), Vec::new());
+                Outcome::Error((e.status(), message))
+            }
         }
     }
 }
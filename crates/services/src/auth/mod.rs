@@ -1 +1,2 @@
+pub mod basic;
 pub mod jwt;
@@ -0,0 +1,351 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use database::entities::task::Model as TaskEntity;
+
+/// Identifier of the product generating the iCalendar objects.
+const PRODID: &str = "-//todo-api//CalDAV//EN";
+
+/// Longest content line of an iCalendar object in octets, longer lines are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// The fields of a task read from an iCalendar `VTODO`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VTodo {
+    /// The `UID` of the to-do.
+    pub uid: String,
+    /// The `SUMMARY`, mapped to the task name.
+    pub summary: Option<String>,
+    /// The `DESCRIPTION`, mapped to the task description.
+    pub description: Option<String>,
+    /// Whether the to-do is done, read from `STATUS`, `COMPLETED` or `PERCENT-COMPLETE`.
+    /// Mapped to the `is_active` flag, which marks the task as done.
+    pub completed: bool,
+    /// The date of `DUE`, mapped to the task due date.
+    pub due: Option<NaiveDate>,
+}
+
+/// Escapes a value of the `TEXT` type.
+pub(crate) fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Unescapes a value of the `TEXT` type.
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+/// Appends a content line, folded every 75 octets without splitting a character.
+pub(crate) fn push_line(ical: &mut String, line: &str) {
+    let mut octets = 0;
+
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            ical.push_str("\r\n ");
+            // The leading space of the continuation counts towards its length
+            octets = 1;
+        }
+        ical.push(c);
+        octets += c.len_utf8();
+    }
+
+    ical.push_str("\r\n");
+}
+
+/// Formats a time as a UTC `DATE-TIME`, such as `20261018T120000Z`.
+pub(crate) fn date_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Appends the `VTODO` component of a task.
+///
+/// # Arguments
+///
+/// * `ical` - The iCalendar object being written.
+/// * `task` - The task.
+/// * `uid` - The `UID` of the task.
+pub(crate) fn push_vtodo(ical: &mut String, task: &TaskEntity, uid: &str) {
+    let status = match task.is_active {
+        true => "COMPLETED",
+        false => "NEEDS-ACTION",
+    };
+
+    push_line(ical, "BEGIN:VTODO");
+    push_line(ical, &format!("UID:{}", escape_text(uid)));
+    push_line(ical, &format!("DTSTAMP:{}", date_time(task.updated_at)));
+    push_line(ical, &format!("CREATED:{}", date_time(task.created_at)));
+    push_line(
+        ical,
+        &format!("LAST-MODIFIED:{}", date_time(task.updated_at)),
+    );
+    push_line(ical, &format!("SEQUENCE:{}", task.version - 1));
+    push_line(ical, &format!("SUMMARY:{}", escape_text(&task.name)));
+    push_line(
+        ical,
+        &format!("DESCRIPTION:{}", escape_text(&task.description)),
+    );
+    push_line(ical, &format!("STATUS:{}", status));
+    if task.is_active {
+        push_line(ical, &format!("COMPLETED:{}", date_time(task.updated_at)));
+        push_line(ical, "PERCENT-COMPLETE:100");
+    }
+    if let Some(due) = task.due_date {
+        push_line(ical, &format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")));
+    }
+    push_line(ical, "END:VTODO");
+}
+
+/// Serializes a task as an iCalendar object holding one `VTODO`.
+///
+/// # Arguments
+///
+/// * `task` - The task.
+/// * `uid` - The `UID` of the task.
+///
+/// # Returns
+///
+/// The iCalendar object, with CRLF line endings.
+pub fn to_ical(task: &TaskEntity, uid: &str) -> String {
+    let mut ical = String::new();
+
+    push_line(&mut ical, "BEGIN:VCALENDAR");
+    push_line(&mut ical, "VERSION:2.0");
+    push_line(&mut ical, &format!("PRODID:{}", PRODID));
+    push_vtodo(&mut ical, task, uid);
+    push_line(&mut ical, "END:VCALENDAR");
+
+    ical
+}
+
+/// Splits a content line into its upper cased property name and its value, dropping the parameters.
+fn parse_line(line: &str) -> Option<(String, &str)> {
+    // The value starts at the first colon outside of a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+
+    let name = line[..colon].split(';').next()?.trim().to_ascii_uppercase();
+
+    Some((name, &line[colon + 1..]))
+}
+
+/// Parses the date of a `DATE` or `DATE-TIME` value, such as `20261018` or `20261018T120000Z`.
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim().get(..8)?, "%Y%m%d").ok()
+}
+
+/// Parses the `VTODO` of an iCalendar object sent by a client.
+///
+/// Properties that have no task field, and nested components such as `VALARM`, are ignored.
+///
+/// # Arguments
+///
+/// * `ical` - The iCalendar object.
+///
+/// # Returns
+///
+/// A `Result` with the `VTodo`, or a message telling why the object is not a valid to-do.
+pub fn parse_vtodo(ical: &str) -> Result<VTodo, String> {
+    // Unfold the content lines, a line starting with a space or tab continues the previous one
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical.split('\n').map(|line| line.trim_end_matches('\r')) {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut components: Vec<String> = Vec::new();
+    let mut vtodo: Option<VTodo> = None;
+    let mut todos = 0;
+    let mut status = None;
+    let mut percent_complete = None;
+    let mut completed_at = false;
+
+    for line in &lines {
+        let (name, value) =
+            parse_line(line).ok_or_else(|| format!("Invalid content line `{line}`."))?;
+
+        match name.as_str() {
+            "BEGIN" => {
+                let component = value.trim().to_ascii_uppercase();
+
+                if components.len() == 1 {
+                    match component.as_str() {
+                        "VTODO" => {
+                            todos += 1;
+                            vtodo = Some(VTodo::default());
+                        }
+                        "VTIMEZONE" => {}
+                        other => return Err(format!("{other} components are not supported.")),
+                    }
+                }
+                components.push(component);
+            }
+            "END" => {
+                components.pop();
+            }
+            // Only the properties of the to-do itself are read
+            name if components.len() == 2 && components[1] == "VTODO" => {
+                let Some(vtodo) = vtodo.as_mut() else {
+                    continue;
+                };
+
+                match name {
+                    "UID" => vtodo.uid = unescape_text(value).trim().to_string(),
+                    "SUMMARY" => vtodo.summary = Some(unescape_text(value)),
+                    "DESCRIPTION" => vtodo.description = Some(unescape_text(value)),
+                    "STATUS" => status = Some(value.trim().to_ascii_uppercase()),
+                    "PERCENT-COMPLETE" => percent_complete = value.trim().parse::<u8>().ok(),
+                    "COMPLETED" => completed_at = true,
+                    "DUE" => {
+                        vtodo.due = Some(
+                            parse_date(value).ok_or_else(|| format!("Invalid DUE `{value}`."))?,
+                        )
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut vtodo = match (todos, vtodo) {
+        (1, Some(vtodo)) => vtodo,
+        (0, _) => return Err("The calendar object must contain a VTODO.".to_string()),
+        _ => return Err("The calendar object must contain a single VTODO.".to_string()),
+    };
+
+    if vtodo.uid.is_empty() {
+        return Err("The VTODO must have a UID.".to_string());
+    }
+
+    // An explicit status wins over the completion time and percentage
+    vtodo.completed = match status.as_deref() {
+        Some("COMPLETED" | "CANCELLED") => true,
+        Some(_) => false,
+        None => completed_at || percent_complete == Some(100),
+    };
+
+    Ok(vtodo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn task() -> TaskEntity {
+        TaskEntity {
+            id: 3,
+            name: "Groceries".to_string(),
+            description: "Milk, eggs; bread\nand \\ butter".to_string(),
+            is_active: false,
+            user_id: 1,
+            due_date: NaiveDate::from_ymd_opt(2026, 12, 1),
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 2,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 10, 18, 12, 30, 0).unwrap(),
+            change_seq: 5,
+            created_seq: 4,
+        }
+    }
+
+    #[test]
+    fn serialized_tasks_parse_back() {
+        let task = task();
+        let ical = to_ical(&task, "todo-api-task-3");
+
+        assert!(ical.contains("DESCRIPTION:Milk\\, eggs\\; bread\\nand \\\\ butter\r\n"));
+        assert!(ical.contains("STATUS:NEEDS-ACTION\r\n"));
+        assert!(ical.contains("DUE;VALUE=DATE:20261201\r\n"));
+        assert_eq!(
+            parse_vtodo(&ical),
+            Ok(VTodo {
+                uid: "todo-api-task-3".to_string(),
+                summary: Some(task.name),
+                description: Some(task.description),
+                completed: false,
+                due: task.due_date,
+            })
+        );
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let mut task = task();
+        task.description = "é".repeat(100);
+        let ical = to_ical(&task, "uid");
+
+        assert!(ical.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert_eq!(
+            parse_vtodo(&ical).unwrap().description,
+            Some(task.description)
+        );
+    }
+
+    #[test]
+    fn client_todos_are_parsed() {
+        let ical = "BEGIN:VCALENDAR\nPRODID:-//Mozilla.org/NONSGML Mozilla Calendar V1.1//EN\n\
+            VERSION:2.0\nBEGIN:VTODO\nUID:6f1c5e0a-1\nSUMMARY:Call mum\nDUE;TZID=Europe/Paris:\n \
+            20261020T090000\nPERCENT-COMPLETE:100\nBEGIN:VALARM\nACTION:DISPLAY\n\
+            DESCRIPTION:Reminder\nEND:VALARM\nEND:VTODO\nEND:VCALENDAR\n";
+
+        assert_eq!(
+            parse_vtodo(ical),
+            Ok(VTodo {
+                uid: "6f1c5e0a-1".to_string(),
+                summary: Some("Call mum".to_string()),
+                description: None,
+                completed: true,
+                due: NaiveDate::from_ymd_opt(2026, 10, 20),
+            })
+        );
+    }
+
+    #[test]
+    fn other_components_are_rejected() {
+        let event = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let no_uid =
+            "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nSUMMARY:x\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+
+        assert!(parse_vtodo(event).is_err());
+        assert!(parse_vtodo(no_uid).is_err());
+        assert!(parse_vtodo("").is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use database::entities::task::Model as TaskEntity;
+
+/// A task exposed as a calendar object resource of the CalDAV task list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarObject {
+    /// The name of the resource in the collection, such as `task-3.ics`.
+    pub name: String,
+    /// The iCalendar `UID` of the task.
+    pub uid: String,
+    /// The task.
+    pub task: TaskEntity,
+}
+
+/// Returns the resource name of a task not created through CalDAV.
+pub fn default_name(task_id: i32) -> String {
+    format!("task-{}.ics", task_id)
+}
+
+/// Returns the iCalendar `UID` of a task not created through CalDAV.
+pub fn default_uid(task_id: i32) -> String {
+    format!("todo-api-task-{}", task_id)
+}
+
+/// Returns the ID of the task named by a default resource name, see `default_name`.
+pub fn parse_default_name(name: &str) -> Option<i32> {
+    name.strip_prefix("task-")?
+        .strip_suffix(".ics")?
+        .parse()
+        .ok()
+        .filter(|id| *id > 0)
+}
+
+/// Changes of the CalDAV task list since a position of the change feed.
+pub struct CalendarChanges {
+    /// The objects created or changed since then.
+    pub changed: Vec<CalendarObject>,
+    /// The names of the objects deleted since then.
+    pub removed: Vec<String>,
+    /// The position of the last change, to sync from next time.
+    pub position: i64,
+}
@@ -0,0 +1,111 @@
+use crate::caldav::models::object::CalendarObject;
+use crate::caldav::queries::caldav::CalDavQueries;
+use crate::error::ApiError;
+use crate::etag::EntityTags;
+use crate::task::mutations::task::{TaskMutation, TaskPayload};
+use database::entities::caldav_object::{ActiveModel, Column, Entity};
+use sea_orm::*;
+
+/// Struct for handling mutations of the CalDAV task list.
+pub struct CalDavMutation;
+
+impl CalDavMutation {
+    /// Asynchronously stores a calendar object, updating its task or creating a new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the resource.
+    /// * `uid` - The `UID` of the to-do, kept for the tasks created by the client.
+    /// * `task_payload` - The fields of the task, with the `If-Match` precondition of the request.
+    /// * `if_none_match` - Whether the request has `If-None-Match: *`, only allowing a new object.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the stored object and whether it was created on success,
+    /// or an `ApiError` on failure.
+    pub async fn put(
+        name: &str,
+        uid: String,
+        task_payload: TaskPayload,
+        if_none_match: bool,
+        db: &DbConn,
+    ) -> Result<(CalendarObject, bool), ApiError> {
+        let user_id = task_payload.user_id;
+
+        match CalDavQueries::get_object(name, user_id, db).await? {
+            Some(_) if if_none_match => Err(ApiError::PreconditionFailed(
+                "The calendar object already exists.".to_string(),
+            )),
+            Some(object) => {
+                let task = TaskMutation::update(task_payload, object.task.id, db).await?;
+
+                Ok((CalendarObject { task, ..object }, false))
+            }
+            None if task_payload.if_match.is_some() => Err(ApiError::PreconditionFailed(
+                "The calendar object does not exist.".to_string(),
+            )),
+            None => {
+                let task = TaskMutation::create(task_payload, db).await?;
+
+                // Keep the name and UID chosen by the client, replacing those of a deleted task
+                let txn = db.begin().await?;
+                Entity::delete_many()
+                    .filter(Column::UserId.eq(user_id))
+                    .filter(Column::Name.eq(name))
+                    .exec(&txn)
+                    .await?;
+                ActiveModel {
+                    task_id: Set(task.id),
+                    user_id: Set(user_id),
+                    name: Set(name.to_string()),
+                    uid: Set(uid.clone()),
+                }
+                .insert(&txn)
+                .await?;
+                txn.commit().await?;
+
+                Ok((
+                    CalendarObject {
+                        name: name.to_string(),
+                        uid,
+                        task,
+                    },
+                    true,
+                ))
+            }
+        }
+    }
+
+    /// Asynchronously deletes a calendar object and its task.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the resource.
+    /// * `user_id` - The ID of the user owning the task.
+    /// * `request_id` - The ID of the request deleting the task, recorded in the task history.
+    /// * `if_match` - The `If-Match` precondition of the request, if any.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `()` on success, or an `ApiError::NotFound` if there is no such object.
+    pub async fn delete(
+        name: &str,
+        user_id: i32,
+        request_id: Option<String>,
+        if_match: Option<EntityTags>,
+        db: &DbConn,
+    ) -> Result<(), ApiError> {
+        let object =
+            CalDavQueries::get_object(name, user_id, db)
+                .await?
+                .ok_or(ApiError::NotFound(String::from(
+                    "Calendar object not found.",
+                )))?;
+
+        TaskMutation::delete(object.task.id, user_id, request_id, if_match, db).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::caldav::models::object::{
+    default_name, default_uid, parse_default_name, CalendarChanges, CalendarObject,
+};
+use crate::error::ApiError;
+use crate::sync::queries::sync::SyncQueries;
+use crate::task::models::change::TaskChangeKind;
+use database::entities::{caldav_object, task};
+use sea_orm::*;
+use std::collections::HashMap;
+
+/// Number of changes fetched at once when reading the changes since a sync token.
+const FEED_PAGE: u64 = 500;
+
+/// Struct for handling queries related to the CalDAV task list.
+pub struct CalDavQueries;
+
+/// Exposes a task as a calendar object, under the name and UID given by its CalDAV client, if any.
+fn object(task: task::Model, names: &HashMap<i32, caldav_object::Model>) -> CalendarObject {
+    match names.get(&task.id) {
+        Some(named) => CalendarObject {
+            name: named.name.clone(),
+            uid: named.uid.clone(),
+            task,
+        },
+        None => CalendarObject {
+            name: default_name(task.id),
+            uid: default_uid(task.id),
+            task,
+        },
+    }
+}
+
+impl CalDavQueries {
+    /// Asynchronously fetches the names and UIDs given by CalDAV clients to the tasks of a user.
+    async fn get_names<C: ConnectionTrait>(
+        user_id: i32,
+        db: &C,
+    ) -> Result<HashMap<i32, caldav_object::Model>, ApiError> {
+        Ok(caldav_object::Entity::find()
+            .filter(caldav_object::Column::UserId.eq(user_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|named| (named.task_id, named))
+            .collect())
+    }
+
+    /// Asynchronously fetches every task of a user as a calendar object.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the calendar objects ordered by task ID on success, or an `ApiError` on failure.
+    pub async fn get_objects(user_id: i32, db: &DbConn) -> Result<Vec<CalendarObject>, ApiError> {
+        let names = Self::get_names(user_id, db).await?;
+
+        Ok(task::Entity::find()
+            .filter(task::Column::UserId.eq(user_id))
+            .order_by_asc(task::Column::Id)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|task| object(task, &names))
+            .collect())
+    }
+
+    /// Asynchronously fetches a calendar object of a user by its resource name.
+    ///
+    /// Objects created through CalDAV keep the name given by the client, the other tasks
+    /// are named after their ID, such as `task-3.ics`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the resource.
+    /// * `user_id` - The ID of the user owning the task.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the calendar object (`None` if there is no such object) on success,
+    /// or an `ApiError` on failure.
+    pub async fn get_object(
+        name: &str,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Option<CalendarObject>, ApiError> {
+        let named = caldav_object::Entity::find()
+            .filter(caldav_object::Column::UserId.eq(user_id))
+            .filter(caldav_object::Column::Name.eq(name))
+            .one(db)
+            .await?;
+
+        // A name given by a client wins, a task with a client name has no default name
+        let task_id = match named {
+            Some(ref named) => named.task_id,
+            None => match parse_default_name(name) {
+                Some(id)
+                    if caldav_object::Entity::find_by_id(id)
+                        .one(db)
+                        .await?
+                        .is_none() =>
+                {
+                    id
+                }
+                _ => return Ok(None),
+            },
+        };
+
+        let task = task::Entity::find_by_id(task_id)
+            .filter(task::Column::UserId.eq(user_id))
+            .one(db)
+            .await?;
+
+        Ok(task.map(|task| match named {
+            Some(named) => CalendarObject {
+                name: named.name,
+                uid: named.uid,
+                task,
+            },
+            None => CalendarObject {
+                name: default_name(task.id),
+                uid: default_uid(task.id),
+                task,
+            },
+        }))
+    }
+
+    /// Asynchronously fetches the calendar objects created, changed and deleted after a position of the change feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The position of the last change the client has seen, `0` for every object.
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `CalendarChanges` on success, or an `ApiError` on failure.
+    pub async fn get_changes(
+        since: i64,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<CalendarChanges, ApiError> {
+        let names = Self::get_names(user_id, db).await?;
+        let mut changes = CalendarChanges {
+            changed: Vec::new(),
+            removed: Vec::new(),
+            position: since,
+        };
+
+        // Read the whole feed since then, a full sync does not report the deleted tasks
+        loop {
+            let (page, has_more) =
+                SyncQueries::get_feed(changes.position, true, FEED_PAGE, user_id, db).await?;
+
+            for change in page {
+                changes.position = change.seq;
+
+                match (change.kind, change.task) {
+                    (TaskChangeKind::Deleted, _) | (_, None) if since == 0 => {}
+                    (TaskChangeKind::Deleted, _) | (_, None) => changes.removed.push(
+                        names
+                            .get(&change.task_id)
+                            .map_or_else(|| default_name(change.task_id), |n| n.name.clone()),
+                    ),
+                    (_, Some(task)) => changes.changed.push(object(task, &names)),
+                }
+            }
+
+            if !has_more {
+                break;
+            }
+        }
+
+        Ok(changes)
+    }
+}
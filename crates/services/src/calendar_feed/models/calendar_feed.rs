@@ -0,0 +1,27 @@
+use database::entities::calendar_feed::Model as CalendarFeedEntity;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+/// A newly created calendar feed, the only time its token is returned.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct NewCalendarFeed {
+    /// The stored calendar feed.
+    #[serde(flatten)]
+    pub calendar_feed: CalendarFeedEntity,
+    /// The secret token identifying the feed in its URL.
+    pub token: String,
+}
+
+/// Hashes the token of a calendar feed, feeds are looked up by this hash.
+///
+/// # Arguments
+///
+/// * `token` - The token found in the feed URL.
+///
+/// # Returns
+///
+/// The hex encoded SHA-256 of the token.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,167 @@
+use crate::caldav::models::ical::{date_time, escape_text, push_line, push_vtodo};
+use crate::caldav::models::object::default_uid;
+use chrono::Duration;
+use database::entities::task::Model as TaskEntity;
+use rocket::FromFormField;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Identifier of the product generating the calendar feeds.
+const PRODID: &str = "-//todo-api//Calendar feed//EN";
+
+/// How often calendar apps are asked to refresh the feed.
+const REFRESH_INTERVAL: &str = "PT1H";
+
+/// The components a calendar feed lists for each task.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromFormField, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedComponents {
+    /// A `VTODO` for every task and a `VEVENT` for every task with a due date.
+    #[default]
+    #[field(value = "all")]
+    All,
+    /// A `VTODO` for every task, for apps showing to-dos.
+    #[field(value = "todo")]
+    Todo,
+    /// A `VEVENT` on the due date of every task having one, for apps ignoring to-dos.
+    #[field(value = "event")]
+    Event,
+}
+
+/// Appends the all-day `VEVENT` placed on the due date of a task.
+fn push_due_event(ical: &mut String, task: &TaskEntity, uid: &str) {
+    let Some(due) = task.due_date else {
+        return;
+    };
+
+    push_line(ical, "BEGIN:VEVENT");
+    push_line(ical, &format!("UID:{}", escape_text(uid)));
+    push_line(ical, &format!("DTSTAMP:{}", date_time(task.updated_at)));
+    push_line(ical, &format!("CREATED:{}", date_time(task.created_at)));
+    push_line(
+        ical,
+        &format!("LAST-MODIFIED:{}", date_time(task.updated_at)),
+    );
+    push_line(ical, &format!("SEQUENCE:{}", task.version - 1));
+    push_line(
+        ical,
+        &format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+    );
+    push_line(
+        ical,
+        &format!(
+            "DTEND;VALUE=DATE:{}",
+            (due + Duration::days(1)).format("%Y%m%d")
+        ),
+    );
+    push_line(ical, &format!("SUMMARY:{}", escape_text(&task.name)));
+    push_line(
+        ical,
+        &format!("DESCRIPTION:{}", escape_text(&task.description)),
+    );
+    // The event only marks a deadline, it does not make the user busy
+    push_line(ical, "TRANSP:TRANSPARENT");
+    push_line(ical, "END:VEVENT");
+}
+
+/// Serializes tasks as the iCalendar object served by a calendar feed.
+///
+/// The `VTODO` of a task uses the same `UID` as over CalDAV, its `VEVENT` gets a `-due` suffix
+/// since the `UID` of every component must be unique.
+///
+/// # Arguments
+///
+/// * `name` - The name of the feed, shown by the calendar apps.
+/// * `tasks` - The tasks listed by the feed.
+/// * `components` - The components listed for each task.
+///
+/// # Returns
+///
+/// The iCalendar object, with CRLF line endings.
+pub fn to_feed(name: &str, tasks: &[TaskEntity], components: FeedComponents) -> String {
+    let mut ical = String::new();
+
+    push_line(&mut ical, "BEGIN:VCALENDAR");
+    push_line(&mut ical, "VERSION:2.0");
+    push_line(&mut ical, &format!("PRODID:{}", PRODID));
+    push_line(&mut ical, "CALSCALE:GREGORIAN");
+    push_line(&mut ical, "METHOD:PUBLISH");
+    push_line(&mut ical, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    push_line(
+        &mut ical,
+        &format!("REFRESH-INTERVAL;VALUE=DURATION:{}", REFRESH_INTERVAL),
+    );
+    push_line(&mut ical, &format!("X-PUBLISHED-TTL:{}", REFRESH_INTERVAL));
+
+    for task in tasks {
+        let uid = default_uid(task.id);
+
+        if components != FeedComponents::Event {
+            push_vtodo(&mut ical, task, &uid);
+        }
+        if components != FeedComponents::Todo {
+            push_due_event(&mut ical, task, &format!("{uid}-due"));
+        }
+    }
+
+    push_line(&mut ical, "END:VCALENDAR");
+
+    ical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn task(id: i32, due_date: Option<NaiveDate>) -> TaskEntity {
+        TaskEntity {
+            id,
+            name: format!("Task {id}"),
+            description: String::new(),
+            is_active: false,
+            user_id: 1,
+            due_date,
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 1,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap(),
+            change_seq: id as i64,
+            created_seq: id as i64,
+        }
+    }
+
+    #[test]
+    fn events_are_listed_for_due_tasks_only() {
+        let tasks = [
+            task(1, NaiveDate::from_ymd_opt(2026, 12, 31)),
+            task(2, None),
+        ];
+        let ical = to_feed("Work, urgent", &tasks, FeedComponents::All);
+
+        assert!(ical.contains("X-WR-CALNAME:Work\\, urgent\r\n"));
+        assert_eq!(ical.matches("BEGIN:VTODO").count(), 2);
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ical.contains("UID:todo-api-task-1-due\r\n"));
+        assert!(ical.contains("DTSTART;VALUE=DATE:20261231\r\nDTEND;VALUE=DATE:20270101\r\n"));
+    }
+
+    #[test]
+    fn components_can_be_selected() {
+        let tasks = [
+            task(1, NaiveDate::from_ymd_opt(2026, 12, 31)),
+            task(2, None),
+        ];
+        let todos = to_feed("Tasks", &tasks, FeedComponents::Todo);
+        let events = to_feed("Tasks", &tasks, FeedComponents::Event);
+
+        assert_eq!(todos.matches("BEGIN:VTODO").count(), 2);
+        assert!(!todos.contains("BEGIN:VEVENT"));
+        assert_eq!(events.matches("BEGIN:VEVENT").count(), 1);
+        assert!(!events.contains("BEGIN:VTODO"));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::calendar_feed::models::calendar_feed::{hash_token, NewCalendarFeed};
+use crate::error::ApiError;
+use chrono::Utc;
+use database::entities::calendar_feed::{ActiveModel, Column, Entity, Model};
+use sea_orm::*;
+use uuid::Uuid;
+
+/// Struct for handling mutations related to calendar feeds.
+pub struct CalendarFeedMutation;
+
+impl CalendarFeedMutation {
+    /// Asynchronously creates a calendar feed with a random token.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the feed, shown by the calendar apps.
+    /// * `user_id` - The ID of the user owning the feed.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the calendar feed along with its token on success, or an `ApiError` on failure.
+    pub async fn create(
+        name: String,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<NewCalendarFeed, ApiError> {
+        let name = name.trim().to_string();
+
+        if !(1..=100).contains(&name.chars().count()) {
+            return Err(ApiError::Validation(
+                "The name must be between 1 to 100 characters long.".to_string(),
+            ));
+        }
+
+        // 122 random bits, only the hash is stored
+        let token = Uuid::new_v4().simple().to_string();
+
+        let calendar_feed = ActiveModel {
+            user_id: Set(user_id),
+            name: Set(name),
+            token_hash: Set(hash_token(&token)),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(NewCalendarFeed {
+            calendar_feed,
+            token,
+        })
+    }
+
+    /// Asynchronously revokes a calendar feed, its URL stops working.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the calendar feed.
+    /// * `user_id` - The ID of the user owning the calendar feed.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion result on success, or an `ApiError::NotFound` if the user has no such calendar feed.
+    pub async fn delete(id: i32, user_id: i32, db: &DbConn) -> Result<DeleteResult, ApiError> {
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound(String::from("Calendar feed not found.")));
+        }
+
+        Ok(result)
+    }
+
+    /// Asynchronously records that a calendar feed was fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `calendar_feed` - The calendar feed.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated calendar feed on success, or an `ApiError` on failure.
+    pub async fn touch(calendar_feed: Model, db: &DbConn) -> Result<Model, ApiError> {
+        let mut calendar_feed: ActiveModel = calendar_feed.into();
+        calendar_feed.last_used_at = Set(Some(Utc::now()));
+
+        Ok(calendar_feed.update(db).await?)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::calendar_feed::models::calendar_feed::hash_token;
+use crate::error::ApiError;
+use crate::task::queries::filter;
+use crate::task::queries::task::contains_condition;
+use database::entities::calendar_feed::{Column, Entity, Model};
+use database::entities::{task, user};
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::*;
+
+/// Struct for handling queries related to calendar feeds.
+pub struct CalendarFeedQueries;
+
+/// Payload selecting the tasks listed by a calendar feed, every condition must match.
+pub struct FeedPayload {
+    /// Texts the name or the description of the tasks must contain, as the `query` of `GET /task`.
+    pub queries: Vec<String>,
+    /// Filter queries, as the `q` of `GET /task`.
+    pub filters: Vec<String>,
+    /// The ID of the user owning the tasks.
+    pub user_id: i32,
+}
+
+impl CalendarFeedQueries {
+    /// Asynchronously fetches the calendar feeds of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the calendar feeds.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the calendar feeds ordered by ID on success, or an `ApiError` on failure.
+    pub async fn get_calendar_feeds(user_id: i32, db: &DbConn) -> Result<Vec<Model>, ApiError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Asynchronously fetches the calendar feed identified by a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token found in the feed URL.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the calendar feed on success, or an `ApiError::NotFound` if the token is unknown or revoked,
+    /// or its user is deactivated or scheduled for deletion.
+    pub async fn get_calendar_feed_by_token(token: &str, db: &DbConn) -> Result<Model, ApiError> {
+        Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token)))
+            .filter(
+                Column::UserId.in_subquery(
+                    Query::select()
+                        .column(user::Column::Id)
+                        .from(user::Entity)
+                        .and_where(user::Column::DeactivatedAt.is_null())
+                        .and_where(user::Column::DeletionScheduledAt.is_null())
+                        .to_owned(),
+                ),
+            )
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("Calendar feed not found.")))
+    }
+
+    /// Asynchronously fetches every task listed by a calendar feed, by due date then ID,
+    /// the tasks without a due date last.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - Payload selecting the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the tasks on success, or an `ApiError` if a filter query is invalid.
+    pub async fn get_feed_tasks(
+        payload: FeedPayload,
+        db: &DbConn,
+    ) -> Result<Vec<task::Model>, ApiError> {
+        let mut condition = Condition::all().add(task::Column::UserId.eq(payload.user_id));
+
+        for query in &payload.queries {
+            condition = condition.add(contains_condition(query));
+        }
+        for filter_query in &payload.filters {
+            condition = condition.add(filter::compile(filter_query)?);
+        }
+
+        Ok(task::Entity::find()
+            .filter(condition)
+            .order_by_asc(Expr::col(task::Column::DueDate).is_null())
+            .order_by_asc(task::Column::DueDate)
+            .order_by_asc(task::Column::Id)
+            .all(db)
+            .await?)
+    }
+}
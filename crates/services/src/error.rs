@@ -0,0 +1,154 @@
+use crate::response::ResponseRequest;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use rocket::Request;
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use utoipa::ToSchema;
+
+/// Error type returned by the services and the API routes.
+///
+/// Each variant maps to an HTTP status and a stable machine readable `code`,
+/// rendered inside the `ResponseRequest` envelope.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The request is malformed, such as a body that is not valid JSON.
+    BadRequest(String),
+    /// The requested resource does not exist or is not visible to the user.
+    NotFound(String),
+    /// The user is authenticated but not allowed to perform the action.
+    Forbidden(String),
+    /// The request conflicts with the current state of a resource.
+    Conflict(String),
+    /// The request payload is invalid.
+    Validation(String),
+    /// The `If-Match` precondition of the request does not match the current resource.
+    PreconditionFailed(String),
+    /// The user is not authenticated or the credentials are invalid.
+    Unauthorized(String),
+    /// An unexpected error, such as a database outage.
+    Internal(String),
+}
+
+impl ApiError {
+    /// Returns the HTTP status associated with the error.
+    pub fn status(&self) -> Status {
+        match self {
+            ApiError::BadRequest(_) => Status::BadRequest,
+            ApiError::NotFound(_) => Status::NotFound,
+            ApiError::Forbidden(_) => Status::Forbidden,
+            ApiError::Conflict(_) => Status::Conflict,
+            ApiError::Validation(_) => Status::UnprocessableEntity,
+            ApiError::PreconditionFailed(_) => Status::PreconditionFailed,
+            ApiError::Unauthorized(_) => Status::Unauthorized,
+            ApiError::Internal(_) => Status::InternalServerError,
+        }
+    }
+
+    /// Returns the stable machine readable code of the error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Validation(_) => "validation_error",
+            ApiError::PreconditionFailed(_) => "precondition_failed",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Internal(_) => "internal_error",
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::BadRequest(message)
+            | ApiError::NotFound(message)
+            | ApiError::Forbidden(message)
+            | ApiError::Conflict(message)
+            | ApiError::Validation(message)
+            | ApiError::PreconditionFailed(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Conversion from database errors, hiding the details of unexpected failures.
+impl From<DbErr> for ApiError {
+    fn from(e: DbErr) -> Self {
+        match e {
+            DbErr::RecordNotFound(message) => ApiError::NotFound(message),
+            e => {
+                println!("Database error: {e}");
+                ApiError::Internal("An internal error occurred.".to_string())
+            }
+        }
+    }
+}
+
+/// Implementation of Rocket's `Responder` trait, rendering the error inside the `ResponseRequest` envelope.
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let status = self.status();
+
+        Custom(
+            status,
+            Json(ResponseRequest::<Option<()>> {
+                message: Some(self.to_string()),
+                status,
+                code: Some(self.code().to_string()),
+                data: None,
+            }),
+        )
+        .respond_to(request)
+    }
+}
+
+/// Error of a single field of a request payload.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the invalid field, if it is known.
+    pub field: Option<String>,
+    /// Validation message of the field.
+    pub message: String,
+}
+
+/// Error raised by a request or data guard.
+///
+/// Rocket catchers cannot access the error of a failing guard, so guards cache it on the
+/// request with `Request::local_cache` and the catchers read it back to render the envelope.
+#[derive(Clone, Debug, Default)]
+pub struct GuardError {
+    /// Message describing the failure.
+    pub message: Option<String>,
+    /// Errors of the individual fields of the payload, if any.
+    pub fields: Vec<FieldError>,
+}
+
+impl GuardError {
+    /// Caches the error on the request, keeping the first error if a guard already failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request whose guard failed.
+    /// * `message` - Message describing the failure.
+    /// * `fields` - Errors of the individual fields of the payload.
+    pub fn cache(request: &Request<'_>, message: String, fields: Vec<FieldError>) {
+        request.local_cache(|| GuardError {
+            message: Some(message),
+            fields,
+        });
+    }
+
+    /// Returns the error cached on the request, or an empty error if no guard failed.
+    pub fn cached<'r>(request: &'r Request<'_>) -> &'r GuardError {
+        request.local_cache(GuardError::default)
+    }
+}
@@ -0,0 +1,223 @@
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::{self, Responder};
+use rocket::{Request, Response};
+use sha2::{Digest, Sha256};
+
+/// Builds the strong entity tag of a resource revision, such as `"v3"`.
+///
+/// # Arguments
+///
+/// * `version` - The revision number of the resource.
+///
+/// # Returns
+///
+/// The quoted entity tag, as sent in the `ETag` header.
+pub fn etag(version: i32) -> String {
+    format!("\"v{}\"", version)
+}
+
+/// Builds the strong entity tag of a generated document from a hash of its content,
+/// for resources without a revision number such as calendar feeds.
+///
+/// # Arguments
+///
+/// * `content` - The bytes sent in the response body.
+///
+/// # Returns
+///
+/// The quoted entity tag, as sent in the `ETag` header.
+pub fn content_etag(content: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(&Sha256::digest(content)[..16]))
+}
+
+/// Entity tags listed in an `If-Match` or `If-None-Match` header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntityTags {
+    /// The `*` wildcard, matching any current revision.
+    Any,
+    /// A list of entity tags, weak ones keeping their `W/` prefix.
+    Tags(Vec<String>),
+}
+
+impl EntityTags {
+    /// Parses the value of an `If-Match` or `If-None-Match` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header value, `*` or a comma separated list of entity tags.
+    ///
+    /// # Returns
+    ///
+    /// The parsed `EntityTags`.
+    pub fn parse(header: &str) -> Self {
+        match header.trim() {
+            "*" => EntityTags::Any,
+            tags => EntityTags::Tags(
+                tags.split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns whether the tags match `etag` using the strong comparison of `If-Match`,
+    /// where weak tags never match.
+    pub fn matches_strong(&self, etag: &str) -> bool {
+        match self {
+            EntityTags::Any => true,
+            EntityTags::Tags(tags) => tags.iter().any(|tag| tag == etag),
+        }
+    }
+
+    /// Returns whether the tags match `etag` using the weak comparison of `If-None-Match`,
+    /// ignoring the `W/` prefix.
+    pub fn matches_weak(&self, etag: &str) -> bool {
+        match self {
+            EntityTags::Any => true,
+            EntityTags::Tags(tags) => tags
+                .iter()
+                .any(|tag| tag.trim_start_matches("W/") == etag.trim_start_matches("W/")),
+        }
+    }
+}
+
+/// The `If-Match` header of the request, if any.
+pub struct IfMatch(pub Option<EntityTags>);
+
+/// The `If-None-Match` header of the request, if any.
+pub struct IfNoneMatch(pub Option<EntityTags>);
+
+/// Implementation of Rocket's `FromRequest` trait for `If-Match`, which never fails.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfMatch(
+            request.headers().get_one("If-Match").map(EntityTags::parse),
+        ))
+    }
+}
+
+/// Implementation of Rocket's `FromRequest` trait for `If-None-Match`, which never fails.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(
+            request
+                .headers()
+                .get_one("If-None-Match")
+                .map(EntityTags::parse),
+        ))
+    }
+}
+
+/// Response carrying the `ETag` of the returned resource.
+pub enum Tagged<R> {
+    /// The resource, with its `ETag` header.
+    Modified {
+        /// The entity tag of the resource.
+        etag: String,
+        /// The response rendering the resource.
+        response: R,
+    },
+    /// `304 Not Modified`, the client copy matching `If-None-Match` is still current.
+    NotModified {
+        /// The entity tag of the resource.
+        etag: String,
+    },
+}
+
+impl<R> Tagged<R> {
+    /// Builds the response for a `GET`, answering `304 Not Modified` when `if_none_match` matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `etag` - The entity tag of the resource.
+    /// * `if_none_match` - The `If-None-Match` header of the request.
+    /// * `response` - Builds the response rendering the resource, only called when it is sent.
+    ///
+    /// # Returns
+    ///
+    /// The `Tagged` response.
+    pub fn conditional(
+        etag: String,
+        if_none_match: IfNoneMatch,
+        response: impl FnOnce() -> R,
+    ) -> Self {
+        match if_none_match.0 {
+            Some(tags) if tags.matches_weak(&etag) => Tagged::NotModified { etag },
+            _ => Tagged::Modified {
+                etag,
+                response: response(),
+            },
+        }
+    }
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Tagged<R> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self {
+            Tagged::Modified { etag, response } => {
+                Response::build_from(response.respond_to(request)?)
+                    .header(Header::new("ETag", etag))
+                    .ok()
+            }
+            Tagged::NotModified { etag } => Response::build()
+                .status(Status::NotModified)
+                .header(Header::new("ETag", etag))
+                .ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wildcards_and_tag_lists() {
+        assert_eq!(EntityTags::parse(" * "), EntityTags::Any);
+        assert_eq!(
+            EntityTags::parse(r#""v1", W/"v2",,"v3""#),
+            EntityTags::Tags(vec![
+                r#""v1""#.to_string(),
+                r#"W/"v2""#.to_string(),
+                r#""v3""#.to_string(),
+            ])
+        );
+        assert_eq!(EntityTags::parse(""), EntityTags::Tags(Vec::new()));
+    }
+
+    #[test]
+    fn weak_tags_never_match_strongly() {
+        let tags = EntityTags::parse(r#"W/"v2", "v3""#);
+
+        assert!(tags.matches_strong(&etag(3)));
+        assert!(!tags.matches_strong(&etag(2)));
+        assert!(!tags.matches_strong(&etag(4)));
+        assert!(EntityTags::Any.matches_strong(&etag(4)));
+        assert!(!EntityTags::parse("").matches_strong(&etag(1)));
+    }
+
+    #[test]
+    fn weak_comparison_ignores_the_weak_prefix() {
+        let tags = EntityTags::parse(r#"W/"v2""#);
+
+        assert!(tags.matches_weak(&etag(2)));
+        assert!(tags.matches_weak(r#"W/"v2""#));
+        assert!(!tags.matches_weak(&etag(3)));
+    }
+
+    #[test]
+    fn content_tags_follow_the_content() {
+        assert_eq!(etag(3), r#""v3""#);
+        assert_eq!(content_etag(b"BEGIN"), content_etag(b"BEGIN"));
+        assert_ne!(content_etag(b"BEGIN"), content_etag(b"END"));
+        assert_eq!(content_etag(b"").len(), 34);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::error::ApiError;
+use crate::sync::queries::sync::SyncQueries;
+use crate::task::models::change::TaskChange;
+use crate::webhook::mutations::delivery::WebhookDeliveryMutation;
+use rocket::request::{FromRequest, Outcome};
+use rocket::tokio::sync::broadcast;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::tokio::time::{sleep, Duration};
+use rocket::Request;
+use sea_orm::{ConnectionTrait, DatabaseTransaction, DbBackend, DbConn, Statement};
+use sqlx::postgres::PgListener;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+/// Postgres channel the changes are sent on when `LISTEN/NOTIFY` is enabled.
+const CHANNEL: &str = "task_changes";
+
+/// Number of changes kept for subscribers that fall behind.
+const CAPACITY: usize = 1024;
+
+/// Number of changes fetched at once when replaying the missed changes.
+const REPLAY_PAGE: u64 = 500;
+
+/// In-process channel the committed task changes are published to.
+static SENDER: LazyLock<broadcast::Sender<TaskChange>> =
+    LazyLock::new(|| broadcast::channel(CAPACITY).0);
+
+/// Whether the changes go through Postgres `LISTEN/NOTIFY`, set once the listener runs.
+static NOTIFY: AtomicBool = AtomicBool::new(false);
+
+/// Hub of the task changes, feeding the event stream of every API instance.
+///
+/// By default the changes are only published in-process. Once `listen` runs, they are sent
+/// with `NOTIFY` on commit instead, and every instance relays them to its own subscribers.
+pub struct TaskEvents;
+
+impl TaskEvents {
+    /// Subscribes to the task changes committed from now on.
+    pub fn subscribe() -> broadcast::Receiver<TaskChange> {
+        SENDER.subscribe()
+    }
+
+    /// Asynchronously commits `txn` and publishes `change`.
+    ///
+    /// The webhook deliveries of the change are queued in `txn`. With `LISTEN/NOTIFY`, the
+    /// notification is part of the transaction too, so Postgres only delivers it if the
+    /// transaction commits.
+    ///
+    /// # Arguments
+    ///
+    /// * `txn` - The transaction writing the change.
+    /// * `change` - The change to publish.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError` if the commit fails.
+    pub async fn commit(txn: DatabaseTransaction, change: TaskChange) -> Result<(), ApiError> {
+        Self::commit_all(txn, vec![change]).await
+    }
+
+    /// Asynchronously commits `txn` and publishes `changes`, in order, like `commit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `txn` - The transaction writing the changes.
+    /// * `changes` - The changes to publish.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError` if the commit fails.
+    pub async fn commit_all(
+        txn: DatabaseTransaction,
+        changes: Vec<TaskChange>,
+    ) -> Result<(), ApiError> {
+        for change in &changes {
+            WebhookDeliveryMutation::enqueue(change, &txn).await?;
+        }
+
+        if !NOTIFY.load(Ordering::Relaxed) {
+            txn.commit().await?;
+
+            // Nobody listening is not an error
+            for change in changes {
+                let _ = SENDER.send(change);
+            }
+            return Ok(());
+        }
+
+        for change in &changes {
+            let payload =
+                serde_json::to_string(change).map_err(|e| ApiError::Internal(e.to_string()))?;
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT pg_notify($1, $2)",
+                [CHANNEL.into(), payload.into()],
+            ))
+            .await?;
+        }
+
+        Ok(txn.commit().await?)
+    }
+
+    /// Asynchronously starts relaying the changes notified by every API instance, switching
+    /// `commit` to `NOTIFY`.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - SeaORM database connection (`&DbConn`), its pool opens the listening connection.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` once listening, or an `ApiError` if the listener cannot connect.
+    pub async fn listen(db: &DbConn) -> Result<(), ApiError> {
+        let mut listener = PgListener::connect_with(db.get_postgres_connection_pool())
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        listener
+            .listen(CHANNEL)
+            .await
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        NOTIFY.store(true, Ordering::Relaxed);
+
+        rocket::tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        if let Ok(change) = serde_json::from_str(notification.payload()) {
+                            let _ = SENDER.send(change);
+                        }
+                    }
+                    Err(e) => {
+                        // The listener reconnects on the next call
+                        println!("Task event listener error: {e}");
+                        sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Changes of the tasks of a user, replayed from the change feed and then followed as they are committed.
+///
+/// Feeds the Server-Sent Events stream and the gRPC `WatchTasks` call. Every change is returned
+/// once, in the order of the feed, and a watcher falling behind the in-process channel catches up
+/// from the feed. `next` is cancel safe, so it can be raced against a shutdown signal.
+pub struct TaskWatch {
+    /// The ID of the user owning the tasks.
+    user_id: i32,
+    /// The committed changes, subscribed before reading the feed so none is missed.
+    changes: broadcast::Receiver<TaskChange>,
+    /// Position of the last change returned.
+    seq: i64,
+    /// Whether changes after `seq` are still waiting in the feed.
+    replay: bool,
+    /// Changes read from the feed and not returned yet.
+    pending: VecDeque<TaskChange>,
+    /// SeaORM database connection, owned as the watch outlives the request.
+    db: DbConn,
+}
+
+impl TaskWatch {
+    /// Asynchronously starts watching the tasks of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `since` - Position of the last change the client received, the changes after it are
+    ///   replayed first. Without it, only the changes committed from now on are returned.
+    /// * `db` - SeaORM database connection (`DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `TaskWatch`, or an `ApiError` if the position cannot be read.
+    pub async fn start(user_id: i32, since: Option<i64>, db: DbConn) -> Result<Self, ApiError> {
+        // Subscribe before reading the feed, so no change committed meanwhile is missed
+        let changes = TaskEvents::subscribe();
+        let (seq, replay) = match since {
+            Some(seq) => (seq, true),
+            None => (SyncQueries::get_position(user_id, &db).await?, false),
+        };
+
+        Ok(Self::new(user_id, changes, seq, replay, db))
+    }
+
+    /// Creates the watch of the changes after `seq`, reading the feed first when `replay` is set.
+    fn new(
+        user_id: i32,
+        changes: broadcast::Receiver<TaskChange>,
+        seq: i64,
+        replay: bool,
+        db: DbConn,
+    ) -> Self {
+        Self {
+            user_id,
+            changes,
+            seq,
+            replay,
+            pending: VecDeque::new(),
+            db,
+        }
+    }
+
+    /// Asynchronously waits for the next change.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the change, or an `ApiError` if the feed cannot be read or the
+    /// channel closed. The watch should be dropped after an error, the client resumes from the
+    /// last change it received.
+    pub async fn next(&mut self) -> Result<TaskChange, ApiError> {
+        loop {
+            // Send the changes after `seq` from the change feed
+            if let Some(change) = self.pending.pop_front() {
+                self.seq = change.seq;
+                return Ok(change);
+            }
+            if self.replay {
+                let (page, has_more) =
+                    SyncQueries::get_feed(self.seq, true, REPLAY_PAGE, self.user_id, &self.db)
+                        .await?;
+                self.pending = page.into();
+                self.replay = has_more;
+                continue;
+            }
+
+            // Then the changes as they are committed
+            if let Some(change) = self.recv().await? {
+                return Ok(change);
+            }
+        }
+    }
+
+    /// Asynchronously waits for the next committed change that was not replayed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the change, `None` once changes were dropped and must be replayed
+    /// from the feed, or an `ApiError` if the channel closed.
+    async fn recv(&mut self) -> Result<Option<TaskChange>, ApiError> {
+        loop {
+            match self.changes.recv().await {
+                Ok(change) if change.user_id == self.user_id && change.seq > self.seq => {
+                    self.seq = change.seq;
+                    return Ok(Some(change));
+                }
+                Ok(_) => continue,
+                // Changes were dropped while the watcher was slow, catch up from the feed
+                Err(RecvError::Lagged(_)) => {
+                    self.replay = true;
+                    return Ok(None);
+                }
+                Err(RecvError::Closed) => {
+                    return Err(ApiError::Internal("The change stream closed.".to_string()))
+                }
+            }
+        }
+    }
+}
+
+/// The `Last-Event-ID` header sent by a reconnecting event stream client, if any.
+pub struct LastEventId(pub Option<i64>);
+
+/// Implementation of Rocket's `FromRequest` trait for `Last-Event-ID`, which never fails.
+///
+/// A value that is not an event ID of the stream is ignored.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LastEventId {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(LastEventId(
+            request
+                .headers()
+                .get_one("Last-Event-ID")
+                .and_then(|id| id.trim().parse().ok())
+                .filter(|id| *id >= 0),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::models::change::TaskChangeKind;
+    use rocket::http::Header;
+    use rocket::local::asynchronous::Client;
+
+    fn change(user_id: i32, seq: i64) -> TaskChange {
+        TaskChange {
+            kind: TaskChangeKind::Deleted,
+            seq,
+            user_id,
+            task_id: 7,
+            task: None,
+        }
+    }
+
+    /// Starts a watch of the changes of user `1` after `seq` on a channel of `capacity` changes,
+    /// without a database to replay from.
+    fn watch(capacity: usize, seq: i64) -> (broadcast::Sender<TaskChange>, TaskWatch) {
+        let (sender, changes) = broadcast::channel(capacity);
+
+        (
+            sender,
+            TaskWatch::new(1, changes, seq, false, DbConn::Disconnected),
+        )
+    }
+
+    #[rocket::async_test]
+    async fn watches_skip_foreign_and_replayed_changes() {
+        let (sender, mut watch) = watch(8, 5);
+        for change in [change(2, 6), change(1, 5), change(1, 7), change(1, 8)] {
+            sender.send(change).unwrap();
+        }
+
+        assert_eq!(watch.next().await.unwrap().seq, 7);
+        assert_eq!(watch.next().await.unwrap().seq, 8);
+    }
+
+    #[rocket::async_test]
+    async fn replayed_changes_are_not_sent_again() {
+        let (sender, mut watch) = watch(8, 5);
+        watch.pending = VecDeque::from([change(1, 6), change(1, 7)]);
+        sender.send(change(1, 7)).unwrap();
+        sender.send(change(1, 8)).unwrap();
+
+        assert_eq!(watch.next().await.unwrap().seq, 6);
+        assert_eq!(watch.next().await.unwrap().seq, 7);
+        assert_eq!(watch.next().await.unwrap().seq, 8);
+    }
+
+    #[rocket::async_test]
+    async fn lagging_watches_catch_up_from_the_feed() {
+        let (sender, mut watch) = watch(1, 5);
+        sender.send(change(1, 6)).unwrap();
+        sender.send(change(1, 7)).unwrap();
+
+        // The change 6 was dropped, so the watch has to read the feed before the change 7
+        assert_eq!(watch.recv().await.unwrap(), None);
+        assert!(watch.replay);
+    }
+
+    #[rocket::async_test]
+    async fn watches_end_when_the_channel_closes() {
+        let (sender, mut watch) = watch(8, 5);
+        sender.send(change(1, 6)).unwrap();
+        drop(sender);
+
+        assert_eq!(watch.next().await.unwrap().seq, 6);
+        assert!(matches!(
+            watch.next().await,
+            Err(ApiError::Internal(message)) if message == "The change stream closed."
+        ));
+    }
+
+    /// Reads the `Last-Event-ID` of a request sending `header`, if any.
+    async fn last_event_id(header: Option<&'static str>) -> Option<i64> {
+        let client = Client::untracked(rocket::build()).await.unwrap();
+        let mut request = client.get("/events");
+        if let Some(header) = header {
+            request.add_header(Header::new("Last-Event-ID", header));
+        }
+
+        match LastEventId::from_request(request.inner()).await {
+            Outcome::Success(LastEventId(id)) => id,
+            _ => panic!("the Last-Event-ID guard failed"),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn event_ids_are_positions_in_the_feed() {
+        assert_eq!(last_event_id(Some("42")).await, Some(42));
+        assert_eq!(last_event_id(Some(" 0 ")).await, Some(0));
+    }
+
+    #[rocket::async_test]
+    async fn missing_or_foreign_event_ids_are_ignored() {
+        assert_eq!(last_event_id(None).await, None);
+        assert_eq!(last_event_id(Some("")).await, None);
+        assert_eq!(last_event_id(Some("-1")).await, None);
+        assert_eq!(last_event_id(Some("abc")).await, None);
+        assert_eq!(last_event_id(Some("1.5")).await, None);
+    }
+}
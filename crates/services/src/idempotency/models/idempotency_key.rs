@@ -0,0 +1,98 @@
+use crate::error::{ApiError, GuardError};
+use database::entities::idempotency_key::Model;
+use rocket::http::{ContentType, Header, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::status::Custom;
+use rocket::response::{self, Responder};
+use rocket::{Request, Response};
+
+/// The `Idempotency-Key` header of the request, if any.
+///
+/// Retries of a request sent with the same key get the response of the first attempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotencyKey(pub Option<String>);
+
+impl IdempotencyKey {
+    /// Name of the header carrying the idempotency key.
+    pub const HEADER: &'static str = "Idempotency-Key";
+}
+
+/// Implementation of Rocket's `FromRequest` trait for the idempotency key.
+///
+/// A missing header is accepted, while a key that is empty, longer than 255 characters
+/// or not printable ASCII fails with `422 Unprocessable Entity`.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKey {
+    type Error = String;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one(Self::HEADER) {
+            None => Outcome::Success(IdempotencyKey(None)),
+            Some(key)
+                if (1..=255).contains(&key.len()) && key.chars().all(|c| c.is_ascii_graphic()) =>
+            {
+                Outcome::Success(IdempotencyKey(Some(key.to_string())))
+            }
+            Some(_) => {
+                let message = format!(
+                    "The {} header must be 1 to 255 printable ASCII characters.",
+                    Self::HEADER
+                );
+
+                // Keep the message around so the 422 catcher can render it.
+                GuardError::cache(request, message.clone(), Vec::new());
+                Outcome::Error((Status::UnprocessableEntity, message))
+            }
+        }
+    }
+}
+
+/// Response stored for an idempotency key, replayed verbatim to the retries of the request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredResponse {
+    /// The HTTP status of the response.
+    pub status: Status,
+    /// The JSON body of the response, kept as sent.
+    pub body: String,
+    /// The `ETag` header of the response, if any.
+    pub etag: Option<String>,
+}
+
+/// Error returned while the first request sent with a key is still running.
+pub(crate) fn in_progress() -> ApiError {
+    ApiError::Conflict("A request with this idempotency key is still in progress.".to_string())
+}
+
+/// Conversion from a completed idempotency key, failing while the first request is still running.
+impl TryFrom<Model> for StoredResponse {
+    type Error = ApiError;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        match (model.response_status, model.response_body) {
+            (Some(status), Some(body)) => Ok(StoredResponse {
+                status: Status::new(status as u16),
+                body,
+                etag: model.response_etag,
+            }),
+            _ => Err(in_progress()),
+        }
+    }
+}
+
+/// Implementation of Rocket's `Responder` trait, marking the response as a replay
+/// with the `Idempotent-Replayed` header.
+impl<'r> Responder<'r, 'static> for StoredResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build_from(
+            Custom(self.status, (ContentType::JSON, self.body)).respond_to(request)?,
+        );
+
+        if let Some(etag) = self.etag {
+            response.header(Header::new("ETag", etag));
+        }
+
+        response
+            .header(Header::new("Idempotent-Replayed", "true"))
+            .ok()
+    }
+}
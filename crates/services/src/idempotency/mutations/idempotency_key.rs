@@ -0,0 +1,259 @@
+use crate::error::ApiError;
+use crate::idempotency::models::idempotency_key::{in_progress, StoredResponse};
+use chrono::{DateTime, Duration, Utc};
+use database::entities::idempotency_key::{ActiveModel, Column, Entity, Model};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::*;
+use serde_json::Value;
+
+/// How long the response of a request is replayed to its retries.
+pub const IDEMPOTENCY_KEY_TTL: Duration = Duration::hours(24);
+
+/// How long a key stays reserved by a request that has not completed, after which a retry takes it over.
+///
+/// Covers a process dying between the reservation and the end of the request, which never releases the key.
+pub const IDEMPOTENCY_KEY_LEASE: Duration = Duration::minutes(1);
+
+/// Matches the keys that can be dropped at `now`: the expired ones and the abandoned reservations.
+fn stale(now: DateTime<Utc>) -> Condition {
+    Condition::any()
+        .add(Column::CreatedAt.lt(now - IDEMPOTENCY_KEY_TTL))
+        .add(
+            Condition::all()
+                .add(Column::ResponseStatus.is_null())
+                .add(Column::CreatedAt.lt(now - IDEMPOTENCY_KEY_LEASE)),
+        )
+}
+
+/// Finds the response to replay to a retry of the request that reserved `earlier`.
+///
+/// # Arguments
+///
+/// * `earlier` - The idempotency key reserved by the first attempt.
+/// * `request` - The normalized payload of the retry.
+///
+/// # Returns
+///
+/// A `Result` containing the stored response, `409 Conflict` while the first attempt is still running,
+/// or `422 Unprocessable Entity` if the payloads differ.
+fn replay(earlier: Model, request: &Value) -> Result<StoredResponse, ApiError> {
+    if earlier.request != *request {
+        return Err(ApiError::Validation(
+            "The idempotency key was already used with a different payload.".to_string(),
+        ));
+    }
+
+    StoredResponse::try_from(earlier)
+}
+
+/// Struct for handling mutations on idempotency keys.
+pub struct IdempotencyMutation;
+
+impl IdempotencyMutation {
+    /// Asynchronously reserves `key` for the request, or finds the response of an earlier attempt.
+    ///
+    /// An expired key, or a reservation held past `IDEMPOTENCY_KEY_LEASE`, is dropped first, so it can be reused.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key sent by the client.
+    /// * `request` - The normalized payload of the request, compared with the one of earlier attempts.
+    /// * `user_id` - The ID of the user sending the request.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `None` if the request must run, or the `StoredResponse` to replay.
+    /// It fails with `409 Conflict` while an earlier attempt is still running within its lease,
+    /// and with `422 Unprocessable Entity` if the key was used with another payload.
+    pub async fn reserve(
+        key: &str,
+        request: Value,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Option<StoredResponse>, ApiError> {
+        // Drop the key if it expired or its reservation was abandoned
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Key.eq(key))
+            .filter(stale(Utc::now()))
+            .exec(db)
+            .await?;
+
+        // Reserve the key, unless an earlier attempt already did
+        let reservation = ActiveModel {
+            user_id: Set(user_id),
+            key: Set(key.to_string()),
+            request: Set(request.clone()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        };
+
+        let inserted = Entity::insert(reservation)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::Key])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec(db)
+            .await;
+
+        match inserted {
+            Ok(_) => return Ok(None),
+            Err(DbErr::RecordNotInserted) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // Replay the response of the earlier attempt if it sent the same payload
+        let earlier = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Key.eq(key))
+            .one(db)
+            .await?
+            .ok_or_else(in_progress)?;
+
+        replay(earlier, &request).map(Some)
+    }
+
+    /// Asynchronously stores the response of the request holding `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key sent by the client.
+    /// * `response` - The response sent to the client.
+    /// * `user_id` - The ID of the user sending the request.
+    /// * `db` - SeaORM connection, or the transaction of the request so the key is completed with its writes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError` on failure.
+    pub async fn complete<C: ConnectionTrait>(
+        key: &str,
+        response: StoredResponse,
+        user_id: i32,
+        db: &C,
+    ) -> Result<(), ApiError> {
+        Entity::update_many()
+            .col_expr(
+                Column::ResponseStatus,
+                Expr::value(response.status.code as i16),
+            )
+            .col_expr(Column::ResponseBody, Expr::value(response.body))
+            .col_expr(Column::ResponseEtag, Expr::value(response.etag))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Key.eq(key))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Asynchronously releases `key` after the request failed, so a retry runs it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key sent by the client.
+    /// * `user_id` - The ID of the user sending the request.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError` on failure.
+    pub async fn release(key: &str, user_id: i32, db: &DbConn) -> Result<(), ApiError> {
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Key.eq(key))
+            .filter(Column::ResponseStatus.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Asynchronously deletes the keys older than `IDEMPOTENCY_KEY_TTL` and the abandoned reservations.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of deleted keys on success, or an `ApiError` on failure.
+    pub async fn sweep_expired(db: &DbConn) -> Result<u64, ApiError> {
+        let result = Entity::delete_many()
+            .filter(stale(Utc::now()))
+            .exec(db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Status;
+    use serde_json::json;
+
+    fn reserved(request: Value, response: Option<(i16, &str)>) -> Model {
+        Model {
+            id: 1,
+            user_id: 1,
+            key: "create-milk".to_string(),
+            request,
+            response_status: response.map(|(status, _)| status),
+            response_body: response.map(|(_, body)| body.to_string()),
+            response_etag: response.map(|_| "\"v1\"".to_string()),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn retries_get_the_stored_response() {
+        let request = json!({ "name": "Buy milk" });
+        let earlier = reserved(request.clone(), Some((200, r#"{"status":200}"#)));
+
+        assert_eq!(
+            replay(earlier, &request).unwrap(),
+            StoredResponse {
+                status: Status::Ok,
+                body: r#"{"status":200}"#.to_string(),
+                etag: Some("\"v1\"".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn retries_with_another_payload_are_refused() {
+        let earlier = reserved(json!({ "name": "Buy milk" }), Some((200, "{}")));
+
+        assert!(matches!(
+            replay(earlier, &json!({ "name": "Buy bread" })),
+            Err(ApiError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn abandoned_reservations_are_stale_before_completed_keys() {
+        let now = "2026-10-19T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let sql = Entity::delete_many()
+            .filter(stale(now))
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            sql.split_once(" WHERE ").unwrap().1,
+            r#""idempotency_keys"."created_at" < '2026-10-18 12:00:00 +00:00' OR ("idempotency_keys"."response_status" IS NULL AND "idempotency_keys"."created_at" < '2026-10-19 11:59:00 +00:00')"#
+        );
+    }
+
+    #[test]
+    fn retries_wait_for_the_first_attempt() {
+        let request = json!({ "name": "Buy milk" });
+
+        assert!(matches!(
+            replay(reserved(request.clone(), None), &request),
+            Err(ApiError::Conflict(_))
+        ));
+    }
+}
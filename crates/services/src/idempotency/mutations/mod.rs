@@ -0,0 +1 @@
+pub mod idempotency_key;
@@ -1,3 +1,15 @@
+pub mod app_password;
 pub mod auth;
+pub mod caldav;
+pub mod calendar_feed;
+pub mod error;
+pub mod etag;
+pub mod events;
+pub mod idempotency;
+pub mod request_id;
+pub mod response;
+pub mod saved_filter;
+pub mod sync;
 pub mod task;
 pub mod user;
+pub mod webhook;
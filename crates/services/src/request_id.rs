@@ -0,0 +1,80 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Request, Response};
+use uuid::Uuid;
+
+/// Identifier of the current request, used to correlate logs and task events.
+///
+/// The identifier sent by the client in the `X-Request-Id` header is kept when it is valid,
+/// otherwise a random one is generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Name of the header carrying the request ID, in both directions.
+    pub const HEADER: &'static str = "X-Request-Id";
+
+    /// Returns the ID of `request`, computing it on first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The incoming Rocket request.
+    ///
+    /// # Returns
+    ///
+    /// The `RequestId` cached for the request.
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r RequestId {
+        request.local_cache(|| RequestId::from_sent(request.headers().get_one(Self::HEADER)))
+    }
+
+    /// Returns the ID sent by the client, or a new one when it is missing or untrusted.
+    ///
+    /// # Arguments
+    ///
+    /// * `sent` - The ID sent by the client, if any.
+    ///
+    /// # Returns
+    ///
+    /// The `RequestId`.
+    pub fn from_sent(sent: Option<&str>) -> RequestId {
+        // Only short, printable identifiers from the client are trusted
+        let sent = sent
+            .filter(|id| (1..=128).contains(&id.len()) && id.chars().all(|c| c.is_ascii_graphic()));
+
+        RequestId(
+            sent.map(str::to_string)
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        )
+    }
+}
+
+/// Implementation of Rocket's `FromRequest` trait for the request ID, which never fails.
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestId {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(RequestId::of(request).clone())
+    }
+}
+
+/// Fairing echoing the request ID in the `X-Request-Id` header of every response.
+pub struct RequestIdFairing;
+
+#[rocket::async_trait]
+impl Fairing for RequestIdFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request ID",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        response.set_header(Header::new(
+            RequestId::HEADER,
+            RequestId::of(request).0.clone(),
+        ));
+    }
+}
@@ -0,0 +1,18 @@
+use rocket::http::Status;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Envelope shared by every API response.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ResponseRequest<T> {
+    /// Human readable message describing the result.
+    pub message: Option<String>,
+    /// HTTP status of the response.
+    #[schema(value_type = u16, example = 200)]
+    pub status: Status,
+    /// Machine readable error code, only present on errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Payload of the response.
+    pub data: T,
+}
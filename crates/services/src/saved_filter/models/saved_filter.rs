@@ -0,0 +1,40 @@
+use database::entities::saved_filter::Model;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Struct representing a task view, either a built-in view or a filter saved by the user.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TaskView {
+    /// The ID of the view: the saved filter ID, or the slug of a built-in view (e.g. `today`).
+    pub id: String,
+    /// The display name of the view.
+    pub name: String,
+    /// Flag indicating whether the view is built-in, built-in views cannot be changed.
+    pub system: bool,
+    /// The task list parameters (`page`, `size`, `query`, `q`, `sort`) applied by the view.
+    #[schema(value_type = Object)]
+    pub filters: Value,
+}
+
+/// Conversion implementation from a saved filter `Model` to `TaskView`.
+impl From<Model> for TaskView {
+    /// Convert a saved filter into a `TaskView`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The saved filter entity.
+    ///
+    /// # Returns
+    ///
+    /// A `TaskView` instance populated with data from the saved filter.
+    ///
+    fn from(value: Model) -> Self {
+        Self {
+            id: value.id.to_string(),
+            name: value.name,
+            system: false,
+            filters: value.filters,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::ApiError;
+use crate::task::queries::filter;
+use database::entities::saved_filter::{ActiveModel, Column, Entity, Model};
+use sea_orm::*;
+use serde_json::Value;
+
+/// Struct for handling mutations (create, update, delete) on saved filters.
+pub struct SavedFilterMutation;
+
+/// Payload structure for creating or updating a saved filter.
+pub struct SavedFilterPayload {
+    /// The display name of the saved filter.
+    pub name: String,
+    /// The serialized task list parameters, the `q` member holds the filter query.
+    pub filters: Value,
+    /// The ID of the user owning the saved filter.
+    pub user_id: i32,
+}
+
+impl SavedFilterPayload {
+    /// Makes sure the filter query of the payload compiles, so a broken filter cannot be saved.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError::Validation` pointing at the invalid token.
+    fn validate(&self) -> Result<(), ApiError> {
+        if let Some(query) = self.filters.get("q").and_then(Value::as_str) {
+            filter::compile(query)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SavedFilterMutation {
+    /// Asynchronously creates a new saved filter with the provided payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - Payload containing the saved filter details.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the created `Model` on success, or an `ApiError` on failure.
+    pub async fn create(payload: SavedFilterPayload, db: &DbConn) -> Result<Model, ApiError> {
+        payload.validate()?;
+
+        // Create an ActiveModel instance with the payload data
+        let active_model = ActiveModel {
+            name: Set(payload.name),
+            filters: Set(payload.filters),
+            user_id: Set(payload.user_id),
+            ..Default::default()
+        };
+
+        // Execute the insert operation and return the stored row
+        Ok(active_model.insert(db).await?)
+    }
+
+    /// Asynchronously updates an existing saved filter identified by `id` with the provided payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - Payload containing the saved filter details.
+    /// * `id` - The ID of the saved filter to be updated.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated `Model` on success, or an `ApiError` on failure.
+    pub async fn update(
+        payload: SavedFilterPayload,
+        id: i32,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        payload.validate()?;
+
+        // Fetch the saved filter of the user and convert it into an ActiveModel
+        let mut saved_filter: ActiveModel = Entity::find_by_id(id)
+            .filter(Column::UserId.eq(payload.user_id))
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("View not found.")))
+            .map(Into::into)?;
+
+        // Update the fields with the new values from the payload
+        saved_filter.name = Set(payload.name);
+        saved_filter.filters = Set(payload.filters);
+
+        // Execute the update operation and await the result
+        Ok(saved_filter.update(db).await?)
+    }
+
+    /// Asynchronously deletes an existing saved filter identified by `id` and `user_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the saved filter to be deleted.
+    /// * `user_id` - The ID of the user deleting the saved filter.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion result on success, or an `ApiError` on failure.
+    pub async fn delete(id: i32, user_id: i32, db: &DbConn) -> Result<DeleteResult, ApiError> {
+        // Only delete the saved filter if it belongs to the user
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound(String::from("View not found.")));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::ApiError;
+use crate::saved_filter::models::saved_filter::TaskView;
+use database::entities::saved_filter::{Column, Entity};
+use sea_orm::*;
+use serde_json::json;
+
+/// Struct for handling queries related to saved filters and task views.
+pub struct SavedFilterQueries;
+
+/// Built-in views available to every user, on top of their saved filters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemView {
+    /// Open tasks due today.
+    Today,
+    /// Open tasks due after today.
+    Upcoming,
+    /// Open tasks whose due date has passed.
+    Overdue,
+    /// Open tasks without a due date.
+    NoDueDate,
+}
+
+impl SystemView {
+    /// Every built-in view, in display order.
+    pub const ALL: [SystemView; 4] = [
+        SystemView::Today,
+        SystemView::Upcoming,
+        SystemView::Overdue,
+        SystemView::NoDueDate,
+    ];
+
+    /// Returns the slug used as the ID of the view.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            SystemView::Today => "today",
+            SystemView::Upcoming => "upcoming",
+            SystemView::Overdue => "overdue",
+            SystemView::NoDueDate => "no-due-date",
+        }
+    }
+
+    /// Returns the display name of the view.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SystemView::Today => "Today",
+            SystemView::Upcoming => "Upcoming",
+            SystemView::Overdue => "Overdue",
+            SystemView::NoDueDate => "No due date",
+        }
+    }
+
+    /// Returns the filter query (see `task::queries::filter`) selecting the tasks of the view.
+    pub fn query(&self) -> &'static str {
+        match self {
+            SystemView::Today => "status:open due:today",
+            SystemView::Upcoming => "status:open due>today",
+            SystemView::Overdue => "status:open due<today",
+            SystemView::NoDueDate => "status:open due:none",
+        }
+    }
+
+    /// Finds the built-in view with the given slug.
+    pub fn from_slug(slug: &str) -> Option<SystemView> {
+        SystemView::ALL.into_iter().find(|view| view.slug() == slug)
+    }
+}
+
+/// Conversion implementation from `SystemView` to `TaskView`.
+impl From<SystemView> for TaskView {
+    fn from(value: SystemView) -> Self {
+        Self {
+            id: value.slug().to_string(),
+            name: value.name().to_string(),
+            system: true,
+            filters: json!({ "q": value.query(), "sort": "id" }),
+        }
+    }
+}
+
+impl SavedFilterQueries {
+    /// Asynchronously fetches every view of a user, the built-in views first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the saved filters.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the views on success, or an `ApiError` on failure.
+    pub async fn get_views(user_id: i32, db: &DbConn) -> Result<Vec<TaskView>, ApiError> {
+        // Fetch the saved filters of the user, oldest first
+        let saved = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(SystemView::ALL
+            .into_iter()
+            .map(TaskView::from)
+            .chain(saved.into_iter().map(TaskView::from))
+            .collect())
+    }
+
+    /// Asynchronously fetches a view by its ID, either a built-in view slug or a saved filter ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The slug of a built-in view or the ID of a saved filter.
+    /// * `user_id` - The ID of the user owning the saved filter.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `TaskView` on success, or an `ApiError` if the view is not found.
+    pub async fn get_view(id: &str, user_id: i32, db: &DbConn) -> Result<TaskView, ApiError> {
+        if let Some(view) = SystemView::from_slug(id) {
+            return Ok(view.into());
+        }
+
+        let not_found = || ApiError::NotFound(String::from("View not found."));
+
+        // Saved filters are identified by their numeric ID
+        let id = id.parse::<i32>().map_err(|_| not_found())?;
+
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .map(TaskView::from)
+            .ok_or_else(not_found)
+    }
+}
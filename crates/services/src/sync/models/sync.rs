@@ -0,0 +1,146 @@
+use crate::error::{ApiError, FieldError};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use database::entities::task::Model as TaskEntity;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Position in the change feed of the tasks, sent to clients as an opaque token.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncToken {
+    /// The last change sequence number the client has seen.
+    seq: i64,
+}
+
+impl SyncToken {
+    /// Creates the token pointing after the change `seq`.
+    pub fn new(seq: i64) -> Self {
+        Self { seq }
+    }
+
+    /// Returns the last change sequence number the client has seen.
+    pub fn seq(&self) -> i64 {
+        self.seq
+    }
+
+    /// Encodes the token as an URL safe string.
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decodes a token previously returned by `encode`.
+    pub fn decode(token: &str) -> Result<Self, ApiError> {
+        URL_SAFE_NO_PAD
+            .decode(token)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|token| token.seq >= 0)
+            .ok_or(ApiError::Validation(
+                "The sync token is invalid.".to_string(),
+            ))
+    }
+}
+
+/// Structure representing the task changes since a sync token.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SyncChanges {
+    /// Tasks created since the token, with their current fields.
+    pub created: Vec<TaskEntity>,
+    /// Tasks created before the token and changed since.
+    pub updated: Vec<TaskEntity>,
+    /// IDs of the tasks deleted since the token.
+    pub deleted: Vec<i32>,
+    /// Token to send as `since` on the next sync.
+    pub token: String,
+    /// Whether more changes are waiting, fetch them right away with `token`.
+    pub has_more: bool,
+}
+
+/// Outcome of a client change sent in a sync batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// The change was applied.
+    Applied,
+    /// The task changed on the server since the version the client based its change on.
+    Conflict,
+    /// The task does not exist anymore, or never existed.
+    NotFound,
+    /// The change is invalid, see `errors`.
+    Invalid,
+}
+
+/// Result of a client change sent in a sync batch.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SyncResult {
+    /// Position of the change in the batch.
+    pub index: usize,
+    /// The identifier the client gave to a created task, echoed back.
+    pub client_id: Option<String>,
+    /// The ID of the task, if known.
+    pub id: Option<i32>,
+    /// Outcome of the change.
+    pub status: SyncStatus,
+    /// The task as stored on the server after the change, or the server copy on a conflict.
+    pub task: Option<TaskEntity>,
+    /// Message describing a change that was not applied.
+    pub message: Option<String>,
+    /// Errors of the individual fields of an invalid change.
+    pub errors: Vec<FieldError>,
+}
+
+impl SyncResult {
+    /// Builds the result of a change rejected before reaching the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the change in the batch.
+    /// * `client_id` - The identifier the client gave to a created task, if any.
+    /// * `id` - The ID of the task, if known.
+    /// * `errors` - Errors of the individual fields of the change.
+    ///
+    /// # Returns
+    ///
+    /// The `SyncResult` with the `invalid` status.
+    pub fn invalid(
+        index: usize,
+        client_id: Option<String>,
+        id: Option<i32>,
+        errors: Vec<FieldError>,
+    ) -> Self {
+        Self {
+            index,
+            client_id,
+            id,
+            status: SyncStatus::Invalid,
+            task: None,
+            message: errors.first().map(|error| error.message.clone()),
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_survive_encoding() {
+        assert_eq!(
+            SyncToken::decode(&SyncToken::new(42).encode())
+                .unwrap()
+                .seq(),
+            42
+        );
+        assert_eq!(SyncToken::default().seq(), 0);
+    }
+
+    #[test]
+    fn malformed_tokens_are_invalid() {
+        let invalid = |token: &str| matches!(SyncToken::decode(token), Err(ApiError::Validation(message)) if message == "The sync token is invalid.");
+
+        assert!(invalid("not a token!"));
+        assert!(invalid(&URL_SAFE_NO_PAD.encode(b"{}")));
+        assert!(invalid(&URL_SAFE_NO_PAD.encode(br#"{"seq":-1}"#)));
+    }
+}
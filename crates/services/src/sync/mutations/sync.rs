@@ -0,0 +1,107 @@
+use crate::error::ApiError;
+use crate::etag::{etag, EntityTags};
+use crate::sync::models::sync::{SyncResult, SyncStatus};
+use crate::task::mutations::task::{TaskMutation, TaskPayload};
+use database::entities::task::{Column, Entity};
+use sea_orm::*;
+
+/// Struct for applying the changes sent by offline clients.
+pub struct SyncMutation;
+
+/// Change of a task made by a client while offline.
+pub enum SyncOperation {
+    /// Create a task.
+    Create(TaskPayload),
+    /// Replace the fields of the task `id`.
+    Update(i32, TaskPayload),
+    /// Delete the task `id`, if it still has `version` when one is given.
+    Delete {
+        /// The ID of the task to delete.
+        id: i32,
+        /// The version of the task the client based the deletion on.
+        version: Option<i32>,
+    },
+}
+
+/// Builds the `If-Match` precondition requiring the task to still have `version`.
+pub fn version_precondition(version: Option<i32>) -> Option<EntityTags> {
+    version.map(|version| EntityTags::Tags(vec![etag(version)]))
+}
+
+impl SyncMutation {
+    /// Asynchronously applies a single client change of a sync batch.
+    ///
+    /// Every change is applied on its own through `TaskMutation`, so it is recorded in the task
+    /// history like any other write. A change based on an outdated version is not applied and
+    /// reported as a conflict, along with the server copy of the task.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the change in the batch.
+    /// * `client_id` - The identifier the client gave to a created task, if any.
+    /// * `operation` - The change to apply.
+    /// * `user_id` - The ID of the user sending the batch.
+    /// * `request_id` - The ID of the request sending the batch, recorded in the task history.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SyncResult` of the change, or an `ApiError` on an unexpected failure.
+    pub async fn apply(
+        index: usize,
+        client_id: Option<String>,
+        operation: SyncOperation,
+        user_id: i32,
+        request_id: Option<String>,
+        db: &DbConn,
+    ) -> Result<SyncResult, ApiError> {
+        // A deleted task has nothing left to send back
+        let (id, applied) = match operation {
+            SyncOperation::Create(payload) => {
+                (None, TaskMutation::create(payload, db).await.map(Some))
+            }
+            SyncOperation::Update(id, payload) => (
+                Some(id),
+                TaskMutation::update(payload, id, db).await.map(Some),
+            ),
+            SyncOperation::Delete { id, version } => {
+                let deleted = TaskMutation::delete(
+                    id,
+                    user_id,
+                    request_id,
+                    version_precondition(version),
+                    db,
+                )
+                .await;
+
+                (Some(id), deleted.map(|_| None))
+            }
+        };
+
+        let (status, task, message) = match applied {
+            Ok(task) => (SyncStatus::Applied, task, None),
+            Err(ApiError::PreconditionFailed(message)) => {
+                // Send the server copy so the client can resolve the conflict
+                let current = Entity::find_by_id(id.unwrap_or_default())
+                    .filter(Column::UserId.eq(user_id))
+                    .one(db)
+                    .await?;
+
+                (SyncStatus::Conflict, current, Some(message))
+            }
+            Err(ApiError::NotFound(message)) => (SyncStatus::NotFound, None, Some(message)),
+            Err(ApiError::Validation(message)) => (SyncStatus::Invalid, None, Some(message)),
+            Err(e) => return Err(e),
+        };
+
+        Ok(SyncResult {
+            index,
+            client_id,
+            id: task.as_ref().map(|task| task.id).or(id),
+            status,
+            task,
+            message,
+            errors: Vec::new(),
+        })
+    }
+}
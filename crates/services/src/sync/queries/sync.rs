@@ -0,0 +1,264 @@
+use crate::error::ApiError;
+use crate::sync::models::sync::{SyncChanges, SyncToken};
+use crate::task::models::change::{TaskChange, TaskChangeKind};
+use database::entities::{task, task_tombstone};
+use sea_orm::*;
+
+/// Struct for handling queries related to the delta sync of tasks.
+pub struct SyncQueries;
+
+/// Merges the changed tasks and the tombstones fetched after the change `since` in the order of the feed.
+///
+/// # Arguments
+///
+/// * `tasks` - The tasks changed after `since`, in the order of the feed.
+/// * `deleted` - The tombstones of the tasks deleted after `since`, in the order of the feed.
+/// * `since` - The last change the client has seen, tasks created after it are reported as created.
+/// * `limit` - The maximum number of changes to return.
+///
+/// # Returns
+///
+/// The first `limit` changes, and whether there are more.
+fn merge_feed(
+    tasks: &[task::Model],
+    deleted: &[task_tombstone::Model],
+    since: i64,
+    limit: u64,
+) -> (Vec<TaskChange>, bool) {
+    let mut changes = tasks
+        .iter()
+        .map(|task| match task.created_seq > since {
+            true => TaskChange::created(task),
+            false => TaskChange::updated(task),
+        })
+        .chain(deleted.iter().map(TaskChange::deleted))
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|change| change.seq);
+
+    let has_more = changes.len() as u64 > limit;
+    changes.truncate(limit as usize);
+
+    (changes, has_more)
+}
+
+impl SyncQueries {
+    /// Asynchronously fetches the position of the last change of the tasks of a user in the change feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM connection or transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the position, `0` if the user never had a task, or an `ApiError` on failure.
+    pub async fn get_position<C: ConnectionTrait>(user_id: i32, db: &C) -> Result<i64, ApiError> {
+        let task = task::Entity::find()
+            .select_only()
+            .column_as(task::Column::ChangeSeq.max(), "seq")
+            .filter(task::Column::UserId.eq(user_id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten();
+
+        let tombstone = task_tombstone::Entity::find()
+            .select_only()
+            .column_as(task_tombstone::Column::ChangeSeq.max(), "seq")
+            .filter(task_tombstone::Column::UserId.eq(user_id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten();
+
+        Ok(task.max(tombstone).unwrap_or_default())
+    }
+
+    /// Asynchronously fetches the changes of the tasks of a user after the change `since` of the feed.
+    ///
+    /// A task changed several times is only returned once, at the position of its last change.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The last change the client has seen, `0` for every task.
+    /// * `tombstones` - Whether to include the deleted tasks.
+    /// * `limit` - The maximum number of changes to fetch.
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM connection or transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the changes in the order of the feed, and whether there are more,
+    /// or an `ApiError` on failure.
+    pub async fn get_feed<C: ConnectionTrait>(
+        since: i64,
+        tombstones: bool,
+        limit: u64,
+        user_id: i32,
+        db: &C,
+    ) -> Result<(Vec<TaskChange>, bool), ApiError> {
+        // Fetch one extra change of each kind to know if there are more
+        let tasks = task::Entity::find()
+            .filter(task::Column::UserId.eq(user_id))
+            .filter(task::Column::ChangeSeq.gt(since))
+            .order_by_asc(task::Column::ChangeSeq)
+            .limit(limit + 1)
+            .all(db)
+            .await?;
+
+        let deleted = match tombstones {
+            true => {
+                task_tombstone::Entity::find()
+                    .filter(task_tombstone::Column::UserId.eq(user_id))
+                    .filter(task_tombstone::Column::ChangeSeq.gt(since))
+                    .order_by_asc(task_tombstone::Column::ChangeSeq)
+                    .limit(limit + 1)
+                    .all(db)
+                    .await?
+            }
+            false => Vec::new(),
+        };
+
+        Ok(merge_feed(&tasks, &deleted, since, limit))
+    }
+
+    /// Asynchronously fetches the changes of the tasks of a user since a sync token.
+    ///
+    /// Changes are returned in the order of the feed, so a client applying them in order and
+    /// then syncing from the returned token never misses one. Without a token every task is
+    /// returned as created, and no tombstones are sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The token returned by the previous sync, if any.
+    /// * `limit` - The maximum number of changes to fetch.
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SyncChanges` on success, or an `ApiError` if the token is invalid.
+    pub async fn get_changes(
+        since: Option<&str>,
+        limit: u64,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<SyncChanges, ApiError> {
+        if limit < 1 {
+            return Err(ApiError::Validation(
+                "The limit number must be greater than 0.".to_string(),
+            ));
+        }
+
+        let token = since.map(SyncToken::decode).transpose()?;
+        let seq = token.unwrap_or_default().seq();
+
+        let (changes, has_more) = Self::get_feed(seq, token.is_some(), limit, user_id, db).await?;
+
+        let next = changes.last().map_or(seq, |change| change.seq);
+
+        let mut created = Vec::new();
+        let mut updated = Vec::new();
+        let mut deleted = Vec::new();
+        for change in changes {
+            match (change.kind, change.task) {
+                (TaskChangeKind::Created, Some(task)) => created.push(task),
+                (TaskChangeKind::Updated, Some(task)) => updated.push(task),
+                _ => deleted.push(change.task_id),
+            }
+        }
+
+        Ok(SyncChanges {
+            created,
+            updated,
+            deleted,
+            token: SyncToken::new(next).encode(),
+            has_more,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn task(id: i32, created_seq: i64, change_seq: i64) -> task::Model {
+        task::Model {
+            id,
+            name: "Buy milk".to_string(),
+            description: "Two litres".to_string(),
+            is_active: false,
+            user_id: 1,
+            due_date: None,
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            change_seq,
+            created_seq,
+        }
+    }
+
+    fn tombstone(task_id: i32, change_seq: i64) -> task_tombstone::Model {
+        task_tombstone::Model {
+            task_id,
+            user_id: 1,
+            change_seq,
+            deleted_at: Utc::now(),
+            version: 2,
+        }
+    }
+
+    /// Summarizes the changes as `(kind, task ID, position)`.
+    fn summary(changes: &[TaskChange]) -> Vec<(TaskChangeKind, i32, i64)> {
+        changes
+            .iter()
+            .map(|change| (change.kind, change.task_id, change.seq))
+            .collect()
+    }
+
+    #[test]
+    fn changes_are_merged_in_the_order_of_the_feed() {
+        let (changes, has_more) =
+            merge_feed(&[task(1, 1, 6), task(2, 4, 8)], &[tombstone(3, 7)], 5, 10);
+
+        assert_eq!(
+            summary(&changes),
+            [
+                (TaskChangeKind::Updated, 1, 6),
+                (TaskChangeKind::Deleted, 3, 7),
+                (TaskChangeKind::Updated, 2, 8),
+            ]
+        );
+        assert!(!has_more);
+    }
+
+    #[test]
+    fn tasks_created_after_the_token_are_created() {
+        let (changes, _) = merge_feed(&[task(1, 6, 9)], &[], 5, 10);
+
+        assert_eq!(summary(&changes), [(TaskChangeKind::Created, 1, 9)]);
+    }
+
+    #[test]
+    fn changes_past_the_limit_are_left_for_the_next_sync() {
+        let (changes, has_more) =
+            merge_feed(&[task(1, 1, 2), task(2, 1, 4)], &[tombstone(3, 3)], 0, 2);
+
+        assert_eq!(
+            summary(&changes),
+            [
+                (TaskChangeKind::Created, 1, 2),
+                (TaskChangeKind::Deleted, 3, 3),
+            ]
+        );
+        assert!(has_more);
+
+        let (changes, has_more) = merge_feed(&[task(1, 1, 2)], &[tombstone(3, 3)], 0, 2);
+        assert_eq!(changes.len(), 2);
+        assert!(!has_more);
+    }
+}
@@ -0,0 +1,75 @@
+use database::entities::task::Model as TaskEntity;
+use database::entities::task_tombstone::Model as TombstoneEntity;
+use serde::{Deserialize, Serialize};
+
+/// Kind of change of a task, as pushed to the event stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskChangeKind {
+    /// The task was created, or restored after a deletion.
+    Created,
+    /// The task was changed.
+    Updated,
+    /// The task was deleted.
+    Deleted,
+}
+
+impl TaskChangeKind {
+    /// Returns the name of the Server-Sent Event announcing the change.
+    pub const fn event_name(&self) -> &'static str {
+        match self {
+            TaskChangeKind::Created => "task.created",
+            TaskChangeKind::Updated => "task.updated",
+            TaskChangeKind::Deleted => "task.deleted",
+        }
+    }
+}
+
+/// Change of a task, published once committed and replayed from the change feed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskChange {
+    /// The kind of change.
+    pub kind: TaskChangeKind,
+    /// Position of the change in the change feed.
+    pub seq: i64,
+    /// The ID of the user owning the task.
+    pub user_id: i32,
+    /// The ID of the task.
+    pub task_id: i32,
+    /// The task after the change, `None` when it was deleted.
+    pub task: Option<TaskEntity>,
+}
+
+impl TaskChange {
+    /// Builds the change announcing that `task` was created.
+    pub fn created(task: &TaskEntity) -> Self {
+        Self::written(TaskChangeKind::Created, task)
+    }
+
+    /// Builds the change announcing that `task` was changed.
+    pub fn updated(task: &TaskEntity) -> Self {
+        Self::written(TaskChangeKind::Updated, task)
+    }
+
+    /// Builds the change announcing the deletion recorded by `tombstone`.
+    pub fn deleted(tombstone: &TombstoneEntity) -> Self {
+        Self {
+            kind: TaskChangeKind::Deleted,
+            seq: tombstone.change_seq,
+            user_id: tombstone.user_id,
+            task_id: tombstone.task_id,
+            task: None,
+        }
+    }
+
+    /// Builds the change announcing a write of `task`.
+    fn written(kind: TaskChangeKind, task: &TaskEntity) -> Self {
+        Self {
+            kind,
+            seq: task.change_seq,
+            user_id: task.user_id,
+            task_id: task.id,
+            task: Some(task.clone()),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use database::entities::task::{Model, TaskPriority};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Struct representing the editable fields of a task at a given revision.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TaskRevision {
+    /// The name of the task.
+    pub name: String,
+    /// The description of the task.
+    pub description: String,
+    /// Flag indicating whether the task is active or not.
+    pub is_active: bool,
+    /// The date the task is due, if any.
+    pub due_date: Option<NaiveDate>,
+    /// The priority of the task, `normal` in revisions recorded before tasks had one.
+    #[serde(default)]
+    pub priority: TaskPriority,
+    /// The tags of the task.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The project of the task, if any.
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+/// Conversion implementation from a task `Model` to its `TaskRevision`.
+impl From<&Model> for TaskRevision {
+    fn from(value: &Model) -> Self {
+        Self {
+            name: value.name.clone(),
+            description: value.description.clone(),
+            is_active: value.is_active,
+            due_date: value.due_date,
+            priority: value.priority,
+            tags: value.tags.clone(),
+            project: value.project.clone(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::error::FieldError;
+use crate::task::mutations::task::TaskPayload;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Largest number of rows in one import.
+pub const MAX_IMPORT_ROWS: usize = 5000;
+
+/// A row of an import, validated.
+pub struct ImportCandidate {
+    /// The position of the row in the imported file.
+    pub row: usize,
+    /// The task to create, or the errors of the invalid fields.
+    pub task: Result<TaskPayload, Vec<FieldError>>,
+}
+
+/// What an import does with a row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// The task is created, or would be by a dry run.
+    Created,
+    /// The user already has the task, or an earlier row holds it, so it is skipped.
+    Duplicate,
+    /// The row has errors, nothing is imported.
+    Invalid,
+}
+
+/// The outcome of a row of an import.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ImportedRow {
+    /// The position of the row: its line in the text formats, its index (from 1) in a JSON array.
+    pub row: usize,
+    /// What the import does with the row.
+    pub status: ImportStatus,
+    /// The name of the task, if the row is valid.
+    pub name: Option<String>,
+    /// The ID of the created task, unless the import is a dry run.
+    pub task_id: Option<i32>,
+    /// The errors of the row, when it is invalid.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// Summary of an import.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    /// Whether the import only checked the rows, without writing anything.
+    pub dry_run: bool,
+    /// The number of rows read.
+    pub total: usize,
+    /// The number of tasks created, or that a dry run would create.
+    pub created: usize,
+    /// The number of rows skipped as duplicates.
+    pub duplicates: usize,
+    /// The number of invalid rows. When there is any, nothing is imported.
+    pub invalid: usize,
+    /// The outcome of every row, in order.
+    pub rows: Vec<ImportedRow>,
+}
@@ -0,0 +1,167 @@
+use crate::task::models::provider::{parse_date, ForeignTask};
+use serde::Deserialize;
+
+/// The task lists of a user, as listed by `GET /me/todo/lists` of the Microsoft Graph API.
+#[derive(Deserialize)]
+struct Lists {
+    #[serde(alias = "lists")]
+    value: Vec<TodoList>,
+}
+
+/// A task list, with its tasks as listed by `GET /me/todo/lists/{id}/tasks`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TodoList {
+    display_name: String,
+    #[serde(default)]
+    tasks: Vec<TodoTask>,
+}
+
+/// A task.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TodoTask {
+    title: String,
+    #[serde(default)]
+    status: String,
+    body: Option<ItemBody>,
+    due_date_time: Option<DateTimeTimeZone>,
+    #[serde(default)]
+    categories: Vec<String>,
+    #[serde(default)]
+    checklist_items: Vec<ChecklistItem>,
+}
+
+/// The notes of a task.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemBody {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    content_type: String,
+}
+
+/// A date and time, with its time zone.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DateTimeTimeZone {
+    date_time: String,
+}
+
+/// A step of a task, the subtask.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecklistItem {
+    display_name: String,
+    #[serde(default)]
+    is_checked: bool,
+}
+
+/// Returns the text of an HTML body, without its tags.
+fn html_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Reads the task lists of a Microsoft To Do account, as returned by the Microsoft Graph API.
+///
+/// The document is the response of `GET /me/todo/lists`, each list holding its `tasks` with their
+/// `checklistItems` (listed with `$expand=checklistItems`). Every task becomes a task in the project
+/// of its list, followed by its steps as subtasks, and its categories become labels.
+///
+/// # Arguments
+///
+/// * `input` - The content of the file.
+///
+/// # Returns
+///
+/// A `Result` with the tasks, or a message telling why the file cannot be read.
+pub fn parse(input: &str) -> Result<Vec<ForeignTask>, String> {
+    let lists: Lists =
+        serde_json::from_str(input).map_err(|e| format!("Invalid Microsoft To Do export: {e}."))?;
+
+    let mut tasks = Vec::new();
+
+    for list in lists.value {
+        for task in list.tasks {
+            let notes = match task.body {
+                Some(body) if body.content_type.eq_ignore_ascii_case("html") => {
+                    html_text(&body.content)
+                }
+                Some(body) => body.content,
+                None => String::new(),
+            };
+
+            tasks.push(ForeignTask {
+                row: tasks.len() + 1,
+                title: task.title.clone(),
+                notes,
+                project: vec![list.display_name.clone()],
+                labels: task.categories,
+                due: task
+                    .due_date_time
+                    .and_then(|due| parse_date(&due.date_time)),
+                completed: task.status == "completed",
+                ..Default::default()
+            });
+
+            for item in task.checklist_items {
+                tasks.push(ForeignTask {
+                    row: tasks.len() + 1,
+                    title: item.display_name,
+                    project: vec![list.display_name.clone()],
+                    parent: Some(task.title.clone()),
+                    completed: item.is_checked,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn graph_lists_are_read() {
+        let tasks = parse(include_str!("../../../fixtures/import/microsoft_todo.json")).unwrap();
+
+        assert_eq!(tasks.len(), 5);
+        assert_eq!(tasks[0].title, "Pay the rent");
+        assert_eq!(
+            tasks[0].notes,
+            "Transfer to the new account & keep the receipt"
+        );
+        assert_eq!(tasks[0].project, ["Tasks"]);
+        assert_eq!(tasks[0].labels, ["Red category"]);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(tasks[1].parent.as_deref(), Some("Pay the rent"));
+        assert!(tasks[1].completed);
+        assert!(!tasks[2].completed);
+        assert!(tasks[3].completed);
+        assert_eq!(tasks[4].project, ["Shopping"]);
+        assert_eq!(tasks[4].notes, "Beans, not ground");
+        assert!(!tasks[4].completed);
+    }
+}
@@ -1 +1,9 @@
+pub mod change;
+pub mod event;
+pub mod import;
+pub mod microsoft_todo;
+pub mod provider;
 pub mod task;
+pub mod todoist;
+pub mod transfer;
+pub mod trello;
@@ -0,0 +1,226 @@
+use crate::task::models::transfer::ImportRow;
+use crate::task::models::{microsoft_todo, todoist, trello};
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// Longest task name accepted by the validation of the tasks, in bytes.
+const NAME_MAX: usize = 20;
+
+/// Shortest task description accepted by the validation of the tasks, in bytes.
+const DESCRIPTION_MIN: usize = 5;
+
+/// Longest task description accepted by the validation of the tasks, in bytes.
+const DESCRIPTION_MAX: usize = 200;
+
+/// A service whose export files can be imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportProvider {
+    /// Todoist: the JSON of its Sync API, or the CSV export of a project.
+    Todoist,
+    /// Trello: the JSON export of a board.
+    Trello,
+    /// Microsoft To Do: the lists of the Microsoft Graph API, with their tasks.
+    MicrosoftTodo,
+}
+
+impl ImportProvider {
+    /// Returns the name of the service.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportProvider::Todoist => "Todoist",
+            ImportProvider::Trello => "Trello",
+            ImportProvider::MicrosoftTodo => "Microsoft To Do",
+        }
+    }
+}
+
+impl FromStr for ImportProvider {
+    type Err = String;
+
+    /// Parses the provider from the path segment naming it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "todoist" => Ok(ImportProvider::Todoist),
+            "trello" => Ok(ImportProvider::Trello),
+            "microsoft-todo" => Ok(ImportProvider::MicrosoftTodo),
+            _ => Err(format!(
+                "Unknown import provider `{s}`, expected `todoist`, `trello` or `microsoft-todo`."
+            )),
+        }
+    }
+}
+
+/// A task read from the export of another service.
+///
+/// Projects, labels and subtasks have no counterpart among the tasks, so they are kept in the description.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ForeignTask {
+    /// The position of the task: its line in CSV files, its position (from 1) in the tasks of JSON files.
+    pub row: usize,
+    /// The title of the task.
+    pub title: String,
+    /// The notes of the task.
+    pub notes: String,
+    /// The path of the project holding the task, such as a board and its list.
+    pub project: Vec<String>,
+    /// The labels of the task.
+    pub labels: Vec<String>,
+    /// The title of the parent task, when the task is a subtask.
+    pub parent: Option<String>,
+    /// The due date of the task.
+    pub due: Option<NaiveDate>,
+    /// The due date as written by the service, when it is not a plain date, such as a recurrence.
+    pub due_text: Option<String>,
+    /// Whether the task is completed.
+    pub completed: bool,
+}
+
+impl ForeignTask {
+    /// Maps the task onto the fields of a task of the user.
+    ///
+    /// A title too long for a name is shortened, and kept whole as the first line of the description.
+    /// The description lists the notes, project, labels, parent and due date text of the task,
+    /// or the service it comes from when there is nothing else to tell.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The service the task comes from.
+    ///
+    /// # Returns
+    ///
+    /// The `ImportRow` of the task, validated as any imported row.
+    pub fn into_row(self, provider: ImportProvider) -> ImportRow {
+        let title = self.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut lines = Vec::new();
+
+        if title.len() > NAME_MAX {
+            lines.push(title.clone());
+        }
+        if !self.notes.trim().is_empty() {
+            lines.push(self.notes.trim().to_string());
+        }
+        if !self.project.is_empty() {
+            lines.push(format!("Project: {}", self.project.join(" / ")));
+        }
+        if !self.labels.is_empty() {
+            lines.push(format!("Labels: {}", self.labels.join(", ")));
+        }
+        if let Some(parent) = self.parent {
+            lines.push(format!("Subtask of: {}", parent.trim()));
+        }
+        if let Some(due_text) = self.due_text {
+            lines.push(format!("Due: {}", due_text.trim()));
+        }
+        if lines.join("\n").len() < DESCRIPTION_MIN {
+            lines.push(format!("Imported from {}", provider.name()));
+        }
+
+        let mut fields = Map::new();
+        fields.insert("name".into(), shorten(&title, NAME_MAX).into());
+        fields.insert(
+            "description".into(),
+            shorten(&lines.join("\n"), DESCRIPTION_MAX).into(),
+        );
+        fields.insert("is_active".into(), self.completed.into());
+        if let Some(due) = self.due {
+            fields.insert("due_date".into(), due.to_string().into());
+        }
+
+        ImportRow {
+            row: self.row,
+            fields: Ok(Value::Object(fields)),
+        }
+    }
+}
+
+/// Shortens text to at most `max` bytes, ending it with an ellipsis when it is cut.
+fn shorten(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+
+    let mut end = max - '…'.len_utf8();
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    format!("{}…", text[..end].trim_end())
+}
+
+/// Reads the date at the start of a date or a timestamp, such as `2026-10-18T09:00:00Z`.
+pub(crate) fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Reads the tasks of the export of another service.
+///
+/// # Arguments
+///
+/// * `provider` - The service the file was exported from.
+/// * `input` - The content of the file.
+///
+/// # Returns
+///
+/// A `Result` with the rows, or a message telling why the file cannot be read at all.
+pub fn parse(provider: ImportProvider, input: &str) -> Result<Vec<ImportRow>, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let tasks = match provider {
+        ImportProvider::Todoist => todoist::parse(input)?,
+        ImportProvider::Trello => trello::parse(input)?,
+        ImportProvider::MicrosoftTodo => microsoft_todo::parse(input)?,
+    };
+
+    Ok(tasks
+        .into_iter()
+        .map(|task| task.into_row(provider))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_titles_are_kept_in_the_description() {
+        let task = ForeignTask {
+            row: 3,
+            title: "Renew the passport before   the trip".to_string(),
+            project: vec!["Personal".to_string(), "Travel".to_string()],
+            labels: vec!["errand".to_string()],
+            parent: Some("Plan the trip".to_string()),
+            due: NaiveDate::from_ymd_opt(2026, 11, 2),
+            completed: true,
+            ..Default::default()
+        };
+
+        let row = task.into_row(ImportProvider::Todoist);
+        let fields = row.fields.unwrap();
+
+        assert_eq!(row.row, 3);
+        assert_eq!(fields["name"], "Renew the passpor…");
+        assert_eq!(
+            fields["description"],
+            "Renew the passport before the trip\nProject: Personal / Travel\n\
+             Labels: errand\nSubtask of: Plan the trip"
+        );
+        assert_eq!(fields["is_active"], true);
+        assert_eq!(fields["due_date"], "2026-11-02");
+    }
+
+    #[test]
+    fn empty_descriptions_name_the_provider() {
+        let task = ForeignTask {
+            title: "Call mom".to_string(),
+            notes: "  ".to_string(),
+            ..Default::default()
+        };
+
+        let fields = task.into_row(ImportProvider::MicrosoftTodo).fields.unwrap();
+
+        assert_eq!(fields["description"], "Imported from Microsoft To Do");
+        assert!(fields.get("due_date").is_none());
+        assert_eq!(shorten("déjà vu", 6), "dé…");
+    }
+}
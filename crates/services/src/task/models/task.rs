@@ -1,10 +1,12 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use database::entities::task::Model;
+use database::entities::task::{Model, TaskPriority};
 use database::entities::user as UserEntity;
 
 /// Struct representing a Task with associated user information.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TaskModel {
     /// The unique identifier of the task.
     pub id: i32,
@@ -16,6 +18,22 @@ pub struct TaskModel {
     pub is_active: bool,
     /// The ID of the user associated with the task.
     pub user_id: i32,
+    /// The date the task is due, if any.
+    pub due_date: Option<NaiveDate>,
+    /// The priority of the task.
+    pub priority: TaskPriority,
+    /// The tags of the task.
+    pub tags: Vec<String>,
+    /// The project of the task, if any.
+    pub project: Option<String>,
+    /// Revision number of the task, increased on every change and used as its `ETag`.
+    pub version: i32,
+    /// When the task was created.
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// When the task was last changed.
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
     /// Optional user entity associated with the task.
     pub user: Option<UserEntity::Model>,
 }
@@ -41,6 +59,13 @@ impl From<(Model, Option<UserEntity::Model>)> for TaskModel {
             description: task_entity.description,
             is_active: task_entity.is_active,
             user_id: task_entity.user_id,
+            due_date: task_entity.due_date,
+            priority: task_entity.priority,
+            tags: task_entity.tags,
+            project: task_entity.project,
+            version: task_entity.version,
+            created_at: task_entity.created_at,
+            updated_at: task_entity.updated_at,
             user: user_entity,
         }
     }
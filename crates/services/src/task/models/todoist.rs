@@ -0,0 +1,249 @@
+use crate::task::models::provider::{parse_date, ForeignTask};
+use crate::task::models::transfer::csv_records;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The data returned by a full sync of the Todoist Sync API.
+#[derive(Deserialize)]
+struct Backup {
+    #[serde(default)]
+    projects: Vec<Container>,
+    #[serde(default)]
+    sections: Vec<Container>,
+    items: Vec<Item>,
+}
+
+/// A project or a section.
+#[derive(Deserialize)]
+struct Container {
+    id: Value,
+    name: String,
+}
+
+/// A task.
+#[derive(Deserialize)]
+struct Item {
+    id: Value,
+    content: String,
+    #[serde(default)]
+    description: String,
+    project_id: Option<Value>,
+    section_id: Option<Value>,
+    parent_id: Option<Value>,
+    #[serde(default)]
+    labels: Vec<String>,
+    due: Option<Due>,
+    #[serde(default)]
+    checked: bool,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+/// The due date of a task.
+#[derive(Deserialize)]
+struct Due {
+    date: String,
+    string: Option<String>,
+    #[serde(default)]
+    is_recurring: bool,
+}
+
+/// Returns an ID as text, Todoist having sent them as numbers before the version 9 of its API.
+fn id(value: &Value) -> String {
+    match value {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+/// Reads a Todoist export: the JSON of a full sync of its Sync API (`projects`, `sections`, `items`),
+/// or the CSV export of a project.
+///
+/// # Arguments
+///
+/// * `input` - The content of the file.
+///
+/// # Returns
+///
+/// A `Result` with the tasks, or a message telling why the file cannot be read.
+pub fn parse(input: &str) -> Result<Vec<ForeignTask>, String> {
+    match input.trim_start().starts_with('{') {
+        true => parse_json(input),
+        false => parse_csv(input),
+    }
+}
+
+/// Reads the JSON of a full sync, skipping the deleted tasks.
+fn parse_json(input: &str) -> Result<Vec<ForeignTask>, String> {
+    let backup: Backup =
+        serde_json::from_str(input).map_err(|e| format!("Invalid Todoist export: {e}."))?;
+
+    let names = |containers: &[Container]| {
+        containers
+            .iter()
+            .map(|container| (id(&container.id), container.name.clone()))
+            .collect::<HashMap<_, _>>()
+    };
+    let projects = names(&backup.projects);
+    let sections = names(&backup.sections);
+    let titles = backup
+        .items
+        .iter()
+        .map(|item| (id(&item.id), item.content.clone()))
+        .collect::<HashMap<_, _>>();
+
+    Ok(backup
+        .items
+        .into_iter()
+        .filter(|item| !item.is_deleted)
+        .enumerate()
+        .map(|(i, item)| {
+            let lookup = |names: &HashMap<String, String>, key: &Option<Value>| {
+                key.as_ref().and_then(|key| names.get(&id(key))).cloned()
+            };
+
+            ForeignTask {
+                row: i + 1,
+                project: [
+                    lookup(&projects, &item.project_id),
+                    lookup(&sections, &item.section_id),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+                parent: lookup(&titles, &item.parent_id),
+                due: item.due.as_ref().and_then(|due| parse_date(&due.date)),
+                due_text: item
+                    .due
+                    .filter(|due| due.is_recurring)
+                    .and_then(|due| due.string),
+                title: item.content,
+                notes: item.description,
+                labels: item.labels,
+                completed: item.checked,
+            }
+        })
+        .collect())
+}
+
+/// Reads the CSV export of a project, its sections becoming projects and the indented tasks subtasks.
+///
+/// The export lists the open tasks only. The comments of a task follow it in `note` rows, and its labels
+/// are written in its content as `@label`.
+fn parse_csv(input: &str) -> Result<Vec<ForeignTask>, String> {
+    let mut records = csv_records(input)?.into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or("The Todoist export must start with a header row.")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name))
+    };
+
+    let (Some(kind), Some(content)) = (column("TYPE"), column("CONTENT")) else {
+        return Err("The Todoist export must have `TYPE` and `CONTENT` columns.".to_string());
+    };
+    let (description, indent, date) = (column("DESCRIPTION"), column("INDENT"), column("DATE"));
+
+    let mut tasks: Vec<ForeignTask> = Vec::new();
+    let mut section = None;
+    // The open parents of the current task, with their indent
+    let mut parents: Vec<(usize, String)> = Vec::new();
+
+    for (line, record) in records {
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .map(|cell| cell.trim())
+                .unwrap_or_default()
+        };
+
+        match cell(Some(kind)) {
+            "section" => {
+                section = Some(cell(Some(content)).to_string());
+                parents.clear();
+            }
+            "task" => {
+                let (labels, words): (Vec<_>, Vec<_>) = cell(Some(content))
+                    .split_whitespace()
+                    .partition(|word| word.len() > 1 && word.starts_with('@'));
+                let title = words.join(" ");
+                let level = cell(indent).parse().unwrap_or(1);
+
+                parents.retain(|(indent, _)| *indent < level);
+                let due = cell(date);
+
+                tasks.push(ForeignTask {
+                    row: line,
+                    notes: cell(description).to_string(),
+                    project: section.iter().cloned().collect(),
+                    labels: labels.iter().map(|label| label[1..].to_string()).collect(),
+                    parent: parents.last().map(|(_, title)| title.clone()),
+                    due: parse_date(due),
+                    due_text: Some(due.to_string())
+                        .filter(|due| !due.is_empty() && parse_date(due).is_none()),
+                    title: title.clone(),
+                    ..Default::default()
+                });
+                parents.push((level, title));
+            }
+            "note" => {
+                if let Some(task) = tasks.last_mut() {
+                    let note = cell(Some(content));
+                    task.notes = [task.notes.as_str(), note]
+                        .into_iter()
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn sync_exports_are_read() {
+        let tasks = parse(include_str!("../../../fixtures/import/todoist.json")).unwrap();
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].title, "Buy milk");
+        assert_eq!(tasks[0].notes, "Oat milk if they have it");
+        assert_eq!(tasks[0].project, ["Inbox"]);
+        assert_eq!(tasks[0].labels, ["errand"]);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(tasks[0].due_text, None);
+        assert_eq!(tasks[1].project, ["Home", "Garden"]);
+        assert_eq!(tasks[1].due, NaiveDate::from_ymd_opt(2026, 10, 24));
+        assert_eq!(tasks[1].due_text.as_deref(), Some("every saturday at 9am"));
+        assert_eq!(tasks[2].parent.as_deref(), Some("Mow the lawn"));
+        assert!(tasks[2].completed);
+    }
+
+    #[test]
+    fn csv_exports_are_read() {
+        let tasks = parse(include_str!("../../../fixtures/import/todoist.csv")).unwrap();
+
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].row, 3);
+        assert_eq!(tasks[0].title, "Buy bread");
+        assert_eq!(tasks[0].labels, ["errand"]);
+        assert_eq!(tasks[0].project, ["Groceries"]);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 10, 21));
+        assert_eq!(tasks[1].parent.as_deref(), Some("Buy bread"));
+        assert_eq!(tasks[1].notes, "Bakery closes at 7, go early");
+        assert_eq!(tasks[2].project, ["Chores"]);
+        assert_eq!(tasks[2].parent, None);
+        assert_eq!(tasks[2].labels, ["home", "weekend"]);
+        assert_eq!(tasks[2].due_text.as_deref(), Some("every sunday"));
+    }
+}
@@ -0,0 +1,640 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use database::entities::task::Model as TaskEntity;
+use rocket::FromFormField;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use utoipa::ToSchema;
+
+/// Columns of the CSV files, in order. Only `name`, `description`, `is_active` and `due_date` are imported.
+const CSV_COLUMNS: [&str; 7] = [
+    "id",
+    "name",
+    "description",
+    "is_active",
+    "due_date",
+    "created_at",
+    "updated_at",
+];
+
+/// File format of an export or an import of tasks.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromFormField, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskFormat {
+    /// A JSON array of tasks.
+    #[default]
+    Json,
+    /// CSV with a header row (RFC 4180).
+    Csv,
+    /// A Markdown checklist, the description indented under each task.
+    Md,
+    /// One task per line in the todo.txt format, the description in a `description:` tag.
+    Todotxt,
+}
+
+impl TaskFormat {
+    /// Returns the media type of the files in the format.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            TaskFormat::Json => "application/json",
+            TaskFormat::Csv => "text/csv; charset=utf-8",
+            TaskFormat::Md => "text/markdown; charset=utf-8",
+            TaskFormat::Todotxt => "text/plain; charset=utf-8",
+        }
+    }
+
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TaskFormat::Json => "json",
+            TaskFormat::Csv => "csv",
+            TaskFormat::Md => "md",
+            TaskFormat::Todotxt => "txt",
+        }
+    }
+}
+
+/// A task as written by an export.
+#[derive(Serialize)]
+struct ExportedTask<'a> {
+    id: i32,
+    name: &'a str,
+    description: &'a str,
+    is_active: bool,
+    due_date: Option<NaiveDate>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Writes tasks in a format one at a time, so exports can be streamed.
+pub struct TaskWriter {
+    /// The format written.
+    format: TaskFormat,
+    /// The number of tasks written so far.
+    written: usize,
+}
+
+impl TaskWriter {
+    /// Starts writing tasks in `format`.
+    pub fn new(format: TaskFormat) -> Self {
+        Self { format, written: 0 }
+    }
+
+    /// Returns the text starting the file, such as the CSV header.
+    pub fn begin(&self) -> String {
+        match self.format {
+            TaskFormat::Json => "[".to_string(),
+            TaskFormat::Csv => format!("{}\r\n", CSV_COLUMNS.join(",")),
+            TaskFormat::Md => "# Tasks\n\n".to_string(),
+            TaskFormat::Todotxt => String::new(),
+        }
+    }
+
+    /// Returns the text of a task.
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task.
+    ///
+    /// # Returns
+    ///
+    /// The text of the task, preceded by a separator when the format needs one.
+    pub fn write(&mut self, task: &TaskEntity) -> String {
+        let text = match self.format {
+            TaskFormat::Json => {
+                let exported = ExportedTask {
+                    id: task.id,
+                    name: &task.name,
+                    description: &task.description,
+                    is_active: task.is_active,
+                    due_date: task.due_date,
+                    created_at: task.created_at,
+                    updated_at: task.updated_at,
+                };
+                let separator = match self.written {
+                    0 => "\n  ",
+                    _ => ",\n  ",
+                };
+
+                format!("{separator}{}", json!(exported))
+            }
+            TaskFormat::Csv => {
+                let fields = [
+                    task.id.to_string(),
+                    task.name.clone(),
+                    task.description.clone(),
+                    task.is_active.to_string(),
+                    task.due_date.map(|due| due.to_string()).unwrap_or_default(),
+                    task.created_at.to_rfc3339(),
+                    task.updated_at.to_rfc3339(),
+                ];
+
+                let fields = fields.iter().map(|field| csv_field(field));
+                format!("{}\r\n", fields.collect::<Vec<_>>().join(","))
+            }
+            TaskFormat::Md => markdown_item(task),
+            TaskFormat::Todotxt => todotxt_line(task),
+        };
+
+        self.written += 1;
+        text
+    }
+
+    /// Returns the text ending the file.
+    pub fn finish(&self) -> String {
+        match (self.format, self.written) {
+            (TaskFormat::Json, 0) => "]\n".to_string(),
+            (TaskFormat::Json, _) => "\n]\n".to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// A row read from an imported file, not validated yet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportRow {
+    /// The position of the row: its line in the text formats, its index (from 1) in a JSON array.
+    pub row: usize,
+    /// The task fields as a JSON object (`name`, `description`, `is_active`, `due_date`),
+    /// or the reason the row could not be read.
+    pub fields: Result<Value, String>,
+}
+
+/// Reads the rows of an imported file.
+///
+/// # Arguments
+///
+/// * `format` - The format of the file.
+/// * `input` - The content of the file.
+///
+/// # Returns
+///
+/// A `Result` with the rows, or a message telling why the file cannot be read at all.
+pub fn parse(format: TaskFormat, input: &str) -> Result<Vec<ImportRow>, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    match format {
+        TaskFormat::Json => parse_json(input),
+        TaskFormat::Csv => parse_csv(input),
+        TaskFormat::Md => Ok(parse_markdown(input)),
+        TaskFormat::Todotxt => Ok(parse_todotxt(input)),
+    }
+}
+
+/// Keeps the task fields of an imported object, so unknown members are ignored.
+fn task_fields(object: &Map<String, Value>) -> Value {
+    let fields = ["name", "description", "is_active", "due_date"]
+        .into_iter()
+        .filter_map(|field| Some((field.to_string(), object.get(field)?.clone())))
+        .collect();
+
+    Value::Object(fields)
+}
+
+/// Reads a JSON array of tasks.
+fn parse_json(input: &str) -> Result<Vec<ImportRow>, String> {
+    let Value::Array(items) =
+        serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {e}."))?
+    else {
+        return Err("The JSON document must be an array of tasks.".to_string());
+    };
+
+    Ok(items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| ImportRow {
+            row: i + 1,
+            fields: match item {
+                Value::Object(object) => Ok(task_fields(object)),
+                _ => Err("The row must be a JSON object.".to_string()),
+            },
+        })
+        .collect())
+}
+
+/// Quotes a CSV field when it contains a delimiter, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\r', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Splits CSV text into records, each with the line it starts on.
+pub(crate) fn csv_records(input: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut record)));
+                line += 1;
+                start = line;
+            }
+            (c, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+
+    if quoted {
+        return Err(format!("Unterminated quoted field on line {start}."));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((start, record));
+    }
+
+    // Blank lines are skipped
+    records.retain(|(_, record)| !(record.len() == 1 && record[0].trim().is_empty()));
+
+    Ok(records)
+}
+
+/// Reads CSV with a header row naming the columns, the `name` column being required.
+fn parse_csv(input: &str) -> Result<Vec<ImportRow>, String> {
+    let mut records = csv_records(input)?.into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or("The CSV file must start with a header row.")?;
+    let header = header
+        .iter()
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    if !header.iter().any(|column| column == "name") {
+        return Err("The CSV header must have a `name` column.".to_string());
+    }
+
+    Ok(records
+        .map(|(line, record)| ImportRow {
+            row: line,
+            fields: match record.len() == header.len() {
+                true => {
+                    // Empty cells leave the field to its default
+                    let object = header
+                        .iter()
+                        .zip(record)
+                        .filter(|(_, value)| !value.trim().is_empty())
+                        .map(|(column, value)| (column.clone(), Value::String(value)))
+                        .collect();
+
+                    Ok(task_fields(&object))
+                }
+                false => Err(format!(
+                    "The row has {} fields, the header has {}.",
+                    record.len(),
+                    header.len()
+                )),
+            },
+        })
+        .collect())
+}
+
+/// Replaces the line breaks of a name, which must fit on the line of its task.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Writes a task as a Markdown checklist item, followed by its indented description.
+fn markdown_item(task: &TaskEntity) -> String {
+    let mut item = format!(
+        "- [{}] {}",
+        if task.is_active { "x" } else { " " },
+        single_line(&task.name)
+    );
+
+    if let Some(due) = task.due_date {
+        item.push_str(&format!(" (due: {due})"));
+    }
+    item.push('\n');
+
+    if !task.description.is_empty() {
+        for line in task.description.lines() {
+            item.push_str(&format!("  {line}\n"));
+        }
+    }
+
+    item
+}
+
+/// Reads a Markdown checklist. Top level list items are tasks, checked when marked with `[x]`,
+/// the indented lines below an item are its description and anything else is ignored.
+fn parse_markdown(input: &str) -> Vec<ImportRow> {
+    let mut rows = Vec::new();
+    let mut task: Option<(usize, Map<String, Value>, Vec<String>)> = None;
+
+    let mut flush = |task: Option<(usize, Map<String, Value>, Vec<String>)>| {
+        if let Some((row, mut object, description)) = task {
+            let description = description.join("\n");
+            if !description.trim().is_empty() {
+                object.insert("description".to_string(), json!(description.trim_end()));
+            }
+
+            rows.push(ImportRow {
+                row,
+                fields: Ok(Value::Object(object)),
+            });
+        }
+    };
+
+    for (i, line) in input.lines().enumerate() {
+        if let Some((_, _, description)) = task.as_mut() {
+            if let Some(text) = line.strip_prefix("  ").or(line.strip_prefix('\t')) {
+                description.push(text.to_string());
+                continue;
+            }
+        }
+
+        let item = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| line.strip_prefix(bullet));
+
+        let Some(item) = item else {
+            // A heading, a paragraph or a blank line ends the current task
+            flush(task.take());
+            continue;
+        };
+
+        let (done, text) = match item.get(..4) {
+            Some("[ ] ") => (false, &item[4..]),
+            Some("[x] " | "[X] ") => (true, &item[4..]),
+            _ => (false, item),
+        };
+
+        let mut object = Map::new();
+        let text = text.trim();
+        let name = match text
+            .strip_suffix(')')
+            .and_then(|text| text.rsplit_once(" (due: "))
+        {
+            Some((name, due)) => {
+                object.insert("due_date".to_string(), json!(due.trim()));
+                name
+            }
+            None => text,
+        };
+        object.insert("name".to_string(), json!(name));
+        object.insert("is_active".to_string(), json!(done));
+
+        flush(task.replace((i + 1, object, Vec::new())));
+    }
+
+    flush(task);
+    rows
+}
+
+/// Percent-encodes the characters a todo.txt tag value cannot hold.
+fn encode_tag(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '%' => encoded.push_str("%25"),
+            c if c.is_whitespace() || c.is_control() => {
+                let mut bytes = [0; 4];
+                for byte in c.encode_utf8(&mut bytes).bytes() {
+                    encoded.push_str(&format!("%{byte:02X}"));
+                }
+            }
+            c => encoded.push(c),
+        }
+    }
+
+    encoded
+}
+
+/// Decodes a percent-encoded todo.txt tag value.
+fn decode_tag(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = match (byte, tail.get(..2)) {
+            (b'%', Some(hex)) => std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Writes a task as a todo.txt line: the completion mark and date, the creation date, the name
+/// and the `due:` and `description:` tags.
+fn todotxt_line(task: &TaskEntity) -> String {
+    let mut line = String::new();
+
+    if task.is_active {
+        line.push_str(&format!("x {} ", task.updated_at.date_naive()));
+    }
+    line.push_str(&format!(
+        "{} {}",
+        task.created_at.date_naive(),
+        single_line(&task.name)
+    ));
+    if let Some(due) = task.due_date {
+        line.push_str(&format!(" due:{due}"));
+    }
+    if !task.description.is_empty() {
+        line.push_str(&format!(" description:{}", encode_tag(&task.description)));
+    }
+    line.push('\n');
+
+    line
+}
+
+/// Reads todo.txt lines. Priorities and dates are read past, `+project`, `@context` and the
+/// other tags are kept in the name.
+fn parse_todotxt(input: &str) -> Vec<ImportRow> {
+    let is_date = |word: &str| NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok();
+
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let mut words = line.split_whitespace().peekable();
+            let mut object = Map::new();
+
+            let done = words.next_if_eq(&"x").is_some();
+            object.insert("is_active".to_string(), json!(done));
+
+            // The priority, then the completion and creation dates
+            words.next_if(|word| {
+                word.len() == 3
+                    && word.starts_with('(')
+                    && word.ends_with(')')
+                    && word.as_bytes()[1].is_ascii_uppercase()
+            });
+            words.next_if(|word| is_date(word));
+            words.next_if(|word| is_date(word));
+
+            let mut name = Vec::new();
+            for word in words {
+                match word.split_once(':') {
+                    Some(("due", due)) => {
+                        object.insert("due_date".to_string(), json!(due));
+                    }
+                    Some(("description", description)) => {
+                        object.insert("description".to_string(), json!(decode_tag(description)));
+                    }
+                    _ => name.push(word),
+                }
+            }
+            object.insert("name".to_string(), json!(name.join(" ")));
+
+            ImportRow {
+                row: i + 1,
+                fields: Ok(Value::Object(object)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn task(id: i32, name: &str, description: &str, is_active: bool) -> TaskEntity {
+        TaskEntity {
+            id,
+            name: name.to_string(),
+            description: description.to_string(),
+            is_active,
+            user_id: 1,
+            due_date: NaiveDate::from_ymd_opt(2026, 11, 2),
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 1,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 1, 8, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap(),
+            change_seq: 1,
+            created_seq: 1,
+        }
+    }
+
+    fn export(format: TaskFormat, tasks: &[TaskEntity]) -> String {
+        let mut writer = TaskWriter::new(format);
+        let mut text = writer.begin();
+        for task in tasks {
+            text.push_str(&writer.write(task));
+        }
+        text.push_str(&writer.finish());
+        text
+    }
+
+    fn fields(rows: Vec<ImportRow>) -> Vec<Value> {
+        rows.into_iter().map(|row| row.fields.unwrap()).collect()
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let tasks = [
+            task(1, "Groceries", "Milk, \"eggs\"\nand  100% bread", false),
+            task(2, "Taxes", "File the return", true),
+        ];
+        let expected = vec![
+            json!({ "name": "Groceries", "description": "Milk, \"eggs\"\nand  100% bread",
+                "is_active": false, "due_date": "2026-11-02" }),
+            json!({ "name": "Taxes", "description": "File the return",
+                "is_active": true, "due_date": "2026-11-02" }),
+        ];
+
+        for format in [TaskFormat::Json, TaskFormat::Md, TaskFormat::Todotxt] {
+            let rows = parse(format, &export(format, &tasks)).unwrap();
+            assert_eq!(fields(rows), expected, "{format:?}");
+        }
+
+        // CSV cells are text, the API parses them like form fields
+        let rows = parse(TaskFormat::Csv, &export(TaskFormat::Csv, &tasks)).unwrap();
+        assert_eq!(rows[1].row, 4);
+        assert_eq!(
+            fields(rows)[0],
+            json!({ "name": "Groceries", "description": "Milk, \"eggs\"\nand  100% bread",
+                "is_active": "false", "due_date": "2026-11-02" })
+        );
+    }
+
+    #[test]
+    fn empty_json_exports_are_valid() {
+        assert_eq!(export(TaskFormat::Json, &[]), "[]\n");
+        assert_eq!(parse(TaskFormat::Json, "[]\n"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn csv_rows_are_checked_against_the_header() {
+        let csv = "Name,Due_Date\nCall mum,\n\nWrite,2026-10-20,extra\n";
+        let rows = parse(TaskFormat::Csv, csv).unwrap();
+
+        assert_eq!(rows[0].fields, Ok(json!({ "name": "Call mum" })));
+        assert_eq!(rows[1].row, 4);
+        assert!(rows[1].fields.is_err());
+        assert!(parse(TaskFormat::Csv, "title\nCall mum\n").is_err());
+        assert!(parse(TaskFormat::Csv, "name\n\"Call mum\n").is_err());
+    }
+
+    #[test]
+    fn foreign_files_are_read() {
+        let md = "# Week\n\n- [X] Call mum\n  about Sunday\n\nSome notes\n* Write report (due: 2026-10-20)\n";
+        assert_eq!(
+            fields(parse(TaskFormat::Md, md).unwrap()),
+            vec![
+                json!({ "name": "Call mum", "description": "about Sunday", "is_active": true }),
+                json!({ "name": "Write report", "due_date": "2026-10-20", "is_active": false }),
+            ]
+        );
+
+        let todotxt = "(A) 2026-10-01 Call mum +family @phone due:2026-10-20 t:2026-10-19\n\n\
+                       x 2026-10-18 2026-10-01 Write report\n";
+        let rows = parse(TaskFormat::Todotxt, todotxt).unwrap();
+        assert_eq!(rows[1].row, 3);
+        assert_eq!(
+            fields(rows),
+            vec![
+                json!({ "name": "Call mum +family @phone t:2026-10-19", "due_date": "2026-10-20", "is_active": false }),
+                json!({ "name": "Write report", "is_active": true }),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_rejected() {
+        assert!(parse(TaskFormat::Json, "{\"name\": \"x\"}").is_err());
+        assert!(parse(TaskFormat::Json, "[").is_err());
+        assert!(parse(TaskFormat::Json, "[1]").unwrap()[0].fields.is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::task::models::provider::{parse_date, ForeignTask};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The JSON export of a Trello board.
+#[derive(Deserialize)]
+struct Board {
+    name: String,
+    #[serde(default)]
+    lists: Vec<List>,
+    cards: Vec<Card>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+}
+
+/// A list of the board.
+#[derive(Deserialize)]
+struct List {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+}
+
+/// A card, the task.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    labels: Vec<Label>,
+    due: Option<String>,
+    #[serde(default)]
+    due_complete: bool,
+    #[serde(default)]
+    closed: bool,
+}
+
+/// A label of a card.
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    name: String,
+    color: Option<String>,
+}
+
+/// A checklist of a card, its items being the subtasks.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checklist {
+    id_card: String,
+    #[serde(default)]
+    check_items: Vec<CheckItem>,
+}
+
+/// An item of a checklist.
+#[derive(Deserialize)]
+struct CheckItem {
+    name: String,
+    state: String,
+    due: Option<String>,
+    #[serde(default)]
+    pos: f64,
+}
+
+/// Reads the JSON export of a Trello board.
+///
+/// Every card becomes a task in the project of its board and list, followed by the items of its checklists
+/// as subtasks. Cards marked complete are completed, as are archived cards and the cards of archived lists.
+///
+/// # Arguments
+///
+/// * `input` - The content of the file.
+///
+/// # Returns
+///
+/// A `Result` with the tasks, or a message telling why the file cannot be read.
+pub fn parse(input: &str) -> Result<Vec<ForeignTask>, String> {
+    let board: Board =
+        serde_json::from_str(input).map_err(|e| format!("Invalid Trello export: {e}."))?;
+
+    let lists = board
+        .lists
+        .iter()
+        .map(|list| (list.id.as_str(), list))
+        .collect::<HashMap<_, _>>();
+    let mut checklists: HashMap<&str, Vec<&CheckItem>> = HashMap::new();
+    for checklist in &board.checklists {
+        checklists
+            .entry(checklist.id_card.as_str())
+            .or_default()
+            .extend(&checklist.check_items);
+    }
+
+    let mut tasks = Vec::new();
+
+    for card in &board.cards {
+        let list = lists.get(card.id_list.as_str());
+        let project = [Some(board.name.clone()), list.map(|list| list.name.clone())]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        tasks.push(ForeignTask {
+            row: tasks.len() + 1,
+            title: card.name.clone(),
+            notes: card.desc.clone(),
+            project: project.clone(),
+            // Labels may only have a color
+            labels: card
+                .labels
+                .iter()
+                .filter_map(|label| match label.name.trim() {
+                    "" => label.color.clone(),
+                    name => Some(name.to_string()),
+                })
+                .collect(),
+            due: card.due.as_deref().and_then(parse_date),
+            completed: card.due_complete || card.closed || list.is_some_and(|list| list.closed),
+            ..Default::default()
+        });
+
+        let mut items = checklists.remove(card.id.as_str()).unwrap_or_default();
+        items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+        for item in items {
+            tasks.push(ForeignTask {
+                row: tasks.len() + 1,
+                title: item.name.clone(),
+                project: project.clone(),
+                parent: Some(card.name.clone()),
+                due: item.due.as_deref().and_then(parse_date),
+                completed: item.state == "complete",
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn board_exports_are_read() {
+        let tasks = parse(include_str!("../../../fixtures/import/trello.json")).unwrap();
+        let titles = tasks
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            titles,
+            [
+                "Book the van",
+                "Compare prices",
+                "Pick a date",
+                "Cancel the internet",
+                "Sell the sofa"
+            ]
+        );
+        assert_eq!(tasks[0].project, ["Moving house", "To do"]);
+        assert_eq!(tasks[0].labels, ["urgent", "green"]);
+        assert_eq!(tasks[0].due, NaiveDate::from_ymd_opt(2026, 11, 5));
+        assert!(!tasks[0].completed);
+        assert_eq!(tasks[1].parent.as_deref(), Some("Book the van"));
+        assert!(tasks[1].completed);
+        assert_eq!(tasks[2].due, NaiveDate::from_ymd_opt(2026, 10, 30));
+        assert!(tasks[3].completed);
+        assert!(tasks[4].completed);
+        assert_eq!(tasks[4].row, 5);
+    }
+
+    #[test]
+    fn other_documents_are_rejected() {
+        assert!(parse("[]").is_err());
+        assert!(parse("{\"name\": \"Board\"}").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::ApiError;
+use chrono::Utc;
+use database::entities::task::Model as TaskModel;
+use database::entities::task_event::{ActiveModel, Model};
+use sea_orm::*;
+use serde_json::{json, Map, Value};
+
+/// Struct for recording the events of the task history.
+pub struct TaskEventMutation;
+
+/// Kind of change recorded in the task history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskAction {
+    /// The task was created.
+    Created,
+    /// Fields of the task other than the active flag changed.
+    Updated,
+    /// Only the active flag of the task changed.
+    StatusChanged,
+    /// The task was deleted.
+    Deleted,
+    /// The task was reverted to an earlier revision.
+    Reverted,
+}
+
+impl TaskAction {
+    /// Returns the name stored in the `action` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskAction::Created => "created",
+            TaskAction::Updated => "updated",
+            TaskAction::StatusChanged => "status_changed",
+            TaskAction::Deleted => "deleted",
+            TaskAction::Reverted => "reverted",
+        }
+    }
+}
+
+/// Fields of a task that are not part of the history, as they never change or change on every write.
+const UNTRACKED_FIELDS: [&str; 5] = ["id", "user_id", "version", "created_at", "updated_at"];
+
+/// Serializes the tracked fields of a task, an absent task has no fields.
+fn tracked_fields(task: Option<&TaskModel>) -> Map<String, Value> {
+    match task.map(serde_json::to_value) {
+        Some(Ok(Value::Object(mut fields))) => {
+            fields.retain(|field, _| !UNTRACKED_FIELDS.contains(&field.as_str()));
+            fields
+        }
+        _ => Map::new(),
+    }
+}
+
+impl TaskEventMutation {
+    /// Computes the field-level diff between two states of a task.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The task before the change, `None` when it is created.
+    /// * `after` - The task after the change, `None` when it is deleted.
+    ///
+    /// # Returns
+    ///
+    /// A JSON object with an `{"old": ..., "new": ...}` member for every changed field.
+    pub fn diff(before: Option<&TaskModel>, after: Option<&TaskModel>) -> Map<String, Value> {
+        let before = tracked_fields(before);
+        let after = tracked_fields(after);
+
+        before
+            .keys()
+            .chain(after.keys().filter(|field| !before.contains_key(*field)))
+            .filter_map(|field| {
+                let old = before.get(field).cloned().unwrap_or(Value::Null);
+                let new = after.get(field).cloned().unwrap_or(Value::Null);
+
+                (old != new).then(|| (field.clone(), json!({ "old": old, "new": new })))
+            })
+            .collect()
+    }
+
+    /// Asynchronously records a change of a task in its history.
+    ///
+    /// Updates changing only the active flag are recorded as `status_changed`,
+    /// and updates changing nothing are not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The kind of change.
+    /// * `before` - The task before the change, `None` when it is created.
+    /// * `after` - The task after the change, `None` when it is deleted.
+    /// * `user_id` - The ID of the user making the change.
+    /// * `request_id` - The ID of the request making the change, if known.
+    /// * `db` - SeaORM connection or transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the recorded event (`None` if nothing changed) on success, or an `ApiError` on failure.
+    pub async fn record<C: ConnectionTrait>(
+        action: TaskAction,
+        before: Option<&TaskModel>,
+        after: Option<&TaskModel>,
+        user_id: i32,
+        request_id: Option<String>,
+        db: &C,
+    ) -> Result<Option<Model>, ApiError> {
+        let changes = Self::diff(before, after);
+
+        let action = match action {
+            _ if changes.is_empty() => return Ok(None),
+            TaskAction::Updated if changes.keys().all(|field| field == "is_active") => {
+                TaskAction::StatusChanged
+            }
+            action => action,
+        };
+
+        let task_id = after.or(before).map(|task| task.id).unwrap_or_default();
+
+        // Insert the event, dated like the change it records, and return the stored row
+        let event = ActiveModel {
+            task_id: Set(task_id),
+            user_id: Set(user_id),
+            action: Set(action.as_str().to_string()),
+            changes: Set(Value::Object(changes)),
+            request_id: Set(request_id),
+            created_at: Set(after.map_or_else(Utc::now, |task| task.updated_at)),
+            ..Default::default()
+        };
+
+        Ok(Some(event.insert(db).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn task() -> TaskModel {
+        TaskModel {
+            id: 1,
+            name: "Buy milk".to_string(),
+            description: "Two litres".to_string(),
+            is_active: false,
+            user_id: 1,
+            due_date: None,
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 1,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 1, 8, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 10, 1, 8, 0, 0).unwrap(),
+            change_seq: 1,
+            created_seq: 1,
+        }
+    }
+
+    #[test]
+    fn timestamps_and_versions_are_not_changes() {
+        let before = task();
+        let after = TaskModel {
+            version: 2,
+            updated_at: before.updated_at + Duration::hours(1),
+            change_seq: 2,
+            ..before.clone()
+        };
+
+        assert!(TaskEventMutation::diff(Some(&before), Some(&after)).is_empty());
+    }
+
+    #[test]
+    fn changed_fields_keep_their_old_and_new_values() {
+        let before = task();
+        let after = TaskModel {
+            name: "Buy oat milk".to_string(),
+            is_active: true,
+            version: 2,
+            ..before.clone()
+        };
+
+        let changes = TaskEventMutation::diff(Some(&before), Some(&after));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes["name"],
+            json!({ "old": "Buy milk", "new": "Buy oat milk" })
+        );
+        assert_eq!(changes["is_active"], json!({ "old": false, "new": true }));
+    }
+
+    #[test]
+    fn created_and_deleted_tasks_change_every_tracked_field() {
+        let created = TaskEventMutation::diff(None, Some(&task()));
+        let deleted = TaskEventMutation::diff(Some(&task()), None);
+
+        // The due date and the project are null on both sides, so they do not change
+        for changes in [&created, &deleted] {
+            let mut fields: Vec<_> = changes.keys().map(String::as_str).collect();
+            fields.sort();
+            assert_eq!(
+                fields,
+                ["description", "is_active", "name", "priority", "tags"]
+            );
+        }
+        assert_eq!(created["name"], json!({ "old": null, "new": "Buy milk" }));
+        assert_eq!(deleted["name"], json!({ "old": "Buy milk", "new": null }));
+    }
+}
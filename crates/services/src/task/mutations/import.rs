@@ -0,0 +1,126 @@
+use crate::error::ApiError;
+use crate::events::TaskEvents;
+use crate::task::models::change::TaskChange;
+use crate::task::models::import::{
+    ImportCandidate, ImportReport, ImportStatus, ImportedRow, MAX_IMPORT_ROWS,
+};
+use crate::task::mutations::task::TaskMutation;
+use chrono::NaiveDate;
+use database::entities::task::{Column, Entity};
+use sea_orm::*;
+use std::collections::HashSet;
+
+/// Struct for handling the imports of tasks.
+pub struct TaskImportMutation;
+
+/// Key identifying a task when looking for duplicates: the name ignoring case and spaces, and the due date.
+fn duplicate_key(name: &str, due_date: Option<NaiveDate>) -> (String, Option<NaiveDate>) {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    (name.to_lowercase(), due_date)
+}
+
+impl TaskImportMutation {
+    /// Asynchronously imports tasks, all of them or none in a single transaction.
+    ///
+    /// Rows matching a task of the user, or an earlier row, by name and due date are skipped as duplicates.
+    /// When any row is invalid, or on a dry run, nothing is written and the report tells what the import would do.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - The validated rows, in order.
+    /// * `dry_run` - Whether to only check the rows.
+    /// * `user_id` - The ID of the user importing the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ImportReport` on success, or an `ApiError` on failure.
+    pub async fn import(
+        candidates: Vec<ImportCandidate>,
+        dry_run: bool,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<ImportReport, ApiError> {
+        if candidates.len() > MAX_IMPORT_ROWS {
+            return Err(ApiError::Validation(format!(
+                "An import is limited to {} tasks.",
+                MAX_IMPORT_ROWS
+            )));
+        }
+
+        let txn = db.begin().await?;
+
+        // The tasks of the user, read in the transaction writing the new ones
+        let mut seen = Entity::find()
+            .select_only()
+            .column(Column::Name)
+            .column(Column::DueDate)
+            .filter(Column::UserId.eq(user_id))
+            .into_tuple::<(String, Option<NaiveDate>)>()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|(name, due_date)| duplicate_key(&name, due_date))
+            .collect::<HashSet<_>>();
+
+        let mut report = ImportReport {
+            dry_run,
+            total: candidates.len(),
+            created: 0,
+            duplicates: 0,
+            invalid: 0,
+            rows: Vec::with_capacity(candidates.len()),
+        };
+        let mut payloads = Vec::new();
+
+        // Sort the rows out before writing anything
+        for candidate in candidates {
+            let (status, name, errors) = match candidate.task {
+                Err(errors) => {
+                    report.invalid += 1;
+                    (ImportStatus::Invalid, None, errors)
+                }
+                Ok(payload) => {
+                    let name = Some(payload.name.clone());
+
+                    match seen.insert(duplicate_key(&payload.name, payload.due_date)) {
+                        true => {
+                            report.created += 1;
+                            payloads.push((report.rows.len(), payload));
+                            (ImportStatus::Created, name, Vec::new())
+                        }
+                        false => {
+                            report.duplicates += 1;
+                            (ImportStatus::Duplicate, name, Vec::new())
+                        }
+                    }
+                }
+            };
+
+            report.rows.push(ImportedRow {
+                row: candidate.row,
+                status,
+                name,
+                task_id: None,
+                errors,
+            });
+        }
+
+        if dry_run || report.invalid > 0 {
+            txn.rollback().await?;
+            return Ok(report);
+        }
+
+        // Create the tasks, publishing their changes once they are all committed
+        let mut changes = Vec::with_capacity(payloads.len());
+        for (index, payload) in payloads {
+            let task = TaskMutation::insert(payload, &txn).await?;
+            report.rows[index].task_id = Some(task.id);
+            changes.push(TaskChange::created(&task));
+        }
+        TaskEvents::commit_all(txn, changes).await?;
+
+        Ok(report)
+    }
+}
@@ -1 +1,3 @@
+pub mod event;
+pub mod import;
 pub mod task;
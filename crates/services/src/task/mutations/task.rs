@@ -1,4 +1,14 @@
-use database::entities::task::{ActiveModel, Entity, Model};
+use crate::error::ApiError;
+use crate::etag::{etag, EntityTags};
+use crate::events::TaskEvents;
+use crate::idempotency::models::idempotency_key::StoredResponse;
+use crate::idempotency::mutations::idempotency_key::IdempotencyMutation;
+use crate::task::models::change::TaskChange;
+use crate::task::mutations::event::{TaskAction, TaskEventMutation};
+use crate::task::queries::event::TaskEventQueries;
+use chrono::NaiveDate;
+use database::entities::task::{ActiveModel, Column, Entity, Model, TaskPriority};
+use database::entities::task_tombstone;
 use sea_orm::*;
 
 /// Struct for handling mutations (create, update, delete) on tasks.
@@ -12,8 +22,101 @@ pub struct TaskPayload {
     pub description: String,
     /// Indicates whether the task is active or not.
     pub is_active: bool,
+    /// The date the task is due, if any.
+    pub due_date: Option<NaiveDate>,
+    /// The priority of the task, `None` keeps the stored one (`normal` for a new task).
+    pub priority: Option<TaskPriority>,
+    /// The tags of the task, `None` keeps the stored ones (none for a new task).
+    pub tags: Option<Vec<String>>,
+    /// The project of the task, `None` keeps the stored one and `Some(None)` removes it.
+    pub project: Option<Option<String>>,
     /// The ID of the user associated with the task.
     pub user_id: i32,
+    /// The ID of the request making the change, recorded in the task history.
+    pub request_id: Option<String>,
+    /// The `If-Match` precondition of the request, if any.
+    pub if_match: Option<EntityTags>,
+}
+
+/// Payload structure for partially updating a task.
+///
+/// Only the fields set to `Some` are written, the rest keep their stored values.
+pub struct TaskPatchPayload {
+    /// The new name of the task, if it changes.
+    pub name: Option<String>,
+    /// The new description of the task, if it changes.
+    pub description: Option<String>,
+    /// The new active flag of the task, if it changes.
+    pub is_active: Option<bool>,
+    /// The new due date of the task, if it changes. `Some(None)` removes the due date.
+    pub due_date: Option<Option<NaiveDate>>,
+    /// The new priority of the task, if it changes.
+    pub priority: Option<TaskPriority>,
+    /// The new tags of the task, if they change.
+    pub tags: Option<Vec<String>>,
+    /// The new project of the task, if it changes. `Some(None)` removes the project.
+    pub project: Option<Option<String>>,
+    /// The ID of the user associated with the task.
+    pub user_id: i32,
+    /// The ID of the request making the change, recorded in the task history.
+    pub request_id: Option<String>,
+    /// The `If-Match` precondition of the request, if any.
+    pub if_match: Option<EntityTags>,
+}
+
+impl TaskPatchPayload {
+    /// Returns whether the payload changes no field.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.is_active.is_none()
+            && self.due_date.is_none()
+            && self.priority.is_none()
+            && self.tags.is_none()
+            && self.project.is_none()
+    }
+}
+
+/// Checks the `If-Match` precondition of a request against the current revision of a task.
+///
+/// # Arguments
+///
+/// * `task` - The task as currently stored.
+/// * `if_match` - The `If-Match` precondition of the request, if any.
+///
+/// # Returns
+///
+/// A `Result` with `()` when the precondition holds, or an `ApiError::PreconditionFailed` otherwise.
+fn check_precondition(task: &Model, if_match: Option<&EntityTags>) -> Result<(), ApiError> {
+    match if_match {
+        Some(tags) if !tags.matches_strong(&etag(task.version)) => Err(changed_underneath()),
+        _ => Ok(()),
+    }
+}
+
+/// Error returned when a task changed since the client (or the current request) read it.
+fn changed_underneath() -> ApiError {
+    ApiError::PreconditionFailed(String::from(
+        "The task was changed by another request, fetch it again.",
+    ))
+}
+
+/// Maps the error of a write guarded by the task version, a missing row meaning the version moved.
+fn version_conflict(e: DbErr) -> ApiError {
+    match e {
+        DbErr::RecordNotUpdated => changed_underneath(),
+        e => e.into(),
+    }
+}
+
+/// Version of a deleted task restored by a revert, above the last version it had.
+///
+/// # Arguments
+///
+/// * `last_version` - The version of the task when it was deleted, as kept by its tombstone, `0` without one.
+/// * `events` - The number of events of the task, each of which bumped its version at least once.
+fn restored_version(last_version: i32, events: usize) -> i32 {
+    last_version.max(events as i32) + 1
 }
 
 impl TaskMutation {
@@ -26,28 +129,87 @@ impl TaskMutation {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the created `Model` on success, or a `DbErr` on failure.
-    pub async fn create(task_payload: TaskPayload, db: &DbConn) -> Result<Model, DbErr> {
+    /// A `Result` containing the created `Model` on success, or an `ApiError` on failure.
+    pub async fn create(task_payload: TaskPayload, db: &DbConn) -> Result<Model, ApiError> {
+        // Insert the task and record its creation in the same transaction
+        let txn = db.begin().await?;
+        let created_task = Self::insert(task_payload, &txn).await?;
+        TaskEvents::commit(txn, TaskChange::created(&created_task)).await?;
+
+        Ok(created_task)
+    }
+
+    /// Asynchronously creates a new task for a request sent with an idempotency key.
+    ///
+    /// The response of the request is stored for the key in the transaction creating the task,
+    /// so a retry either finds the task and its response or creates the task again.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_payload` - Payload containing task details to be created.
+    /// * `key` - The idempotency key reserved for the request.
+    /// * `respond` - Builds the response of the request from the created task.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the created `Model` on success, or an `ApiError` on failure.
+    pub async fn create_with_key(
+        task_payload: TaskPayload,
+        key: &str,
+        respond: impl FnOnce(&Model) -> Result<StoredResponse, ApiError>,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        let user_id = task_payload.user_id;
+
+        // Insert the task, record its creation and complete the key in the same transaction
+        let txn = db.begin().await?;
+        let created_task = Self::insert(task_payload, &txn).await?;
+        IdempotencyMutation::complete(key, respond(&created_task)?, user_id, &txn).await?;
+        TaskEvents::commit(txn, TaskChange::created(&created_task)).await?;
+
+        Ok(created_task)
+    }
+
+    /// Asynchronously inserts a new task and records its creation, without committing.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_payload` - Payload containing task details to be created.
+    /// * `txn` - The transaction writing the task.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the created `Model` on success, or an `ApiError` on failure.
+    pub(crate) async fn insert(
+        task_payload: TaskPayload,
+        txn: &DatabaseTransaction,
+    ) -> Result<Model, ApiError> {
         // Create an ActiveModel instance with task payload data
         let active_model = ActiveModel {
             name: Set(task_payload.name.to_owned()),
             description: Set(task_payload.description.to_owned()),
             is_active: Set(task_payload.is_active.to_owned()),
+            due_date: Set(task_payload.due_date),
+            priority: task_payload.priority.map_or(NotSet, Set),
+            tags: task_payload.tags.map_or(NotSet, Set),
+            project: task_payload.project.map_or(NotSet, Set),
             user_id: Set(task_payload.user_id),
             ..Default::default() // Use default values for unspecified fields
         };
 
-        // Execute the insert operation and await the result
-        let res = Entity::insert(active_model).exec(db).await?;
-
-        // Construct and return a Model instance with created task details
-        Ok(Model {
-            id: res.last_insert_id,
-            name: task_payload.name,
-            description: task_payload.description,
-            is_active: task_payload.is_active,
-            user_id: task_payload.user_id,
-        })
+        let created_task = active_model.insert(txn).await?;
+        TaskEventMutation::record(
+            TaskAction::Created,
+            None,
+            Some(&created_task),
+            task_payload.user_id,
+            task_payload.request_id,
+            txn,
+        )
+        .await?;
+
+        Ok(created_task)
     }
 
     /// Asynchronously updates an existing task identified by `id` with the provided payload.
@@ -60,27 +222,137 @@ impl TaskMutation {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the updated `Model` on success, or a `DbErr` on failure.
-    pub async fn update(task_payload: TaskPayload, id: i32, db: &DbConn) -> Result<Model, DbErr> {
-        // Fetch the task by ID and convert it into an ActiveModel
-        let mut task: ActiveModel = Entity::find_by_id(id)
+    /// A `Result` containing the updated `Model` on success, or an `ApiError` on failure.
+    pub async fn update(
+        task_payload: TaskPayload,
+        id: i32,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        // Fetch the task by ID
+        let before = Entity::find_by_id(id)
             .one(db)
             .await?
-            .ok_or(DbErr::RecordNotFound(String::from("Task not found.")))
-            .map(Into::into)?; // Convert found entity into ActiveModel
+            .ok_or(ApiError::NotFound(String::from("Task not found.")))?;
 
         // Check if the user ID in the task matches the user ID in the payload
-        if task.user_id.clone().unwrap() != task_payload.user_id {
-            return Err(DbErr::RecordNotFound(String::from("Task not found.")));
+        if before.user_id != task_payload.user_id {
+            return Err(ApiError::NotFound(String::from("Task not found.")));
         }
 
+        check_precondition(&before, task_payload.if_match.as_ref())?;
+
         // Update task fields with new values from the payload
+        let mut task: ActiveModel = before.clone().into();
         task.name = Set(task_payload.name.to_owned());
         task.description = Set(task_payload.description.to_owned());
         task.is_active = Set(task_payload.is_active.to_owned());
+        task.due_date = Set(task_payload.due_date);
+        if let Some(priority) = task_payload.priority {
+            task.priority = Set(priority);
+        }
+        if let Some(tags) = task_payload.tags {
+            task.tags = Set(tags);
+        }
+        if let Some(project) = task_payload.project {
+            task.project = Set(project);
+        }
+        task.version = Set(before.version + 1);
 
-        // Execute the update operation and await the result
-        task.update(db).await
+        // Update the task, only if nobody changed it since it was read, and record the change
+        let txn = db.begin().await?;
+        let task = ActiveModelBehavior::before_save(task, &txn, false).await?;
+        let updated_task = Entity::update(task)
+            .filter(Column::Version.eq(before.version))
+            .exec(&txn)
+            .await
+            .map_err(version_conflict)?;
+        TaskEventMutation::record(
+            TaskAction::Updated,
+            Some(&before),
+            Some(&updated_task),
+            task_payload.user_id,
+            task_payload.request_id,
+            &txn,
+        )
+        .await?;
+        TaskEvents::commit(txn, TaskChange::updated(&updated_task)).await?;
+
+        Ok(updated_task)
+    }
+
+    /// Asynchronously applies a partial update to an existing task identified by `id`.
+    ///
+    /// Fields missing from the payload are left as `NotSet`, so they are not part of the `UPDATE`.
+    /// A payload changing no field leaves the task, and its version, as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_payload` - Payload containing the task fields to be changed.
+    /// * `id` - The ID of the task to be updated.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated `Model` on success, or an `ApiError` on failure.
+    pub async fn patch(
+        task_payload: TaskPatchPayload,
+        id: i32,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        // Make sure the task exists and belongs to the user before touching it
+        let task = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("Task not found.")))?;
+
+        if task.user_id != task_payload.user_id {
+            return Err(ApiError::NotFound(String::from("Task not found.")));
+        }
+
+        check_precondition(&task, task_payload.if_match.as_ref())?;
+
+        if task_payload.is_empty() {
+            return Ok(task);
+        }
+
+        // Only the fields present in the payload are set, the rest are left untouched
+        let active_model = ActiveModel {
+            id: Unchanged(task.id),
+            name: task_payload.name.map_or(NotSet, Set),
+            description: task_payload.description.map_or(NotSet, Set),
+            is_active: task_payload.is_active.map_or(NotSet, Set),
+            user_id: NotSet,
+            due_date: task_payload.due_date.map_or(NotSet, Set),
+            priority: task_payload.priority.map_or(NotSet, Set),
+            tags: task_payload.tags.map_or(NotSet, Set),
+            project: task_payload.project.map_or(NotSet, Set),
+            version: Set(task.version + 1),
+            created_at: NotSet,
+            updated_at: NotSet,
+            change_seq: NotSet,
+            created_seq: NotSet,
+        };
+
+        // Update the task, only if nobody changed it since it was read, and record the change
+        let txn = db.begin().await?;
+        let active_model = ActiveModelBehavior::before_save(active_model, &txn, false).await?;
+        let updated_task = Entity::update(active_model)
+            .filter(Column::Version.eq(task.version))
+            .exec(&txn)
+            .await
+            .map_err(version_conflict)?;
+        TaskEventMutation::record(
+            TaskAction::Updated,
+            Some(&task),
+            Some(&updated_task),
+            task_payload.user_id,
+            task_payload.request_id,
+            &txn,
+        )
+        .await?;
+        TaskEvents::commit(txn, TaskChange::updated(&updated_task)).await?;
+
+        Ok(updated_task)
     }
 
     /// Asynchronously deletes an existing task identified by `id` and `user_id`.
@@ -89,26 +361,174 @@ impl TaskMutation {
     ///
     /// * `id` - The ID of the task to be deleted.
     /// * `user_id` - The ID of the user deleting the task.
+    /// * `request_id` - The ID of the request deleting the task, recorded in the task history.
+    /// * `if_match` - The `If-Match` precondition of the request, if any.
     /// * `db` - SeaORM database connection (`&DbConn`).
     ///
     /// # Returns
     ///
-    /// A `Result` containing the deletion result on success, or a `DbErr` on failure.
-    pub async fn delete(id: i32, user_id: i32, db: &DbConn) -> Result<DeleteResult, DbErr> {
-        // Fetch the task by ID and convert it into an ActiveModel
-        let task: ActiveModel = Entity::find_by_id(id)
+    /// A `Result` containing the deletion result on success, or an `ApiError` on failure.
+    pub async fn delete(
+        id: i32,
+        user_id: i32,
+        request_id: Option<String>,
+        if_match: Option<EntityTags>,
+        db: &DbConn,
+    ) -> Result<DeleteResult, ApiError> {
+        // Fetch the task by ID
+        let task = Entity::find_by_id(id)
             .one(db)
             .await?
-            .ok_or(DbErr::RecordNotFound(String::from("Task not found.")))
-            .map(Into::into)?;
+            .ok_or(ApiError::NotFound(String::from("Task not found.")))?;
 
         // Check if the user ID in the task matches the provided user ID
-        if task.user_id.clone().unwrap() != user_id {
-            return Err(DbErr::RecordNotFound(String::from("Task not found.")));
+        if task.user_id != user_id {
+            return Err(ApiError::NotFound(String::from("Task not found.")));
         }
 
-        // Execute the delete operation and await the result
-        task.delete(db).await
+        check_precondition(&task, if_match.as_ref())?;
+
+        // Delete the task, only if nobody changed it since it was read, and record the deletion
+        let txn = db.begin().await?;
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::Version.eq(task.version))
+            .exec(&txn)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(changed_underneath());
+        }
+
+        TaskEventMutation::record(
+            TaskAction::Deleted,
+            Some(&task),
+            None,
+            user_id,
+            request_id,
+            &txn,
+        )
+        .await?;
+
+        // The tombstone written by the delete carries its position in the change feed
+        let tombstone = task_tombstone::Entity::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(ApiError::Internal(String::from(
+                "Task tombstone not found.",
+            )))?;
+        TaskEvents::commit(txn, TaskChange::deleted(&tombstone)).await?;
+
+        Ok(result)
+    }
+
+    /// Asynchronously reverts a task to the state it had right after the event `revision` of its history.
+    ///
+    /// The revert is recorded as a new event, and a deleted task is restored with the same ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the task to be reverted.
+    /// * `revision` - The ID of the history event to revert to.
+    /// * `user_id` - The ID of the user reverting the task.
+    /// * `request_id` - The ID of the request reverting the task, recorded in the task history.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the reverted `Model` on success, or an `ApiError` on failure.
+    pub async fn revert(
+        id: i32,
+        revision: i32,
+        user_id: i32,
+        request_id: Option<String>,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        // Rebuild the task at the requested revision
+        let state = TaskEventQueries::get_revision(id, revision, user_id, db)
+            .await?
+            .ok_or(ApiError::Validation(String::from(
+                "The task did not exist at this revision.",
+            )))?;
+
+        let before = Entity::find_by_id(id).one(db).await?;
+
+        if before.as_ref().is_some_and(|task| task.user_id != user_id) {
+            return Err(ApiError::NotFound(String::from("Task not found.")));
+        }
+
+        // A restored task gets a version above any it had before, so old ETags never match it,
+        // and keeps the creation time of its first event
+        let (version, created_at) = match &before {
+            Some(task) => (task.version + 1, NotSet),
+            None => {
+                let history = TaskEventQueries::get_history(id, user_id, db).await?;
+                let created_at = history
+                    .first()
+                    .map_or(NotSet, |event| Set(event.created_at));
+                let last_version = task_tombstone::Entity::find_by_id(id)
+                    .filter(task_tombstone::Column::UserId.eq(user_id))
+                    .one(db)
+                    .await?
+                    .map_or(0, |tombstone| tombstone.version);
+
+                (restored_version(last_version, history.len()), created_at)
+            }
+        };
+
+        let active_model = ActiveModel {
+            id: Unchanged(id),
+            name: Set(state.name),
+            description: Set(state.description),
+            is_active: Set(state.is_active),
+            due_date: Set(state.due_date),
+            priority: Set(state.priority),
+            tags: Set(state.tags),
+            project: Set(state.project),
+            user_id: Set(user_id),
+            version: Set(version),
+            created_at,
+            updated_at: NotSet,
+            change_seq: NotSet,
+            created_seq: NotSet,
+        };
+
+        // Write the task back, restoring it if it was deleted, and record the revert
+        let txn = db.begin().await?;
+        let reverted_task = match &before {
+            Some(task) => {
+                Entity::update(ActiveModelBehavior::before_save(active_model, &txn, false).await?)
+                    .filter(Column::Version.eq(task.version))
+                    .exec(&txn)
+                    .await
+                    .map_err(version_conflict)?
+            }
+            None => {
+                ActiveModel {
+                    id: Set(id),
+                    ..active_model
+                }
+                .insert(&txn)
+                .await?
+            }
+        };
+        TaskEventMutation::record(
+            TaskAction::Reverted,
+            before.as_ref(),
+            Some(&reverted_task),
+            user_id,
+            request_id,
+            &txn,
+        )
+        .await?;
+
+        let change = match before {
+            Some(_) => TaskChange::updated(&reverted_task),
+            None => TaskChange::created(&reverted_task),
+        };
+        TaskEvents::commit(txn, change).await?;
+
+        Ok(reverted_task)
     }
 
     /// Asynchronously deletes all tasks from the database.
@@ -119,9 +539,83 @@ impl TaskMutation {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the deletion result on success, or a `DbErr` on failure.
-    pub async fn delete_all(db: &DbConn) -> Result<DeleteResult, DbErr> {
+    /// A `Result` containing the deletion result on success, or an `ApiError` on failure.
+    pub async fn delete_all(db: &DbConn) -> Result<DeleteResult, ApiError> {
         // Execute the delete operation for all tasks and await the result
-        Entity::delete_many().exec(db).await
+        Ok(Entity::delete_many().exec(db).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn task(version: i32) -> Model {
+        Model {
+            id: 1,
+            name: "Buy milk".to_string(),
+            description: "Two litres".to_string(),
+            is_active: false,
+            user_id: 1,
+            due_date: None,
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            change_seq: 1,
+            created_seq: 1,
+        }
+    }
+
+    #[test]
+    fn patches_without_members_are_empty() {
+        let patch = TaskPatchPayload {
+            name: None,
+            description: None,
+            is_active: None,
+            due_date: None,
+            priority: None,
+            tags: None,
+            project: None,
+            user_id: 1,
+            request_id: None,
+            if_match: None,
+        };
+        assert!(patch.is_empty());
+
+        // Removing the due date is a change
+        let patch = TaskPatchPayload {
+            due_date: Some(None),
+            ..patch
+        };
+        assert!(!patch.is_empty());
+    }
+
+    #[test]
+    fn restored_tasks_are_above_their_last_version() {
+        // Updates changing nothing bump the version without recording an event
+        assert_eq!(restored_version(7, 3), 8);
+        // Tasks deleted before the change feed existed have no tombstone
+        assert_eq!(restored_version(0, 3), 4);
+    }
+
+    #[test]
+    fn etags_of_a_deleted_task_do_not_match_it_once_reverted() {
+        let restored = task(restored_version(7, 3));
+
+        for version in 1..=7 {
+            let if_match = EntityTags::parse(&etag(version));
+
+            assert!(matches!(
+                check_precondition(&restored, Some(&if_match)),
+                Err(ApiError::PreconditionFailed(_))
+            ));
+        }
+
+        let if_match = EntityTags::parse(&etag(restored.version));
+        assert!(check_precondition(&restored, Some(&if_match)).is_ok());
     }
 }
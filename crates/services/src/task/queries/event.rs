@@ -0,0 +1,142 @@
+use crate::error::ApiError;
+use crate::task::models::event::TaskRevision;
+use database::entities::task;
+use database::entities::task_event::{Column, Entity, Model};
+use sea_orm::*;
+use serde_json::{Map, Value};
+
+/// Struct for handling queries related to the task history.
+pub struct TaskEventQueries;
+
+impl TaskEventQueries {
+    /// Asynchronously fetches the history of a task, oldest event first.
+    ///
+    /// The history is kept after the task is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_id` - The ID of the task.
+    /// * `user_id` - The ID of the user owning the task.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the events on success, or an `ApiError` if the task has no history.
+    pub async fn get_history(
+        task_id: i32,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Vec<Model>, ApiError> {
+        let events = Entity::find()
+            .filter(Column::TaskId.eq(task_id))
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?;
+
+        if events.is_empty() {
+            return Err(ApiError::NotFound(String::from("Task not found.")));
+        }
+
+        Ok(events)
+    }
+
+    /// Asynchronously fetches the histories of several tasks of a user, in one query.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_ids` - The IDs of the tasks.
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the events of every task, oldest first, or an `ApiError` on failure.
+    pub async fn get_histories(
+        task_ids: &[i32],
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Vec<Model>, ApiError> {
+        Ok(Entity::find()
+            .filter(Column::TaskId.is_in(task_ids.iter().copied()))
+            .filter(Column::UserId.eq(user_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Asynchronously rebuilds the task as it was right after the event `revision`.
+    ///
+    /// Starting from the current task, the changes of every later event are undone,
+    /// newest first, so tasks created before the history existed can be reverted too.
+    ///
+    /// # Arguments
+    ///
+    /// * `task_id` - The ID of the task.
+    /// * `revision` - The ID of the event to rebuild the task at.
+    /// * `user_id` - The ID of the user owning the task.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `TaskRevision` (`None` if the task did not exist at that point) on success,
+    /// or an `ApiError` if the revision is not part of the task history.
+    pub async fn get_revision(
+        task_id: i32,
+        revision: i32,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Option<TaskRevision>, ApiError> {
+        let history = Self::get_history(task_id, user_id, db).await?;
+
+        if !history.iter().any(|event| event.id == revision) {
+            return Err(ApiError::NotFound(String::from("Revision not found.")));
+        }
+
+        // Start from the current state of the task, if it still exists
+        let mut state = task::Entity::find_by_id(task_id)
+            .filter(task::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .map(
+                |task| match serde_json::to_value(TaskRevision::from(&task)) {
+                    Ok(Value::Object(fields)) => fields,
+                    _ => Map::new(),
+                },
+            );
+
+        // Undo the events newer than the revision
+        for event in history
+            .iter()
+            .rev()
+            .take_while(|event| event.id != revision)
+        {
+            let Some(changes) = event.changes.as_object() else {
+                continue;
+            };
+
+            // A missing old name means the task did not exist before the event
+            let existed = changes
+                .get("name")
+                .is_none_or(|change| !change["old"].is_null());
+            if !existed {
+                state = None;
+                continue;
+            }
+
+            let fields = state.get_or_insert_with(Map::new);
+            for (field, change) in changes {
+                fields.insert(field.clone(), change["old"].clone());
+            }
+        }
+
+        state
+            .map(|fields| serde_json::from_value(Value::Object(fields)))
+            .transpose()
+            .map_err(|_| {
+                ApiError::Validation(
+                    "The history of the task does not reach back to this revision.".to_string(),
+                )
+            })
+    }
+}
@@ -0,0 +1,818 @@
+use crate::error::ApiError;
+use crate::task::queries::task::like_pattern;
+use chrono::{Duration, NaiveDate, Utc};
+use database::entities::task::{Column, Entity, TaskPriority};
+use sea_orm::sea_query::extension::postgres::{PgBinOper, PgExpr};
+use sea_orm::sea_query::{Alias, Expr, Func};
+use sea_orm::*;
+use std::fmt::{Display, Formatter};
+
+/// Comparison operator of a filter term.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// `:`, contains for text fields and equals for the rest.
+    Has,
+    /// `=`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Has => ":",
+            Operator::Eq => "=",
+            Operator::Ne => "!=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
+/// A single term of a filter query, such as `-name:"groceries"` or `bread`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Term {
+    /// Whether the term is negated with a leading `-`.
+    pub negated: bool,
+    /// The field compared by the term, `None` for bare words matching the name or description.
+    pub field: Option<String>,
+    /// The comparison operator, `Operator::Has` for bare words.
+    pub operator: Operator,
+    /// The value compared against the field, without quotes.
+    pub value: String,
+    /// Position (in characters) where the term starts.
+    pub position: usize,
+    /// Position (in characters) where the value starts.
+    pub value_position: usize,
+}
+
+/// Error raised while parsing or compiling a filter query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilterError {
+    /// Position (in characters) of the offending token.
+    pub position: usize,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl Display for FilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at position {})", self.message, self.position)
+    }
+}
+
+impl From<FilterError> for ApiError {
+    fn from(e: FilterError) -> Self {
+        ApiError::Validation(format!("Invalid filter query: {}.", e))
+    }
+}
+
+/// Returns whether the character ends a field name.
+fn is_operator_start(c: char) -> bool {
+    matches!(c, ':' | '=' | '!' | '<' | '>')
+}
+
+/// Reads a value starting at `position`, either quoted or up to the next whitespace.
+///
+/// # Arguments
+///
+/// * `chars` - Characters of the whole query.
+/// * `position` - Position of the first character of the value.
+///
+/// # Returns
+///
+/// A `Result` with the value and the position right after it, or a `FilterError` if a quote is not closed.
+fn read_value(chars: &[char], mut position: usize) -> Result<(String, usize), FilterError> {
+    if chars.get(position) == Some(&'"') {
+        let start = position;
+        position += 1;
+
+        let mut value = String::new();
+        while let Some(&c) = chars.get(position) {
+            position += 1;
+            if c == '"' {
+                return Ok((value, position));
+            }
+            value.push(c);
+        }
+
+        return Err(FilterError {
+            position: start,
+            message: "Unterminated quoted value".to_string(),
+        });
+    }
+
+    let mut value = String::new();
+    while let Some(&c) = chars.get(position).filter(|c| !c.is_whitespace()) {
+        value.push(c);
+        position += 1;
+    }
+
+    Ok((value, position))
+}
+
+/// Parses a filter query into its terms.
+///
+/// The grammar is a list of whitespace separated terms:
+///
+/// ```text
+/// term     := ["-"] (field operator value | value)
+/// operator := ":" | "=" | "!=" | ">" | ">=" | "<" | "<="
+/// value    := word | '"' any character but '"' '"'
+/// ```
+///
+/// # Arguments
+///
+/// * `query` - The filter query, such as `status:open -name:"groceries" bread`.
+///
+/// # Returns
+///
+/// A `Result` with the parsed terms, or a `FilterError` pointing at the invalid token.
+pub fn parse(query: &str) -> Result<Vec<Term>, FilterError> {
+    let chars = query.chars().collect::<Vec<_>>();
+    let mut terms = Vec::new();
+    let mut position = 0;
+
+    while position < chars.len() {
+        if chars[position].is_whitespace() {
+            position += 1;
+            continue;
+        }
+
+        let start = position;
+
+        // A leading dash negates the term
+        let negated = chars[position] == '-';
+        if negated {
+            position += 1;
+        }
+
+        // Quoted words cannot be field names
+        if chars.get(position) == Some(&'"') {
+            let (value, end) = read_value(&chars, position)?;
+            terms.push(Term {
+                negated,
+                field: None,
+                operator: Operator::Has,
+                value,
+                position: start,
+                value_position: position,
+            });
+            position = end;
+            continue;
+        }
+
+        // Read a word, which is a field name if an operator follows it
+        let word_start = position;
+        while chars
+            .get(position)
+            .is_some_and(|c| !c.is_whitespace() && !is_operator_start(*c))
+        {
+            position += 1;
+        }
+        let word = chars[word_start..position].iter().collect::<String>();
+
+        let operator = match (chars.get(position), chars.get(position + 1)) {
+            (Some(':'), _) => Some((Operator::Has, 1)),
+            (Some('='), _) => Some((Operator::Eq, 1)),
+            (Some('!'), Some('=')) => Some((Operator::Ne, 2)),
+            (Some('>'), Some('=')) => Some((Operator::Ge, 2)),
+            (Some('>'), _) => Some((Operator::Gt, 1)),
+            (Some('<'), Some('=')) => Some((Operator::Le, 2)),
+            (Some('<'), _) => Some((Operator::Lt, 1)),
+            (Some('!'), _) => {
+                return Err(FilterError {
+                    position,
+                    message: "Expected `!=`".to_string(),
+                })
+            }
+            _ => None,
+        };
+
+        let Some((operator, length)) = operator else {
+            // A bare word
+            if word.is_empty() {
+                return Err(FilterError {
+                    position: start,
+                    message: "Expected a term after `-`".to_string(),
+                });
+            }
+
+            terms.push(Term {
+                negated,
+                field: None,
+                operator: Operator::Has,
+                value: word,
+                position: start,
+                value_position: word_start,
+            });
+            continue;
+        };
+
+        if word.is_empty() {
+            return Err(FilterError {
+                position,
+                message: format!("Expected a field name before `{}`", operator),
+            });
+        }
+
+        position += length;
+        let value_position = position;
+        let (value, end) = read_value(&chars, position)?;
+
+        if value.is_empty() {
+            return Err(FilterError {
+                position: value_position,
+                message: format!("Expected a value for `{}`", word),
+            });
+        }
+
+        terms.push(Term {
+            negated,
+            field: Some(word.to_lowercase()),
+            operator,
+            value,
+            position: start,
+            value_position,
+        });
+        position = end;
+    }
+
+    Ok(terms)
+}
+
+/// Parses the value of a `due` term into a date.
+///
+/// Accepts `YYYY-MM-DD` dates, `today`, `tomorrow`, `yesterday` and offsets from today
+/// in days or weeks such as `7d`, `-3d` or `2w`.
+///
+/// # Arguments
+///
+/// * `value` - The value of the term.
+/// * `today` - The date the relative values are computed from.
+///
+/// # Returns
+///
+/// The date, or `None` if the value is not a valid date.
+fn parse_date(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match value.to_lowercase().as_str() {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        "yesterday" => today.pred_opt(),
+        value => {
+            let days = |unit: &str, multiplier: i64| {
+                value
+                    .strip_suffix(unit)
+                    .and_then(|amount| amount.parse::<i64>().ok())
+                    .and_then(|amount| amount.checked_mul(multiplier))
+                    .and_then(|days| today.checked_add_signed(Duration::try_days(days)?))
+            };
+
+            days("d", 1)
+                .or_else(|| days("w", 7))
+                .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        }
+    }
+}
+
+/// Parses the value of a `priority` term, by name or by rank from `1` (low) to `4` (urgent).
+///
+/// # Arguments
+///
+/// * `value` - The value of the term.
+///
+/// # Returns
+///
+/// The priority, or `None` if the value is not a priority.
+fn parse_priority(value: &str) -> Option<TaskPriority> {
+    match value.to_lowercase().as_str() {
+        "low" | "1" => Some(TaskPriority::Low),
+        "normal" | "2" => Some(TaskPriority::Normal),
+        "high" | "3" => Some(TaskPriority::High),
+        "urgent" | "4" => Some(TaskPriority::Urgent),
+        _ => None,
+    }
+}
+
+/// Compiles a single term into a SeaORM condition against the `tasks` columns.
+///
+/// # Arguments
+///
+/// * `term` - The term to compile.
+/// * `today` - The date relative due dates are computed from.
+///
+/// # Returns
+///
+/// A `Result` with the condition (not yet negated), or a `FilterError` for unsupported fields and values.
+fn compile_term(term: &Term, today: NaiveDate) -> Result<Condition, FilterError> {
+    let unsupported_operator = |field: &str| FilterError {
+        position: term.value_position - term.operator.to_string().len(),
+        message: format!(
+            "The operator `{}` cannot be used with `{}`",
+            term.operator, field
+        ),
+    };
+
+    let condition = match term.field.as_deref() {
+        // Bare words match the name or the description
+        None => Condition::any()
+            .add(Expr::col((Entity, Column::Name)).ilike(like_pattern(&term.value)))
+            .add(Expr::col((Entity, Column::Description)).ilike(like_pattern(&term.value))),
+        Some(field @ ("name" | "description")) => {
+            let column = match field {
+                "name" => Column::Name,
+                _ => Column::Description,
+            };
+
+            let expr = match term.operator {
+                Operator::Has => Expr::col((Entity, column)).ilike(like_pattern(&term.value)),
+                Operator::Eq => column.eq(term.value.as_str()),
+                Operator::Ne => column.ne(term.value.as_str()),
+                _ => return Err(unsupported_operator(field)),
+            };
+
+            Condition::all().add(expr)
+        }
+        // Tasks are open until they are flagged as active (done)
+        Some("status") => {
+            let done = match term.value.to_lowercase().as_str() {
+                "open" | "todo" => false,
+                "done" | "completed" | "closed" => true,
+                value => {
+                    return Err(FilterError {
+                        position: term.value_position,
+                        message: format!("Unknown status `{}`, expected `open` or `done`", value),
+                    })
+                }
+            };
+
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::IsActive.eq(done),
+                Operator::Ne => Column::IsActive.ne(done),
+                _ => return Err(unsupported_operator("status")),
+            };
+
+            Condition::all().add(expr)
+        }
+        Some("id") => {
+            let id = term.value.parse::<i32>().map_err(|_| FilterError {
+                position: term.value_position,
+                message: format!("Expected a number for `id`, found `{}`", term.value),
+            })?;
+
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::Id.eq(id),
+                Operator::Ne => Column::Id.ne(id),
+                Operator::Gt => Column::Id.gt(id),
+                Operator::Ge => Column::Id.gte(id),
+                Operator::Lt => Column::Id.lt(id),
+                Operator::Le => Column::Id.lte(id),
+            };
+
+            Condition::all().add(expr)
+        }
+        // `due:none` matches the tasks without a due date
+        Some("due") if term.value.eq_ignore_ascii_case("none") => {
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::DueDate.is_null(),
+                Operator::Ne => Column::DueDate.is_not_null(),
+                _ => return Err(unsupported_operator("due:none")),
+            };
+
+            Condition::all().add(expr)
+        }
+        Some("due") => {
+            let date = parse_date(&term.value, today).ok_or_else(|| FilterError {
+                position: term.value_position,
+                message: format!("Expected a date for `due`, found `{}`", term.value),
+            })?;
+
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::DueDate.eq(date),
+                Operator::Ne => Column::DueDate.ne(date),
+                Operator::Gt => Column::DueDate.gt(date),
+                Operator::Ge => Column::DueDate.gte(date),
+                Operator::Lt => Column::DueDate.lt(date),
+                Operator::Le => Column::DueDate.lte(date),
+            };
+
+            Condition::all().add(expr)
+        }
+        Some("priority") => {
+            let priority = parse_priority(&term.value).ok_or_else(|| FilterError {
+                position: term.value_position,
+                message: format!(
+                    "Unknown priority `{}`, expected `low`, `normal`, `high` or `urgent`",
+                    term.value
+                ),
+            })?;
+
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::Priority.eq(priority),
+                Operator::Ne => Column::Priority.ne(priority),
+                Operator::Gt => Column::Priority.gt(priority),
+                Operator::Ge => Column::Priority.gte(priority),
+                Operator::Lt => Column::Priority.lt(priority),
+                Operator::Le => Column::Priority.lte(priority),
+            };
+
+            Condition::all().add(expr)
+        }
+        // `tag:none` matches the tasks without tags, tags are stored in lowercase
+        Some("tag") => {
+            let tags = Expr::col((Entity, Column::Tags));
+            let expr = match term.value.eq_ignore_ascii_case("none") {
+                true => Expr::expr(Func::cust(Alias::new("cardinality")).arg(tags)).eq(0),
+                false => tags.binary(
+                    PgBinOper::Contains,
+                    Expr::val(vec![term.value.to_lowercase()]),
+                ),
+            };
+
+            match term.operator {
+                Operator::Has | Operator::Eq => Condition::all().add(expr),
+                Operator::Ne => Condition::all().add(expr).not(),
+                _ => return Err(unsupported_operator("tag")),
+            }
+        }
+        // `project:none` matches the tasks without a project
+        Some("project") if term.value.eq_ignore_ascii_case("none") => {
+            let expr = match term.operator {
+                Operator::Has | Operator::Eq => Column::Project.is_null(),
+                Operator::Ne => Column::Project.is_not_null(),
+                _ => return Err(unsupported_operator("project:none")),
+            };
+
+            Condition::all().add(expr)
+        }
+        // Tasks without a project match neither `project:` nor `project=`, so negating them keeps those tasks
+        Some("project") => {
+            let expr = match term.operator {
+                Operator::Has => {
+                    Expr::col((Entity, Column::Project)).ilike(like_pattern(&term.value))
+                }
+                Operator::Eq | Operator::Ne => Column::Project.eq(term.value.as_str()),
+                _ => return Err(unsupported_operator("project")),
+            };
+
+            let condition = Condition::all()
+                .add(Column::Project.is_not_null())
+                .add(expr);
+            match term.operator {
+                Operator::Ne => condition.not(),
+                _ => condition,
+            }
+        }
+        Some(field) => {
+            return Err(FilterError {
+                position: term.position + usize::from(term.negated),
+                message: format!("Unknown field `{}`", field),
+            })
+        }
+    };
+
+    Ok(condition)
+}
+
+/// Parses a filter query and compiles it into a SeaORM condition against the `tasks` columns.
+///
+/// Every term must match, the supported fields are:
+/// - `status` (`:`, `=`, `!=`) with `open` or `done`.
+/// - `name` and `description`, `:` matches a part of the text ignoring case, `=` and `!=` the whole text.
+/// - `id` with any comparison operator.
+/// - `due` with any comparison operator and a date (see `parse_date`), or `due:none` for tasks without one.
+/// - `priority` with any comparison operator and a priority (see `parse_priority`), `low` being the lowest.
+/// - `tag` (`:`, `=`, `!=`) matches the tasks having the tag, `tag:none` the tasks without tags.
+/// - `project`, `:` matches a part of the project ignoring case, `=` and `!=` the whole project,
+///   `project:none` the tasks without a project.
+/// - Bare words and quoted values match a part of the name or the description.
+///
+/// Relative due dates are computed from the current UTC date.
+///
+/// # Arguments
+///
+/// * `query` - The filter query, such as `status:open priority>=high due<7d -tag:someday project:"Home"`.
+///
+/// # Returns
+///
+/// A `Result` with the condition, or a `FilterError` pointing at the invalid token.
+pub fn compile(query: &str) -> Result<Condition, FilterError> {
+    compile_at(query, Utc::now().date_naive())
+}
+
+/// Same as `compile`, computing the relative due dates from `today`.
+///
+/// # Arguments
+///
+/// * `query` - The filter query.
+/// * `today` - The date relative due dates are computed from.
+///
+/// # Returns
+///
+/// A `Result` with the condition, or a `FilterError` pointing at the invalid token.
+pub fn compile_at(query: &str, today: NaiveDate) -> Result<Condition, FilterError> {
+    parse(query)?
+        .iter()
+        .try_fold(Condition::all(), |condition, term| {
+            let compiled = compile_term(term, today)?;
+            Ok(condition.add(match term.negated {
+                true => compiled.not(),
+                false => compiled,
+            }))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders the `WHERE` clause of a query filtered by `query`.
+    fn sql(query: &str) -> String {
+        let sql = Entity::find()
+            .filter(compile(query).unwrap())
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        sql.split_once(" WHERE ")
+            .map(|(_, clause)| clause.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_fields_operators_and_values() {
+        let terms = parse(r#"status:open id>=3 -name:"Home stuff" bread"#).unwrap();
+
+        assert_eq!(terms.len(), 4);
+        assert_eq!(terms[0].field.as_deref(), Some("status"));
+        assert_eq!(terms[0].operator, Operator::Has);
+        assert_eq!(terms[0].value, "open");
+        assert_eq!(terms[1].operator, Operator::Ge);
+        assert_eq!(terms[1].value, "3");
+        assert!(terms[2].negated);
+        assert_eq!(terms[2].value, "Home stuff");
+        assert_eq!(terms[2].position, 18);
+        assert_eq!(terms[3].field, None);
+        assert_eq!(terms[3].value, "bread");
+    }
+
+    #[test]
+    fn parses_every_operator() {
+        let operators = ["id:1", "id=1", "id!=1", "id>1", "id>=1", "id<1", "id<=1"]
+            .iter()
+            .map(|query| parse(query).unwrap()[0].operator)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            operators,
+            [
+                Operator::Has,
+                Operator::Eq,
+                Operator::Ne,
+                Operator::Gt,
+                Operator::Ge,
+                Operator::Lt,
+                Operator::Le
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_quoted_bare_words() {
+        let terms = parse(r#"-"fresh bread""#).unwrap();
+
+        assert!(terms[0].negated);
+        assert_eq!(terms[0].field, None);
+        assert_eq!(terms[0].value, "fresh bread");
+    }
+
+    #[test]
+    fn parses_empty_query() {
+        assert_eq!(parse("   ").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn reports_unterminated_quote() {
+        let error = parse(r#"name:"Home"#).unwrap_err();
+
+        assert_eq!(error.position, 5);
+    }
+
+    #[test]
+    fn reports_missing_value() {
+        let error = parse("status: bread").unwrap_err();
+
+        assert_eq!(error.position, 7);
+    }
+
+    #[test]
+    fn reports_missing_field() {
+        let error = parse("bread :open").unwrap_err();
+
+        assert_eq!(error.position, 6);
+    }
+
+    #[test]
+    fn reports_lone_exclamation_mark() {
+        let error = parse("id!3").unwrap_err();
+
+        assert_eq!(error.position, 2);
+    }
+
+    #[test]
+    fn reports_lone_dash() {
+        let error = parse("bread - milk").unwrap_err();
+
+        assert_eq!(error.position, 6);
+    }
+
+    #[test]
+    fn reports_unknown_field() {
+        let error = compile("status:open assignee:bob").unwrap_err();
+
+        assert_eq!(error.position, 12);
+        assert!(error.message.contains("assignee"));
+    }
+
+    #[test]
+    fn reports_unknown_negated_field() {
+        let error = compile("-label:someday").unwrap_err();
+
+        assert_eq!(error.position, 1);
+    }
+
+    #[test]
+    fn reports_invalid_status() {
+        let error = compile("status:maybe").unwrap_err();
+
+        assert_eq!(error.position, 7);
+    }
+
+    #[test]
+    fn reports_unsupported_operator() {
+        let error = compile("status>open").unwrap_err();
+
+        assert_eq!(error.position, 6);
+    }
+
+    #[test]
+    fn reports_invalid_number() {
+        let error = compile("id>three").unwrap_err();
+
+        assert_eq!(error.position, 3);
+    }
+
+    #[test]
+    fn compiles_status() {
+        assert_eq!(sql("status:open"), r#""tasks"."is_active" = FALSE"#);
+        assert_eq!(sql("status!=done"), r#""tasks"."is_active" <> TRUE"#);
+    }
+
+    #[test]
+    fn compiles_text_fields() {
+        assert_eq!(sql("name:Home"), r#""tasks"."name" ILIKE '%Home%'"#);
+        assert_eq!(sql("description=Home"), r#""tasks"."description" = 'Home'"#);
+    }
+
+    #[test]
+    fn compiles_bare_words() {
+        assert_eq!(
+            sql("bread"),
+            r#"("tasks"."name" ILIKE '%bread%') OR ("tasks"."description" ILIKE '%bread%')"#
+        );
+    }
+
+    #[test]
+    fn compiles_negated_terms() {
+        assert_eq!(sql("-id<=3"), r#"NOT "tasks"."id" <= 3"#);
+    }
+
+    #[test]
+    fn compiles_all_terms_together() {
+        assert_eq!(
+            sql("status:done id>2"),
+            r#""tasks"."is_active" = TRUE AND "tasks"."id" > 2"#
+        );
+    }
+
+    #[test]
+    fn compiles_due_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let sql = |query: &str| {
+            let sql = Entity::find()
+                .filter(compile_at(query, today).unwrap())
+                .build(DbBackend::Postgres)
+                .to_string();
+            sql.split_once(" WHERE ").unwrap().1.to_string()
+        };
+
+        assert_eq!(sql("due=today"), r#""tasks"."due_date" = '2026-10-18'"#);
+        assert_eq!(sql("due<7d"), r#""tasks"."due_date" < '2026-10-25'"#);
+        assert_eq!(sql("due>=-1w"), r#""tasks"."due_date" >= '2026-10-11'"#);
+        assert_eq!(
+            sql("due<=tomorrow"),
+            r#""tasks"."due_date" <= '2026-10-19'"#
+        );
+        assert_eq!(
+            sql("due:2026-12-31"),
+            r#""tasks"."due_date" = '2026-12-31'"#
+        );
+        assert_eq!(sql("due:none"), r#""tasks"."due_date" IS NULL"#);
+        assert_eq!(sql("-due:none"), r#"NOT "tasks"."due_date" IS NULL"#);
+    }
+
+    #[test]
+    fn reports_invalid_due_date() {
+        let error = compile("due<soon").unwrap_err();
+
+        assert_eq!(error.position, 4);
+    }
+
+    #[test]
+    fn reports_unsupported_due_none_operator() {
+        let error = compile("due>none").unwrap_err();
+
+        assert_eq!(error.position, 3);
+    }
+
+    #[test]
+    fn compiles_priorities() {
+        assert_eq!(sql("priority>=high"), r#""tasks"."priority" >= 3"#);
+        assert_eq!(sql("priority:Urgent"), r#""tasks"."priority" = 4"#);
+        assert_eq!(sql("priority<2"), r#""tasks"."priority" < 2"#);
+    }
+
+    #[test]
+    fn reports_invalid_priority() {
+        let error = compile("priority>=soon").unwrap_err();
+
+        assert_eq!(error.position, 10);
+        assert!(error.message.contains("urgent"));
+    }
+
+    #[test]
+    fn compiles_tags() {
+        assert_eq!(sql("tag:Home"), r#""tasks"."tags" @> ARRAY ['home']"#);
+        assert_eq!(
+            sql("-tag:someday"),
+            r#"NOT "tasks"."tags" @> ARRAY ['someday']"#
+        );
+        assert_eq!(sql("tag:none"), r#"cardinality("tasks"."tags") = 0"#);
+    }
+
+    #[test]
+    fn reports_unsupported_tag_operator() {
+        let error = compile("tag>home").unwrap_err();
+
+        assert_eq!(error.position, 3);
+    }
+
+    #[test]
+    fn compiles_projects() {
+        assert_eq!(
+            sql("project:Home"),
+            r#""tasks"."project" IS NOT NULL AND ("tasks"."project" ILIKE '%Home%')"#
+        );
+        assert_eq!(
+            sql("project!=Home"),
+            r#"NOT ("tasks"."project" IS NOT NULL AND "tasks"."project" = 'Home')"#
+        );
+        assert_eq!(sql("project:none"), r#""tasks"."project" IS NULL"#);
+    }
+
+    #[test]
+    fn compiles_the_example_query() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let condition = compile_at(
+            r#"status:open priority>=high due<7d -tag:someday project:"Home""#,
+            today,
+        )
+        .unwrap();
+        let sql = Entity::find()
+            .filter(condition)
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert_eq!(
+            sql.split_once(" WHERE ").unwrap().1,
+            r#""tasks"."is_active" = FALSE AND "tasks"."priority" >= 3 AND "tasks"."due_date" < '2026-10-25' AND (NOT "tasks"."tags" @> ARRAY ['someday']) AND ("tasks"."project" IS NOT NULL AND ("tasks"."project" ILIKE '%Home%'))"#
+        );
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(sql("name:100%"), r#""tasks"."name" ILIKE E'%100\\%%'"#);
+    }
+}
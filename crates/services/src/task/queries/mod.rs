@@ -1 +1,3 @@
+pub mod event;
+pub mod filter;
 pub mod task;
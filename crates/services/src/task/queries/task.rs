@@ -1,11 +1,56 @@
+use crate::error::ApiError;
 use crate::task::models::task::TaskModel;
+use crate::task::queries::filter;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
 use database::entities::task::{Column, Entity};
+use rocket::FromFormField;
+use sea_orm::sea_query::extension::postgres::PgExpr;
+use sea_orm::sea_query::Expr;
 use sea_orm::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Struct for handling queries related to tasks.
 pub struct TaskQueries;
 
+/// Largest page of tasks the GraphQL and gRPC APIs let a client request.
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// Key used to sort the tasks, ties are always broken by the task ID.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromFormField, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    /// Sort by task ID, which follows the creation order.
+    #[default]
+    #[field(value = "id")]
+    Id,
+    /// Sort by task name.
+    #[field(value = "name")]
+    Name,
+    /// Sort by creation time, oldest first.
+    #[field(value = "created_at")]
+    CreatedAt,
+    /// Sort by time of the last change, least recently changed first.
+    #[field(value = "updated_at")]
+    UpdatedAt,
+}
+
+impl TaskSort {
+    /// Returns the column backing the sort key.
+    fn column(&self) -> Column {
+        match self {
+            TaskSort::Id => Column::Id,
+            TaskSort::Name => Column::Name,
+            TaskSort::CreatedAt => Column::CreatedAt,
+            TaskSort::UpdatedAt => Column::UpdatedAt,
+        }
+    }
+}
+
 /// Payload structure for pagination and filtering tasks.
 pub struct PaginationPayload {
     /// The page number to fetch.
@@ -14,12 +59,42 @@ pub struct PaginationPayload {
     pub size: u64,
     /// Optional query string for filtering tasks by name.
     pub query: Option<String>,
+    /// Optional structured filter query, see `filter::compile`.
+    pub filter: Option<String>,
+    /// Only fetch the tasks created after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only fetch the tasks changed at or after this time.
+    pub updated_since: Option<DateTime<Utc>>,
+    /// The key used to sort the tasks.
+    pub sort: TaskSort,
+    /// The ID of the user associated with the tasks.
+    pub user_id: i32,
+}
+
+/// Payload structure for cursor (keyset) pagination and filtering tasks.
+pub struct CursorPayload {
+    /// Opaque cursor, fetch the tasks after it.
+    pub after: Option<String>,
+    /// Opaque cursor, fetch the tasks before it.
+    pub before: Option<String>,
+    /// The maximum number of tasks to fetch.
+    pub limit: u64,
+    /// Optional query string for filtering tasks by name.
+    pub query: Option<String>,
+    /// Optional structured filter query, see `filter::compile`.
+    pub filter: Option<String>,
+    /// Only fetch the tasks created after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only fetch the tasks changed at or after this time.
+    pub updated_since: Option<DateTime<Utc>>,
+    /// The key used to sort the tasks.
+    pub sort: TaskSort,
     /// The ID of the user associated with the tasks.
     pub user_id: i32,
 }
 
 /// Structure representing the result of fetching all tasks.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct GetAllTasks {
     /// List of task items fetched.
     pub items: Vec<TaskModel>,
@@ -31,6 +106,294 @@ pub struct GetAllTasks {
     pub page: u64,
 }
 
+/// Structure representing the result of fetching tasks by cursor.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CursorTasks {
+    /// List of task items fetched.
+    pub items: Vec<TaskModel>,
+    /// Maximum number of items fetched.
+    pub limit: u64,
+    /// Cursor to fetch the following tasks with `after`, if there are any.
+    pub next_cursor: Option<String>,
+    /// Cursor to fetch the previous tasks with `before`, if there are any.
+    pub prev_cursor: Option<String>,
+}
+
+/// Payload structure for the full-text search of tasks.
+pub struct SearchPayload {
+    /// The search query, see `TaskQueries::search_tasks` for the supported operators.
+    pub query: String,
+    /// The page number to fetch.
+    pub page: u64,
+    /// The size (number of items) per page.
+    pub size: u64,
+    /// The ID of the user associated with the tasks.
+    pub user_id: i32,
+}
+
+/// Task matching a full-text search, with its relevance and highlighted text.
+#[derive(Serialize, Deserialize, ToSchema, FromQueryResult)]
+pub struct TaskSearchHit {
+    /// The unique identifier of the task.
+    pub id: i32,
+    /// The name of the task.
+    pub name: String,
+    /// The description of the task.
+    pub description: String,
+    /// Flag indicating whether the task is active or not.
+    pub is_active: bool,
+    /// The ID of the user associated with the task.
+    pub user_id: i32,
+    /// The date the task is due, if any.
+    pub due_date: Option<NaiveDate>,
+    /// When the task was created.
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// When the task was last changed.
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+    /// Relevance of the task for the search, higher is better.
+    pub rank: f32,
+    /// The name with the matching words wrapped in `<mark>` tags.
+    pub name_highlight: String,
+    /// Fragments of the description with the matching words wrapped in `<mark>` tags.
+    pub description_snippet: String,
+}
+
+/// Structure representing the result of a full-text search of tasks.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SearchTasks {
+    /// List of matching tasks, the most relevant first.
+    pub items: Vec<TaskSearchHit>,
+    /// Total number of tasks matching the search.
+    pub total: u64,
+    /// Number of items per page.
+    pub size: u64,
+    /// Current page number fetched.
+    pub page: u64,
+}
+
+/// Total of tasks matching a full-text search.
+#[derive(FromQueryResult)]
+struct SearchTotal {
+    total: i64,
+}
+
+/// Tasks fetched either by page number or by cursor.
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum TaskList {
+    /// Tasks fetched by page number.
+    Page(GetAllTasks),
+    /// Tasks fetched by cursor.
+    Cursor(CursorTasks),
+}
+
+/// Position of a task within a sorted task list, encoded as an opaque cursor.
+#[derive(Serialize, Deserialize)]
+struct TaskCursor {
+    /// The sort the cursor was created for.
+    sort: TaskSort,
+    /// The value of the sort key, absent when sorting by ID, timestamps in RFC 3339.
+    key: Option<String>,
+    /// The ID of the task.
+    id: i32,
+}
+
+impl TaskCursor {
+    /// Creates the cursor pointing at `task` for the given sort.
+    fn new(sort: TaskSort, task: &TaskModel) -> Self {
+        let key = match sort {
+            TaskSort::Id => None,
+            TaskSort::Name => Some(task.name.clone()),
+            TaskSort::CreatedAt => {
+                Some(task.created_at.to_rfc3339_opts(SecondsFormat::Micros, true))
+            }
+            TaskSort::UpdatedAt => {
+                Some(task.updated_at.to_rfc3339_opts(SecondsFormat::Micros, true))
+            }
+        };
+
+        Self {
+            sort,
+            key,
+            id: task.id,
+        }
+    }
+
+    /// Encodes the cursor as an URL safe string.
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decodes a cursor previously returned by `encode`.
+    fn decode(cursor: &str) -> Result<Self, ApiError> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Self>(&bytes).ok())
+            .filter(|cursor| cursor.sort == TaskSort::Id || cursor.key_value().is_some())
+            .ok_or(ApiError::Validation("The cursor is invalid.".to_string()))
+    }
+
+    /// Returns the value of the sort key as a database value, `None` when sorting by ID.
+    fn key_value(&self) -> Option<Value> {
+        let key = self.key.as_deref()?;
+
+        match self.sort {
+            TaskSort::Id => None,
+            TaskSort::Name => Some(key.into()),
+            TaskSort::CreatedAt | TaskSort::UpdatedAt => DateTime::parse_from_rfc3339(key)
+                .ok()
+                .map(|time| time.with_timezone(&Utc).into()),
+        }
+    }
+
+    /// Builds the condition matching the tasks after (or before) the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `backward` - Whether to match the tasks before the cursor instead of after it.
+    ///
+    /// # Returns
+    ///
+    /// A `Condition` comparing the sort key first and the task ID on ties.
+    fn condition(&self, backward: bool) -> Condition {
+        let past_id = match backward {
+            true => Column::Id.lt(self.id),
+            false => Column::Id.gt(self.id),
+        };
+
+        match self.key_value() {
+            Some(key) => {
+                let column = self.sort.column();
+                let past_key = match backward {
+                    true => column.lt(key.clone()),
+                    false => column.gt(key.clone()),
+                };
+
+                Condition::any()
+                    .add(past_key)
+                    .add(Condition::all().add(column.eq(key)).add(past_id))
+            }
+            None => Condition::all().add(past_id),
+        }
+    }
+}
+
+/// Builds the condition matching the tasks created after `created_after` and changed since `updated_since`.
+fn timestamp_condition(
+    created_after: Option<DateTime<Utc>>,
+    updated_since: Option<DateTime<Utc>>,
+) -> Condition {
+    Condition::all()
+        .add_option(created_after.map(|time| Column::CreatedAt.gt(time)))
+        .add_option(updated_since.map(|time| Column::UpdatedAt.gte(time)))
+}
+
+/// Builds the `ILIKE` pattern matching any text that contains `query`.
+///
+/// The `LIKE` wildcards in `query` are escaped with a backslash, the Postgres default escape character,
+/// so they match literally.
+pub(crate) fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{}%", escaped)
+}
+
+/// Builds the condition matching tasks whose name or description contains `query`, ignoring case.
+pub(crate) fn contains_condition(query: &str) -> Condition {
+    let pattern = like_pattern(query);
+
+    Condition::any()
+        .add(Expr::col((Entity, Column::Name)).ilike(pattern.clone()))
+        .add(Expr::col((Entity, Column::Description)).ilike(pattern))
+}
+
+/// Converts a user search query into a Postgres `tsquery` expression.
+///
+/// Terms are combined with `&` and support these operators:
+/// - `"some words"` matches the words as a phrase.
+/// - `-word` or `-"some words"` excludes the tasks containing them.
+/// - `word*` matches every word starting with `word`.
+///
+/// Only letters and digits are kept from each term, so the result is always a valid `tsquery`.
+///
+/// # Arguments
+///
+/// * `query` - The search query typed by the user.
+///
+/// # Returns
+///
+/// The `tsquery` text, or `None` if the query has no positive term to search for.
+fn build_tsquery(query: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        // A leading dash negates the term
+        let negated = c == '-';
+        if negated {
+            chars.next();
+        }
+
+        // Quoted terms are phrases, they end at the closing quote instead of the next space
+        let phrase = chars.peek() == Some(&'"');
+        let text: String = match phrase {
+            true => {
+                chars.next();
+                let text = chars.by_ref().take_while(|c| *c != '"').collect();
+                text
+            }
+            false => {
+                let mut text = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    text.push(c);
+                }
+                text
+            }
+        };
+
+        let words = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>();
+
+        if words.is_empty() {
+            continue;
+        }
+
+        let mut term = words.join(" <-> ");
+        if !phrase && text.ends_with('*') {
+            term.push_str(":*");
+        }
+        if words.len() > 1 {
+            term = format!("({})", term);
+        }
+        if negated {
+            term = format!("!{}", term);
+        }
+
+        terms.push(term);
+    }
+
+    // Negations alone would match every task, which is not a search
+    if terms.iter().all(|term| term.starts_with('!')) {
+        return None;
+    }
+
+    Some(terms.join(" & "))
+}
+
 impl TaskQueries {
     /// Asynchronously fetches a task by its ID and user ID.
     ///
@@ -42,15 +405,15 @@ impl TaskQueries {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the fetched `TaskModel` on success, or a `DbErr` on failure.
-    pub async fn get_task_by_id(id: i32, user_id: i32, db: &DbConn) -> Result<TaskModel, DbErr> {
+    /// A `Result` containing the fetched `TaskModel` on success, or an `ApiError` on failure.
+    pub async fn get_task_by_id(id: i32, user_id: i32, db: &DbConn) -> Result<TaskModel, ApiError> {
         // Fetch the task by ID and ensure it is associated with the provided user ID
         let task: TaskModel = Entity::find_by_id(id)
             .find_also_related(database::entities::user::Entity)
             .filter(Column::UserId.eq(user_id))
             .one(db)
             .await?
-            .ok_or(DbErr::RecordNotFound("Task not found.".to_string()))?
+            .ok_or(ApiError::NotFound("Task not found.".to_string()))?
             .into();
 
         Ok(task)
@@ -66,21 +429,46 @@ impl TaskQueries {
     /// # Returns
     ///
     /// A `Result` containing `GetAllTasks` with fetched task items, pagination details on success,
-    /// or a `DbErr` on failure.
+    /// or an `ApiError` on failure.
     pub async fn get_tasks(
         pagination_payload: PaginationPayload,
         db: &DbConn,
-    ) -> Result<GetAllTasks, DbErr> {
+    ) -> Result<GetAllTasks, ApiError> {
         // Extract pagination and query parameters from the payload
         let query = pagination_payload.query.unwrap_or("".to_string());
+        let filter_query = pagination_payload.filter.unwrap_or("".to_string());
         let page = pagination_payload.page;
         let size = pagination_payload.size;
 
+        // Pages are numbered from 1
+        if page < 1 || size < 1 {
+            return Err(ApiError::Validation(
+                "The page and size numbers must be greater than 0.".to_string(),
+            ));
+        }
+
+        // The offset of the page is a signed 64 bit integer in the database
+        if (page - 1)
+            .checked_mul(size)
+            .is_none_or(|offset| offset > i64::MAX as u64)
+        {
+            return Err(ApiError::Validation(
+                "The page number is too large.".to_string(),
+            ));
+        }
+
         // Construct the paginator for querying tasks
         let paginator = Entity::find()
             .find_also_related(database::entities::user::Entity)
-            .filter(Column::Name.contains(query))
+            .filter(contains_condition(&query))
+            .filter(filter::compile(&filter_query)?)
+            .filter(timestamp_condition(
+                pagination_payload.created_after,
+                pagination_payload.updated_since,
+            ))
             .filter(Column::UserId.eq(pagination_payload.user_id))
+            .order_by_asc(pagination_payload.sort.column())
+            .order_by_asc(Column::Id)
             .paginate(db, pagination_payload.size);
 
         // Retrieve the total number of pages
@@ -102,4 +490,349 @@ impl TaskQueries {
             items,
         })
     }
+
+    /// Asynchronously fetches tasks after or before a cursor, sorted by the requested key and the task ID.
+    ///
+    /// Unlike `get_tasks`, this does not count the pages and is not affected by tasks
+    /// inserted or deleted while the client is scrolling.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor_payload` - Payload containing cursor and filtering parameters.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `CursorTasks` with fetched task items and the surrounding cursors on success,
+    /// or an `ApiError` on failure.
+    pub async fn get_tasks_by_cursor(
+        cursor_payload: CursorPayload,
+        db: &DbConn,
+    ) -> Result<CursorTasks, ApiError> {
+        // Extract cursor and query parameters from the payload
+        let query = cursor_payload.query.unwrap_or("".to_string());
+        let filter_query = cursor_payload.filter.unwrap_or("".to_string());
+        let sort = cursor_payload.sort;
+        let limit = cursor_payload.limit;
+
+        if limit < 1 {
+            return Err(ApiError::Validation(
+                "The limit number must be greater than 0.".to_string(),
+            ));
+        }
+
+        // Decode the cursor, paging backward when `before` is used
+        let (cursor, backward) = match (cursor_payload.after, cursor_payload.before) {
+            (Some(_), Some(_)) => {
+                return Err(ApiError::Validation(
+                    "The after and before cursors cannot be used together.".to_string(),
+                ))
+            }
+            (Some(after), None) => (Some(TaskCursor::decode(&after)?), false),
+            (None, Some(before)) => (Some(TaskCursor::decode(&before)?), true),
+            (None, None) => (None, false),
+        };
+
+        let mut select = Entity::find()
+            .find_also_related(database::entities::user::Entity)
+            .filter(contains_condition(&query))
+            .filter(filter::compile(&filter_query)?)
+            .filter(timestamp_condition(
+                cursor_payload.created_after,
+                cursor_payload.updated_since,
+            ))
+            .filter(Column::UserId.eq(cursor_payload.user_id));
+
+        // Only fetch the tasks past the cursor
+        if let Some(cursor) = &cursor {
+            if cursor.sort != sort {
+                return Err(ApiError::Validation(
+                    "The cursor was created for a different sort.".to_string(),
+                ));
+            }
+
+            select = select.filter(cursor.condition(backward));
+        }
+
+        // Walk the list in reverse when paging backward, fetching one extra task to know if there are more
+        let order = match backward {
+            true => Order::Desc,
+            false => Order::Asc,
+        };
+
+        let mut items: Vec<TaskModel> = select
+            .order_by(sort.column(), order.clone())
+            .order_by(Column::Id, order)
+            .limit(limit + 1)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(TaskModel::from)
+            .collect();
+
+        let has_more = items.len() as u64 > limit;
+        items.truncate(limit as usize);
+
+        if backward {
+            items.reverse();
+        }
+
+        let first = items
+            .first()
+            .map(|task| TaskCursor::new(sort, task).encode());
+        let last = items
+            .last()
+            .map(|task| TaskCursor::new(sort, task).encode());
+
+        // Paging backward always comes from a later task, paging forward from an earlier one
+        let (next_cursor, prev_cursor) = match backward {
+            true => (last, first.filter(|_| has_more)),
+            false => (
+                last.filter(|_| has_more),
+                first.filter(|_| cursor.is_some()),
+            ),
+        };
+
+        Ok(CursorTasks {
+            items,
+            limit,
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
+    /// Asynchronously searches the tasks of a user with the Postgres full-text search.
+    ///
+    /// The name and description of the tasks are indexed in the `search_vector` column,
+    /// the name weighing more than the description when ranking. See `build_tsquery` for the
+    /// operators supported in the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_payload` - Payload containing the search query and pagination parameters.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `SearchTasks` with the matching tasks sorted by relevance on success,
+    /// or an `ApiError` on failure.
+    pub async fn search_tasks(
+        search_payload: SearchPayload,
+        db: &DbConn,
+    ) -> Result<SearchTasks, ApiError> {
+        let page = search_payload.page;
+        let size = search_payload.size;
+
+        if page < 1 || size < 1 {
+            return Err(ApiError::Validation(
+                "The page and size numbers must be greater than 0.".to_string(),
+            ));
+        }
+
+        let tsquery = build_tsquery(&search_payload.query).ok_or(ApiError::Validation(
+            "The search query must contain at least one word to search for.".to_string(),
+        ))?;
+
+        // Count the matching tasks
+        let total = SearchTotal::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT COUNT(*) AS total FROM tasks \
+             WHERE user_id = $1 AND search_vector @@ to_tsquery('simple', $2)",
+            [search_payload.user_id.into(), tsquery.clone().into()],
+        ))
+        .one(db)
+        .await?
+        .map_or(0, |result| result.total as u64);
+
+        // Fetch the requested page, the most relevant tasks first
+        let items = TaskSearchHit::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT t.id, t.name, t.description, t.is_active, t.user_id, t.due_date, \
+                 t.created_at, t.updated_at, \
+                 ts_rank(t.search_vector, q.query) AS rank, \
+                 ts_headline('simple', t.name, q.query, \
+                     'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') AS name_highlight, \
+                 ts_headline('simple', t.description, q.query, \
+                     'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=20, MinWords=5') \
+                     AS description_snippet \
+             FROM tasks t, to_tsquery('simple', $2) AS q(query) \
+             WHERE t.user_id = $1 AND t.search_vector @@ q.query \
+             ORDER BY rank DESC, t.id ASC \
+             LIMIT $3 OFFSET $4",
+            [
+                search_payload.user_id.into(),
+                tsquery.into(),
+                size.into(),
+                ((page - 1) * size).into(),
+            ],
+        ))
+        .all(db)
+        .await?;
+
+        Ok(SearchTasks {
+            items,
+            total,
+            size,
+            page,
+        })
+    }
+
+    /// Asynchronously fetches the tasks of a user following a task ID, by ID.
+    ///
+    /// Used to read every task of a user in batches, such as for an export.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The ID of the last task already read, `0` to start with the first task.
+    /// * `limit` - The largest number of tasks to fetch.
+    /// * `user_id` - The ID of the user owning the tasks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the tasks on success, or an `ApiError` on failure.
+    pub async fn get_tasks_after(
+        after: i32,
+        limit: u64,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Vec<database::entities::task::Model>, ApiError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Id.gt(after))
+            .order_by_asc(Column::Id)
+            .limit(limit)
+            .all(db)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn task() -> TaskModel {
+        TaskModel {
+            id: 7,
+            name: "Buy milk".to_string(),
+            description: "Two litres".to_string(),
+            is_active: false,
+            user_id: 1,
+            due_date: None,
+            priority: Default::default(),
+            tags: Vec::new(),
+            project: None,
+            version: 1,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 1, 8, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2026, 10, 18, 8, 0, 0).unwrap(),
+            user: None,
+        }
+    }
+
+    /// Renders the `WHERE` clause of a query filtered by `condition`.
+    fn sql(condition: Condition) -> String {
+        let sql = Entity::find()
+            .filter(condition)
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        sql.split_once(" WHERE ")
+            .map(|(_, clause)| clause.to_string())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn cursors_survive_encoding() {
+        for sort in [
+            TaskSort::Id,
+            TaskSort::Name,
+            TaskSort::CreatedAt,
+            TaskSort::UpdatedAt,
+        ] {
+            let cursor = TaskCursor::decode(&TaskCursor::new(sort, &task()).encode()).unwrap();
+
+            assert_eq!(cursor.sort, sort);
+            assert_eq!(cursor.id, 7);
+        }
+
+        let cursor = TaskCursor::new(TaskSort::UpdatedAt, &task());
+        assert_eq!(cursor.key.as_deref(), Some("2026-10-18T08:00:00.000000Z"));
+    }
+
+    #[test]
+    fn malformed_cursors_are_invalid() {
+        let invalid = |cursor: &str| matches!(TaskCursor::decode(cursor), Err(ApiError::Validation(message)) if message == "The cursor is invalid.");
+
+        assert!(invalid("not a cursor!"));
+        assert!(invalid(&URL_SAFE_NO_PAD.encode(b"{}")));
+        // Only cursors sorted by ID may omit the key
+        assert!(invalid(
+            &URL_SAFE_NO_PAD.encode(br#"{"sort":"name","key":null,"id":7}"#)
+        ));
+        assert!(invalid(
+            &URL_SAFE_NO_PAD.encode(br#"{"sort":"created_at","key":"yesterday","id":7}"#)
+        ));
+    }
+
+    #[test]
+    fn cursors_compare_the_sort_key_then_the_id() {
+        assert_eq!(
+            sql(TaskCursor::new(TaskSort::Id, &task()).condition(false)),
+            r#""tasks"."id" > 7"#
+        );
+        assert_eq!(
+            sql(TaskCursor::new(TaskSort::Name, &task()).condition(false)),
+            r#""tasks"."name" > 'Buy milk' OR ("tasks"."name" = 'Buy milk' AND "tasks"."id" > 7)"#
+        );
+        assert_eq!(
+            sql(TaskCursor::new(TaskSort::Name, &task()).condition(true)),
+            r#""tasks"."name" < 'Buy milk' OR ("tasks"."name" = 'Buy milk' AND "tasks"."id" < 7)"#
+        );
+    }
+
+    #[test]
+    fn words_are_combined_with_and() {
+        assert_eq!(build_tsquery("Buy  MILK").as_deref(), Some("buy & milk"));
+    }
+
+    #[test]
+    fn quoted_words_are_phrases() {
+        assert_eq!(
+            build_tsquery(r#""fresh bread" bakery"#).as_deref(),
+            Some("(fresh <-> bread) & bakery")
+        );
+        // An unclosed quote runs to the end of the query
+        assert_eq!(
+            build_tsquery(r#"milk "whole grain"#).as_deref(),
+            Some("milk & (whole <-> grain)")
+        );
+    }
+
+    #[test]
+    fn operators_negate_and_match_prefixes() {
+        assert_eq!(
+            build_tsquery(r#"groc* -milk -"oat milk""#).as_deref(),
+            Some("groc:* & !milk & !(oat <-> milk)")
+        );
+        // A star inside a phrase is not an operator
+        assert_eq!(build_tsquery(r#""groc*""#).as_deref(), Some("groc"));
+    }
+
+    #[test]
+    fn tsquery_syntax_is_not_passed_through() {
+        assert_eq!(
+            build_tsquery("milk|bread & !(eggs) <-> 'x':*").as_deref(),
+            Some("(milk <-> bread) & eggs & x:*")
+        );
+    }
+
+    #[test]
+    fn queries_without_positive_terms_are_empty() {
+        assert_eq!(build_tsquery(""), None);
+        assert_eq!(build_tsquery("   "), None);
+        assert_eq!(build_tsquery("&| !*"), None);
+        assert_eq!(build_tsquery("-milk -bread"), None);
+        assert_eq!(build_tsquery(r#""""#), None);
+    }
 }
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use crc::{Crc, CRC_32_ISO_HDLC};
+
+/// The CRC-32 of the zip format.
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Version 1.0 of the zip format, enough for stored files.
+const VERSION: u16 = 10;
+
+/// General purpose flag telling the file names are UTF-8.
+const UTF8_NAMES: u16 = 1 << 11;
+
+/// A file written to the archive, remembered for the central directory.
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+    offset: u32,
+}
+
+/// Writes a zip archive in memory, the files stored without compression.
+///
+/// Archives are limited to 65535 files and 4 GiB, as Zip64 is not written.
+#[derive(Default)]
+pub struct ZipWriter {
+    /// The archive written so far.
+    buffer: Vec<u8>,
+    /// The files written so far.
+    entries: Vec<Entry>,
+}
+
+/// Converts a timestamp to the MS-DOS time and date of the zip format, in UTC.
+fn dos_date_time(modified: DateTime<Utc>) -> (u16, u16) {
+    let time = (modified.hour() << 11) | (modified.minute() << 5) | (modified.second() / 2);
+    let date = ((modified.year().clamp(1980, 2107) - 1980) as u32) << 9
+        | (modified.month() << 5)
+        | modified.day();
+
+    (time as u16, date as u16)
+}
+
+impl ZipWriter {
+    /// Starts an empty archive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The path of the file in the archive, with `/` separating the folders.
+    /// * `content` - The content of the file.
+    /// * `modified` - The modification time of the file.
+    pub fn add(&mut self, name: &str, content: &[u8], modified: DateTime<Utc>) {
+        let (time, date) = dos_date_time(modified);
+        let entry = Entry {
+            name: name.to_string(),
+            crc: CRC32.checksum(content),
+            size: content.len() as u32,
+            time,
+            date,
+            offset: self.buffer.len() as u32,
+        };
+
+        // Local file header
+        self.buffer.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        self.header(&entry);
+        self.buffer.extend_from_slice(name.as_bytes());
+        self.buffer.extend_from_slice(content);
+
+        self.entries.push(entry);
+    }
+
+    /// Writes the fields shared by the local and the central headers of a file.
+    fn header(&mut self, entry: &Entry) {
+        self.buffer.extend_from_slice(&VERSION.to_le_bytes());
+        self.buffer.extend_from_slice(&UTF8_NAMES.to_le_bytes());
+        // Stored, without compression
+        self.buffer.extend_from_slice(&0u16.to_le_bytes());
+        self.buffer.extend_from_slice(&entry.time.to_le_bytes());
+        self.buffer.extend_from_slice(&entry.date.to_le_bytes());
+        self.buffer.extend_from_slice(&entry.crc.to_le_bytes());
+        // Compressed and uncompressed sizes
+        self.buffer.extend_from_slice(&entry.size.to_le_bytes());
+        self.buffer.extend_from_slice(&entry.size.to_le_bytes());
+        self.buffer
+            .extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+        // No extra field
+        self.buffer.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    /// Ends the archive with its central directory.
+    ///
+    /// # Returns
+    ///
+    /// The content of the archive.
+    pub fn finish(mut self) -> Vec<u8> {
+        let entries = std::mem::take(&mut self.entries);
+        let directory_offset = self.buffer.len() as u32;
+
+        for entry in &entries {
+            self.buffer.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            // Made by Unix, so the permissions below are read
+            self.buffer
+                .extend_from_slice(&(3 << 8 | VERSION).to_le_bytes());
+            self.header(entry);
+            // No comment, first disk, no internal attributes
+            self.buffer.extend_from_slice(&[0; 6]);
+            // Regular file readable by everyone
+            self.buffer
+                .extend_from_slice(&(0o100644u32 << 16).to_le_bytes());
+            self.buffer.extend_from_slice(&entry.offset.to_le_bytes());
+            self.buffer.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let directory_size = self.buffer.len() as u32 - directory_offset;
+
+        // End of central directory record
+        self.buffer.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        self.buffer.extend_from_slice(&[0; 4]);
+        self.buffer
+            .extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        self.buffer
+            .extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        self.buffer.extend_from_slice(&directory_size.to_le_bytes());
+        self.buffer
+            .extend_from_slice(&directory_offset.to_le_bytes());
+        self.buffer.extend_from_slice(&0u16.to_le_bytes());
+
+        self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a little-endian number at `offset`.
+    fn read(bytes: &[u8], offset: usize, size: usize) -> u32 {
+        bytes[offset..offset + size]
+            .iter()
+            .rev()
+            .fold(0, |number, byte| number << 8 | *byte as u32)
+    }
+
+    #[test]
+    fn files_are_stored_with_a_central_directory() {
+        let modified = DateTime::parse_from_rfc3339("2026-10-18T13:45:30Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut zip = ZipWriter::new();
+        zip.add("tasks.json", b"123456789", modified);
+        zip.add("user.json", b"{}", modified);
+        let archive = zip.finish();
+
+        // The first local header, with the CRC-32 check value of `123456789`
+        assert_eq!(read(&archive, 0, 4), 0x04034b50);
+        assert_eq!(read(&archive, 14, 4), 0xcbf43926);
+        assert_eq!(read(&archive, 18, 4), 9);
+        assert_eq!(&archive[30..40], b"tasks.json");
+        assert_eq!(&archive[40..49], b"123456789");
+        assert_eq!(dos_date_time(modified), (0x6daf, 0x5d52));
+
+        // The end record lists both files, its central directory pointing back at them
+        let end = archive.len() - 22;
+        let directory = read(&archive, end + 16, 4) as usize;
+        assert_eq!(read(&archive, end, 4), 0x06054b50);
+        assert_eq!(read(&archive, end + 10, 2), 2);
+        assert_eq!(read(&archive, directory, 4), 0x02014b50);
+        assert_eq!(read(&archive, directory + 42, 4), 0);
+        assert_eq!(read(&archive, end + 12, 4) as usize, end - directory);
+    }
+}
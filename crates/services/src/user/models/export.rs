@@ -0,0 +1,95 @@
+use crate::error::ApiError;
+use crate::user::models::archive::ZipWriter;
+use chrono::{DateTime, Utc};
+use database::entities::{
+    app_password, caldav_object, calendar_feed, idempotency_key, saved_filter, task, task_event,
+    task_tombstone, user, webhook, webhook_delivery,
+};
+use serde::Serialize;
+
+/// The account of a user, as written to `user.json`.
+#[derive(Serialize)]
+struct ExportedUser<'a> {
+    id: i32,
+    username: &'a str,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    exported_at: DateTime<Utc>,
+}
+
+/// Every piece of data tied to a user.
+///
+/// Secrets are left out: the password hashes, the webhook secrets and the calendar feed tokens.
+pub struct AccountExport {
+    /// The user.
+    pub user: user::Model,
+    /// The tasks of the user.
+    pub tasks: Vec<task::Model>,
+    /// The history of the tasks.
+    pub task_events: Vec<task_event::Model>,
+    /// The deleted tasks, as reported to the synced clients.
+    pub task_tombstones: Vec<task_tombstone::Model>,
+    /// The saved filters.
+    pub saved_filters: Vec<saved_filter::Model>,
+    /// The webhooks.
+    pub webhooks: Vec<webhook::Model>,
+    /// The deliveries of the webhooks.
+    pub webhook_deliveries: Vec<webhook_delivery::Model>,
+    /// The app passwords.
+    pub app_passwords: Vec<app_password::Model>,
+    /// The calendar feeds.
+    pub calendar_feeds: Vec<calendar_feed::Model>,
+    /// The names of the tasks for the CalDAV clients.
+    pub caldav_objects: Vec<caldav_object::Model>,
+    /// The stored responses of the idempotent requests.
+    pub idempotency_keys: Vec<idempotency_key::Model>,
+    /// When the data was gathered.
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Serializes a file of the archive as indented JSON.
+fn json<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, ApiError> {
+    serde_json::to_vec_pretty(value).map_err(|_| {
+        ApiError::Internal("An error occurred when exporting the account.".to_string())
+    })
+}
+
+impl AccountExport {
+    /// Writes the data as a zip archive holding a JSON file for each kind of data.
+    ///
+    /// The archive only holds JSON files, as tasks have no attachments: nothing else is stored for a user.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the archive, or an `ApiError` on failure.
+    pub fn into_archive(self) -> Result<Vec<u8>, ApiError> {
+        let user = ExportedUser {
+            id: self.user.id,
+            username: &self.user.username,
+            created_at: self.user.created_at,
+            updated_at: self.user.updated_at,
+            exported_at: self.exported_at,
+        };
+
+        let files = [
+            ("user.json", json(&user)?),
+            ("tasks.json", json(&self.tasks)?),
+            ("task_history.json", json(&self.task_events)?),
+            ("deleted_tasks.json", json(&self.task_tombstones)?),
+            ("saved_filters.json", json(&self.saved_filters)?),
+            ("webhooks.json", json(&self.webhooks)?),
+            ("webhook_deliveries.json", json(&self.webhook_deliveries)?),
+            ("app_passwords.json", json(&self.app_passwords)?),
+            ("calendar_feeds.json", json(&self.calendar_feeds)?),
+            ("caldav_objects.json", json(&self.caldav_objects)?),
+            ("idempotency_keys.json", json(&self.idempotency_keys)?),
+        ];
+
+        let mut zip = ZipWriter::new();
+        for (name, content) in files {
+            zip.add(name, &content, self.exported_at);
+        }
+
+        Ok(zip.finish())
+    }
+}
@@ -1 +1,3 @@
+pub mod archive;
+pub mod export;
 pub mod user;
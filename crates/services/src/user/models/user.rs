@@ -1,7 +1,9 @@
+use chrono::{DateTime, Utc};
 use database::entities::user::Model;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UserModel {
     pub id: i32,
     pub username: String,
@@ -19,3 +21,20 @@ impl From<Option<&(Model, Vec<database::entities::task::Model>)>> for UserModel
         }
     }
 }
+
+/// An account as listed to the administrators.
+#[derive(Serialize)]
+pub struct AccountSummary {
+    /// The ID of the user.
+    pub id: i32,
+    /// The username.
+    pub username: String,
+    /// The number of tasks of the user.
+    pub tasks: i64,
+    /// When the account was created.
+    pub created_at: DateTime<Utc>,
+    /// When the account was deactivated, if it is.
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// When the account is deleted for good, if its deletion is scheduled.
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
+}
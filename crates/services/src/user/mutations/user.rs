@@ -1,13 +1,54 @@
 use crate::auth::jwt::JWT;
+use crate::error::ApiError;
+use crate::user::queries::user::UserQueries;
 use bcrypt::DEFAULT_COST;
+use chrono::{DateTime, Duration, Utc};
 use database::entities::user::{ActiveModel, Column, Entity, Model};
+use database::entities::{app_password, calendar_feed};
+use sea_orm::sea_query::Expr;
 use sea_orm::ActiveValue::Set;
 use sea_orm::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Days an account stays restorable after its deletion is requested.
+pub const ACCOUNT_DELETION_GRACE_DAYS: i64 = 30;
 
 pub struct UserMutations;
 
-#[derive(Serialize, Deserialize)]
+/// Builds the update of a user revoking its auth tokens, to which the caller adds the other changes.
+///
+/// The token generation is incremented by the statement itself, so concurrent revocations never
+/// write the same generation. The update bypasses `ActiveModelBehavior`, so it sets `updated_at` too.
+fn revoke_tokens(user_id: i32, now: DateTime<Utc>) -> UpdateMany<Entity> {
+    Entity::update_many()
+        .col_expr(
+            Column::TokenGeneration,
+            Expr::col(Column::TokenGeneration).add(1),
+        )
+        .col_expr(Column::UpdatedAt, Expr::value(now))
+        .filter(Column::Id.eq(user_id))
+}
+
+/// Asynchronously runs a `revoke_tokens` update, returning the updated user.
+async fn update_user<C: ConnectionTrait>(
+    update: UpdateMany<Entity>,
+    db: &C,
+) -> Result<Model, ApiError> {
+    update
+        .exec_with_returning(db)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::NotFound("User not found.".to_string()))
+}
+
+/// The error of signing in to a deactivated account.
+fn deactivated() -> ApiError {
+    ApiError::Forbidden("The account is deactivated, contact an administrator.".to_string())
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct SignIn {
     token: String,
     token_type: String,
@@ -15,43 +56,50 @@ pub struct SignIn {
     username: String,
 }
 
+/// A scheduled deletion of an account.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AccountDeletion {
+    /// When the account is deleted for good, unless restored before.
+    #[schema(value_type = String, format = DateTime)]
+    pub deletion_scheduled_at: DateTime<Utc>,
+}
+
 impl UserMutations {
-    pub async fn create(username: String, password: String, db: &DbConn) -> Result<Model, DbErr> {
-        let user_exist = Entity::find()
-            .filter(Column::Username.contains(&username))
-            .one(db)
-            .await?;
+    pub async fn create(
+        username: String,
+        password: String,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        let user_exist = UserQueries::find_by_username(&username).one(db).await?;
 
         if user_exist.is_some() {
-            return Err(DbErr::Custom(format!(
+            return Err(ApiError::Conflict(format!(
                 "Already exist an user with the username {}.",
                 username
             )));
         }
 
-        let hashed_password = bcrypt::hash(&password, DEFAULT_COST).unwrap();
+        let hashed_password = bcrypt::hash(&password, DEFAULT_COST).map_err(|_| {
+            ApiError::Internal("An error occurred when hashing the password.".to_string())
+        })?;
         let user = ActiveModel {
             username: Set(username.to_owned()),
             password: Set(hashed_password),
             ..Default::default()
         };
 
-        let user_created = Entity::insert(user).exec(db).await?;
-        Ok(Model {
-            id: user_created.last_insert_id,
-            username: username.to_owned(),
-            password: password.to_owned(),
-        })
+        Ok(user.insert(db).await?)
     }
 
-    pub async fn sign_in(username: String, password: String, db: &DbConn) -> Result<SignIn, DbErr> {
-        let user = Entity::find()
-            .filter(Column::Username.contains(username))
-            .one(db)
-            .await?;
+    pub async fn sign_in(
+        username: String,
+        password: String,
+        db: &DbConn,
+    ) -> Result<SignIn, ApiError> {
+        let user = UserQueries::find_by_username(&username).one(db).await?;
 
         if user.is_none() {
-            return Err(DbErr::RecordNotFound(
+            return Err(ApiError::Unauthorized(
                 "Cannot find an user with these credentials.".to_string(),
             ));
         };
@@ -59,13 +107,37 @@ impl UserMutations {
         let user = user.unwrap();
 
         let password_try = bcrypt::verify(&password, &user.password);
-        if password_try.is_err() {
-            return Err(DbErr::Custom("Invalid password.".to_string()));
+        if !password_try.unwrap_or(false) {
+            return Err(ApiError::Unauthorized("Invalid password.".to_string()));
+        }
+
+        if user.deactivated_at.is_some() {
+            return Err(deactivated());
+        }
+
+        if let Some(deletion_scheduled_at) = user.deletion_scheduled_at {
+            return Err(ApiError::Forbidden(format!(
+                "The account is scheduled for deletion on {}, restore it to sign in.",
+                deletion_scheduled_at.format("%Y-%m-%d %H:%M UTC")
+            )));
         }
 
-        let token = JWT::encode(&user.id);
+        Self::issue_token(user)
+    }
+
+    /// Issues an auth token for a user who proved their credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The user.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SignIn` with the token, or an `ApiError` on failure.
+    fn issue_token(user: Model) -> Result<SignIn, ApiError> {
+        let token = JWT::encode(&user.id, user.token_generation);
         if token.is_err() {
-            return Err(DbErr::Custom(
+            return Err(ApiError::Internal(
                 "An error occurred when creating auth token.".to_string(),
             ));
         }
@@ -77,4 +149,218 @@ impl UserMutations {
             user_id: user.id,
         })
     }
+
+    /// Asynchronously schedules the deletion of an account, after the grace period.
+    ///
+    /// Until then the account is locked: its auth tokens are revoked, and its app passwords and calendar feeds,
+    /// which are credentials too, are deleted. Signing in again requires restoring the account.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `password` - The password of the user, confirming the deletion.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AccountDeletion` on success, `ApiError::Forbidden` if the password is wrong,
+    /// or another `ApiError` on failure.
+    pub async fn schedule_deletion(
+        user_id: i32,
+        password: String,
+        db: &DbConn,
+    ) -> Result<AccountDeletion, ApiError> {
+        let user = Entity::find_by_id(user_id).one(db).await?.ok_or_else(|| {
+            ApiError::Unauthorized("The user of this auth token no longer exists.".to_string())
+        })?;
+
+        if !bcrypt::verify(&password, &user.password).unwrap_or(false) {
+            return Err(ApiError::Forbidden("Invalid password.".to_string()));
+        }
+
+        let now = Utc::now();
+        let deletion_scheduled_at = now + Duration::days(ACCOUNT_DELETION_GRACE_DAYS);
+        let txn = db.begin().await?;
+
+        app_password::Entity::delete_many()
+            .filter(app_password::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        calendar_feed::Entity::delete_many()
+            .filter(calendar_feed::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+
+        update_user(
+            revoke_tokens(user.id, now).col_expr(
+                Column::DeletionScheduledAt,
+                Expr::value(deletion_scheduled_at),
+            ),
+            &txn,
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        Ok(AccountDeletion {
+            deletion_scheduled_at,
+        })
+    }
+
+    /// Asynchronously cancels the scheduled deletion of an account, signing the user in.
+    ///
+    /// The auth tokens revoked by the deletion stay revoked, and the app passwords and calendar feeds are not restored.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    /// * `password` - The password of the user.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `SignIn` on success, `ApiError::Unauthorized` if the credentials are invalid,
+    /// `ApiError::Conflict` if the account is not scheduled for deletion, or another `ApiError` on failure.
+    pub async fn restore(
+        username: String,
+        password: String,
+        db: &DbConn,
+    ) -> Result<SignIn, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid credentials.".to_string());
+
+        let user = UserQueries::find_by_username(&username)
+            .one(db)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if !bcrypt::verify(&password, &user.password).unwrap_or(false) {
+            return Err(invalid());
+        }
+
+        if user.deactivated_at.is_some() {
+            return Err(deactivated());
+        }
+
+        if user.deletion_scheduled_at.is_none() {
+            return Err(ApiError::Conflict(
+                "The account is not scheduled for deletion.".to_string(),
+            ));
+        }
+
+        let mut user: ActiveModel = user.into();
+        user.deletion_scheduled_at = Set(None);
+        let user = user.update(db).await?;
+
+        Self::issue_token(user)
+    }
+
+    /// Asynchronously deletes the accounts whose grace period is over.
+    ///
+    /// Every table holding data of a user references it with `ON DELETE CASCADE`, the tasks through `fk_user`,
+    /// so deleting the user deletes its tasks, their history, its saved filters, webhooks and their deliveries,
+    /// CalDAV objects and idempotency keys along with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of deleted accounts on success, or an `ApiError` on failure.
+    pub async fn purge_deleted(db: &DbConn) -> Result<u64, ApiError> {
+        let deleted = Entity::delete_many()
+            .filter(Column::DeletionScheduledAt.lte(Utc::now()))
+            .exec(db)
+            .await?;
+
+        Ok(deleted.rows_affected)
+    }
+
+    /// Asynchronously replaces the password of a user, revoking the auth tokens issued before.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    /// * `password` - The new password.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated user on success, `ApiError::NotFound` if there is no such user,
+    /// or another `ApiError` on failure.
+    pub async fn reset_password(
+        username: &str,
+        password: String,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        let user = UserQueries::get_user_by_username(username, db).await?;
+
+        let hashed_password = bcrypt::hash(&password, DEFAULT_COST).map_err(|_| {
+            ApiError::Internal("An error occurred when hashing the password.".to_string())
+        })?;
+
+        update_user(
+            revoke_tokens(user.id, Utc::now())
+                .col_expr(Column::Password, Expr::value(hashed_password)),
+            db,
+        )
+        .await
+    }
+
+    /// Asynchronously deactivates an account, locking it until it is activated again.
+    ///
+    /// Its auth tokens are revoked, and its app passwords and calendar feeds are rejected while it is deactivated.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated user on success, `ApiError::NotFound` if there is no such user,
+    /// `ApiError::Conflict` if the account is already deactivated, or another `ApiError` on failure.
+    pub async fn deactivate(username: &str, db: &DbConn) -> Result<Model, ApiError> {
+        let user = UserQueries::get_user_by_username(username, db).await?;
+
+        if user.deactivated_at.is_some() {
+            return Err(ApiError::Conflict(format!(
+                "The account of {username} is already deactivated."
+            )));
+        }
+
+        let now = Utc::now();
+
+        update_user(
+            revoke_tokens(user.id, now).col_expr(Column::DeactivatedAt, Expr::value(now)),
+            db,
+        )
+        .await
+    }
+
+    /// Asynchronously activates a deactivated account.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated user on success, `ApiError::NotFound` if there is no such user,
+    /// `ApiError::Conflict` if the account is not deactivated, or another `ApiError` on failure.
+    pub async fn activate(username: &str, db: &DbConn) -> Result<Model, ApiError> {
+        let user = UserQueries::get_user_by_username(username, db).await?;
+
+        if user.deactivated_at.is_none() {
+            return Err(ApiError::Conflict(format!(
+                "The account of {username} is not deactivated."
+            )));
+        }
+
+        let mut user: ActiveModel = user.into();
+        user.deactivated_at = Set(None);
+
+        Ok(user.update(db).await?)
+    }
 }
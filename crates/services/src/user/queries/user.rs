@@ -1,20 +1,279 @@
-use crate::auth::jwt::JWT;
-use crate::user::models::user::UserModel;
-use database::entities::user::{Column, Entity};
+use crate::auth::jwt::{Claims, JWT};
+use crate::error::ApiError;
+use crate::user::models::export::AccountExport;
+use crate::user::models::user::{AccountSummary, UserModel};
+use chrono::Utc;
+use database::entities::user::{Column, Entity, Model};
+use database::entities::{
+    app_password, caldav_object, calendar_feed, idempotency_key, saved_filter, task, task_event,
+    task_tombstone, webhook, webhook_delivery,
+};
 use sea_orm::*;
 
 pub struct UserQueries;
 
+/// Returns whether the auth token of `claims` was revoked, being issued in an older generation than the current one of `user`.
+fn is_revoked(claims: &Claims, user: &Model) -> bool {
+    claims.gen != user.token_generation
+}
+
 impl UserQueries {
-    pub async fn get_current_user(user: JWT, db: &DbConn) -> Result<UserModel, DbErr> {
-        let user: UserModel = Entity::find()
+    /// Builds the query of the user with a username, compared as a whole and not as a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    ///
+    /// # Returns
+    ///
+    /// The query, matching at most one user.
+    pub(crate) fn find_by_username(username: &str) -> Select<Entity> {
+        Entity::find().filter(Column::Username.eq(username))
+    }
+
+    pub async fn get_current_user(user: JWT, db: &DbConn) -> Result<UserModel, ApiError> {
+        let users = Entity::find()
             .filter(Column::Id.eq(user.claims.sub))
             .find_with_related(database::entities::task::Entity)
             .all(db)
+            .await?;
+
+        if users.is_empty() {
+            return Err(ApiError::Unauthorized(
+                "The user of this auth token no longer exists.".to_string(),
+            ));
+        }
+
+        Ok(users.first().into())
+    }
+
+    /// Asynchronously fetches the user of an auth token, checking the token is still honoured.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The claims of the auth token.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the user on success, or an `ApiError::Unauthorized` if the user no longer exists,
+    /// is scheduled for deletion, is deactivated or revoked the tokens issued before this one.
+    pub async fn get_token_user(claims: &Claims, db: &DbConn) -> Result<Model, ApiError> {
+        let user = Entity::find_by_id(claims.sub)
+            .one(db)
             .await?
-            .first()
-            .into();
+            .ok_or_else(|| {
+                ApiError::Unauthorized("The user of this auth token no longer exists.".to_string())
+            })?;
+
+        if user.deletion_scheduled_at.is_some() {
+            return Err(ApiError::Unauthorized(
+                "The account of this auth token is scheduled for deletion.".to_string(),
+            ));
+        }
+
+        if user.deactivated_at.is_some() {
+            return Err(ApiError::Unauthorized(
+                "The account of this auth token is deactivated.".to_string(),
+            ));
+        }
+
+        // Tokens issued before the last revocation belong to an older generation, even within the same second
+        if is_revoked(claims, &user) {
+            return Err(ApiError::Unauthorized(
+                "The auth token has been revoked.".to_string(),
+            ));
+        }
 
         Ok(user)
     }
+
+    /// Asynchronously gathers every piece of data tied to a user, for the export of the account.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AccountExport` on success, or an `ApiError` on failure.
+    pub async fn get_account_export(user_id: i32, db: &DbConn) -> Result<AccountExport, ApiError> {
+        let user = Entity::find_by_id(user_id).one(db).await?.ok_or_else(|| {
+            ApiError::Unauthorized("The user of this auth token no longer exists.".to_string())
+        })?;
+
+        let webhooks = webhook::Entity::find()
+            .filter(webhook::Column::UserId.eq(user_id))
+            .order_by_asc(webhook::Column::Id)
+            .all(db)
+            .await?;
+        let webhook_deliveries = webhook_delivery::Entity::find()
+            .filter(
+                webhook_delivery::Column::WebhookId
+                    .is_in(webhooks.iter().map(|webhook| webhook.id)),
+            )
+            .order_by_asc(webhook_delivery::Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(AccountExport {
+            tasks: task::Entity::find()
+                .filter(task::Column::UserId.eq(user_id))
+                .order_by_asc(task::Column::Id)
+                .all(db)
+                .await?,
+            task_events: task_event::Entity::find()
+                .filter(task_event::Column::UserId.eq(user_id))
+                .order_by_asc(task_event::Column::Id)
+                .all(db)
+                .await?,
+            task_tombstones: task_tombstone::Entity::find()
+                .filter(task_tombstone::Column::UserId.eq(user_id))
+                .order_by_asc(task_tombstone::Column::ChangeSeq)
+                .all(db)
+                .await?,
+            saved_filters: saved_filter::Entity::find()
+                .filter(saved_filter::Column::UserId.eq(user_id))
+                .order_by_asc(saved_filter::Column::Id)
+                .all(db)
+                .await?,
+            webhooks,
+            webhook_deliveries,
+            app_passwords: app_password::Entity::find()
+                .filter(app_password::Column::UserId.eq(user_id))
+                .order_by_asc(app_password::Column::Id)
+                .all(db)
+                .await?,
+            calendar_feeds: calendar_feed::Entity::find()
+                .filter(calendar_feed::Column::UserId.eq(user_id))
+                .order_by_asc(calendar_feed::Column::Id)
+                .all(db)
+                .await?,
+            caldav_objects: caldav_object::Entity::find()
+                .filter(caldav_object::Column::UserId.eq(user_id))
+                .order_by_asc(caldav_object::Column::TaskId)
+                .all(db)
+                .await?,
+            idempotency_keys: idempotency_key::Entity::find()
+                .filter(idempotency_key::Column::UserId.eq(user_id))
+                .order_by_asc(idempotency_key::Column::Id)
+                .all(db)
+                .await?,
+            user,
+            exported_at: Utc::now(),
+        })
+    }
+
+    /// Asynchronously fetches the users with the given IDs, in one query.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The IDs of the users.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the users found, in no particular order, or an `ApiError` on failure.
+    pub async fn get_users_by_ids(ids: &[i32], db: &DbConn) -> Result<Vec<Model>, ApiError> {
+        Ok(Entity::find()
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .all(db)
+            .await?)
+    }
+
+    /// Asynchronously fetches a user by username, the exact one.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the user.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the user on success, `ApiError::NotFound` if there is no such user,
+    /// or another `ApiError` on failure.
+    pub async fn get_user_by_username(username: &str, db: &DbConn) -> Result<Model, ApiError> {
+        Self::find_by_username(username)
+            .one(db)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Cannot find the user {username}.")))
+    }
+
+    /// Asynchronously fetches every account with the number of its tasks, for the administrators.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the accounts ordered by ID on success, or an `ApiError` on failure.
+    pub async fn get_account_summaries(db: &DbConn) -> Result<Vec<AccountSummary>, ApiError> {
+        let users = Entity::find().order_by_asc(Column::Id).all(db).await?;
+
+        let task_counts = task::Entity::find()
+            .select_only()
+            .column(task::Column::UserId)
+            .column_as(task::Column::Id.count(), "tasks")
+            .group_by(task::Column::UserId)
+            .into_tuple::<(i32, i64)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect::<std::collections::HashMap<_, _>>();
+
+        Ok(users
+            .into_iter()
+            .map(|user| AccountSummary {
+                tasks: task_counts.get(&user.id).copied().unwrap_or_default(),
+                id: user.id,
+                username: user.username,
+                created_at: user.created_at,
+                deactivated_at: user.deactivated_at,
+                deletion_scheduled_at: user.deletion_scheduled_at,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usernames_are_compared_as_a_whole() {
+        // `bob` must neither find `bobby` nor treat `%` and `_` as wildcards
+        let sql = UserQueries::find_by_username("bob%")
+            .build(DbBackend::Postgres)
+            .to_string();
+
+        assert!(sql.contains(r#""users"."username" = 'bob%'"#), "{sql}");
+        assert!(!sql.contains("LIKE"), "{sql}");
+    }
+
+    #[test]
+    fn tokens_of_older_generations_are_revoked() {
+        let now = Utc::now();
+        let user = Model {
+            id: 1,
+            username: "bobby".to_string(),
+            password: String::new(),
+            created_at: now,
+            updated_at: now,
+            token_generation: 2,
+            deletion_scheduled_at: None,
+            deactivated_at: None,
+        };
+        // Issued in the same second as the revocation, before and after it
+        let claims = |gen| Claims {
+            sub: 1,
+            exp: now.timestamp() as usize + 3600,
+            iat: now.timestamp() as usize,
+            gen,
+        };
+
+        assert!(is_revoked(&claims(1), &user));
+        assert!(!is_revoked(&claims(2), &user));
+    }
 }
@@ -0,0 +1,199 @@
+use reqwest::dns::{Name, Resolve, Resolving};
+use rocket::tokio::net::lookup_host;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Returns whether an IPv4 address is reachable on the public internet.
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        // Link-local, with the metadata service of the cloud providers at 169.254.169.254
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // This network, 0.0.0.0/8
+        || a == 0
+        // Shared address space of the carrier-grade NATs, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (18..20).contains(&b))
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+/// Returns whether an IPv6 address is reachable on the public internet.
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+
+    // IPv4 addresses written as IPv6 ones are judged as IPv4
+    if let Some(ip) = ip.to_ipv4_mapped() {
+        return is_public_v4(ip);
+    }
+    // NAT64 addresses, 64:ff9b::/96, embed the IPv4 address they reach
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let octets = ip.octets();
+        return is_public_v4(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        ));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Deprecated IPv4-compatible addresses, ::/96
+        || segments[..6] == [0; 6]
+        // Unique local, with the metadata service of AWS at fd00:ec2::254
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+/// Returns whether an address is reachable on the public internet.
+///
+/// Loopback, private, link-local, unspecified and reserved addresses are not, so webhooks cannot reach
+/// the server itself, its network or the metadata service of its cloud provider.
+///
+/// # Arguments
+///
+/// * `ip` - The address.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+/// Asynchronously checks that every address a webhook URL points to is public.
+///
+/// # Arguments
+///
+/// * `url` - The URL of the webhook.
+///
+/// # Returns
+///
+/// A `Result` with `()` if every address is public, or a message telling why the URL is refused.
+pub async fn check_url(url: &str) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|_| "The URL is invalid.".to_string())?;
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let addresses = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![IpAddr::V4(ip)],
+        Some(Host::Ipv6(ip)) => vec![IpAddr::V6(ip)],
+        Some(Host::Domain(domain)) => lookup_host((domain, port))
+            .await
+            .map_err(|_| format!("The host {domain} cannot be resolved."))?
+            .map(|address| address.ip())
+            .collect(),
+        None => return Err("The URL must have a host.".to_string()),
+    };
+
+    if addresses.is_empty() {
+        return Err("The host of the URL has no address.".to_string());
+    }
+
+    match addresses.into_iter().find(|ip| !is_public(*ip)) {
+        Some(ip) => Err(format!("The URL must point to a public address, not {ip}.")),
+        None => Ok(()),
+    }
+}
+
+/// DNS resolver of the webhook client, refusing the names resolving to any non-public address.
+///
+/// Connections use the addresses checked here, so a name changing its answer after the webhook
+/// was created, as in a DNS rebinding attack, cannot reach a private address.
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            // The connector sets the port of the URL
+            let addresses = lookup_host((name.as_str(), 0)).await?.collect::<Vec<_>>();
+
+            if let Some(address) = addresses.iter().find(|address| !is_public(address.ip())) {
+                return Err(format!(
+                    "{} resolves to the non-public address {}.",
+                    name.as_str(),
+                    address.ip()
+                )
+                .into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Box<dyn Iterator<Item = _> + Send>)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_public_addresses_are_rejected() {
+        for ip in [
+            "127.0.0.1",
+            "127.1.2.3",
+            "10.0.0.8",
+            "172.16.4.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "255.255.255.255",
+            "224.0.0.1",
+            "::1",
+            "::",
+            "fe80::1",
+            "fd00:ec2::254",
+            "::ffff:10.0.0.1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} is not public");
+        }
+    }
+
+    #[test]
+    fn public_addresses_are_accepted() {
+        for ip in [
+            "93.184.216.34",
+            "1.1.1.1",
+            "172.32.0.1",
+            "2606:4700:4700::1111",
+            "::ffff:8.8.8.8",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} is public");
+        }
+    }
+
+    #[rocket::async_test]
+    async fn urls_of_non_public_addresses_are_refused() {
+        for url in [
+            "http://127.0.0.1:8000/hook",
+            "http://10.1.2.3/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]/hook",
+            "http://[::ffff:192.168.0.1]/hook",
+            "http://0.0.0.0/hook",
+            "http://localhost:8000/hook",
+        ] {
+            assert!(check_url(url).await.is_err(), "{url} is refused");
+        }
+
+        assert_eq!(check_url("https://93.184.216.34/hook").await, Ok(()));
+    }
+
+    #[rocket::async_test]
+    async fn the_resolver_refuses_names_of_non_public_addresses() {
+        let resolved = PublicResolver.resolve("localhost".parse().unwrap()).await;
+
+        assert!(resolved.is_err());
+    }
+}
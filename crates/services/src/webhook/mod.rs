@@ -0,0 +1,5 @@
+pub mod address;
+pub mod models;
+pub mod mutations;
+pub mod queries;
+pub mod sender;
@@ -0,0 +1,112 @@
+use crate::task::models::change::{TaskChange, TaskChangeKind};
+use chrono::{Duration, Utc};
+use database::entities::webhook_delivery::Model as DeliveryEntity;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+/// Events a webhook can subscribe to.
+pub const WEBHOOK_EVENTS: [&str; 3] = [
+    TaskChangeKind::Created.event_name(),
+    TaskChangeKind::Updated.event_name(),
+    TaskChangeKind::Deleted.event_name(),
+];
+
+/// Number of attempts after which a delivery is given up.
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+
+/// Delay before the second attempt of a delivery, doubled after every failed attempt.
+const FIRST_RETRY_DELAY: Duration = Duration::seconds(30);
+
+/// Longest delay between two attempts of a delivery.
+const MAX_RETRY_DELAY: Duration = Duration::hours(1);
+
+/// State of a webhook delivery.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// The delivery waits for its next attempt.
+    Pending,
+    /// The receiver answered with a `2xx` status.
+    Delivered,
+    /// Every attempt failed, the delivery is given up.
+    Failed,
+}
+
+impl DeliveryStatus {
+    /// Returns the name stored in the `status` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Returns how long to wait before retrying a delivery that failed `attempts` times.
+///
+/// # Arguments
+///
+/// * `attempts` - The number of failed attempts, at least `1`.
+///
+/// # Returns
+///
+/// The delay, doubling from 30 seconds up to an hour.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.clamp(1, 16) - 1;
+
+    (FIRST_RETRY_DELAY * 2i32.pow(doublings as u32)).min(MAX_RETRY_DELAY)
+}
+
+/// Builds the JSON body sent to the webhooks subscribed to a task change.
+///
+/// # Arguments
+///
+/// * `change` - The committed task change.
+///
+/// # Returns
+///
+/// The serialized body, holding the event name, the position of the change in the change feed,
+/// when it was published and the task (only its `id` once deleted).
+pub fn event_payload(change: &TaskChange) -> String {
+    let data = match &change.task {
+        Some(task) => json!(task),
+        None => json!({ "id": change.task_id }),
+    };
+
+    json!({
+        "event": change.kind.event_name(),
+        "seq": change.seq,
+        "occurred_at": Utc::now(),
+        "data": data,
+    })
+    .to_string()
+}
+
+/// Structure representing a page of the delivery log of a webhook.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveries {
+    /// Deliveries of the page, the latest first.
+    pub items: Vec<DeliveryEntity>,
+    /// Total number of pages based on pagination settings and query results.
+    pub num_pages: u64,
+    /// Number of items per page.
+    pub size: u64,
+    /// Current page number fetched.
+    pub page: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_an_hour() {
+        assert_eq!(retry_delay(1), Duration::seconds(30));
+        assert_eq!(retry_delay(2), Duration::seconds(60));
+        assert_eq!(retry_delay(4), Duration::seconds(240));
+        assert_eq!(retry_delay(8), Duration::hours(1));
+        assert_eq!(retry_delay(i32::MAX), Duration::hours(1));
+    }
+}
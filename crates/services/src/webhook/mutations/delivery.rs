@@ -0,0 +1,238 @@
+use crate::error::ApiError;
+use crate::task::models::change::TaskChange;
+use crate::webhook::models::webhook::{
+    event_payload, retry_delay, DeliveryStatus, MAX_DELIVERY_ATTEMPTS,
+};
+use crate::webhook::queries::webhook::WebhookQueries;
+use crate::webhook::sender::{DeliveryOutcome, WebhookSender};
+use chrono::Utc;
+use database::entities::webhook;
+use database::entities::webhook_delivery::{ActiveModel, Column, Entity, Model};
+use rocket::futures::future::join_all;
+use sea_orm::*;
+use std::collections::HashMap;
+
+/// Number of due deliveries sent at once by the worker.
+pub const DELIVERY_BATCH: u64 = 20;
+
+/// How long a claimed delivery is hidden from the other workers, longer than an attempt can take.
+const CLAIM_LEASE_SECONDS: i32 = 60;
+
+/// Struct for handling the outbox of the webhook deliveries.
+pub struct WebhookDeliveryMutation;
+
+impl WebhookDeliveryMutation {
+    /// Asynchronously queues a task change for the active webhooks of its user subscribed to it.
+    ///
+    /// Called in the transaction writing the change, so a delivery exists if and only if the change is committed.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - The task change.
+    /// * `db` - SeaORM connection or transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the number of queued deliveries on success, or an `ApiError` on failure.
+    pub async fn enqueue<C: ConnectionTrait>(
+        change: &TaskChange,
+        db: &C,
+    ) -> Result<usize, ApiError> {
+        let event = change.kind.event_name();
+
+        let webhooks = webhook::Entity::find()
+            .filter(webhook::Column::UserId.eq(change.user_id))
+            .filter(webhook::Column::IsActive.eq(true))
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|webhook| {
+                webhook
+                    .events
+                    .as_array()
+                    .is_some_and(|events| events.iter().any(|subscribed| subscribed == event))
+            })
+            .collect::<Vec<_>>();
+
+        if webhooks.is_empty() {
+            return Ok(0);
+        }
+
+        let payload = event_payload(change);
+        let deliveries = webhooks.iter().map(|webhook| ActiveModel {
+            webhook_id: Set(webhook.id),
+            event: Set(event.to_string()),
+            payload: Set(payload.clone()),
+            ..Default::default()
+        });
+
+        Entity::insert_many(deliveries).exec(db).await?;
+
+        Ok(webhooks.len())
+    }
+
+    /// Asynchronously claims the pending deliveries that are due, the oldest first.
+    ///
+    /// A claimed delivery is pushed back by a short lease, so several API instances can drain
+    /// the outbox without sending it twice, and a delivery claimed by a crashed worker is retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of deliveries to claim.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the claimed deliveries on success, or an `ApiError` on failure.
+    pub async fn claim_due(limit: u64, db: &DbConn) -> Result<Vec<Model>, ApiError> {
+        Ok(Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"UPDATE webhook_deliveries
+                   SET next_attempt_at = now() + make_interval(secs => $1)
+                   WHERE id IN (
+                       SELECT id FROM webhook_deliveries
+                       WHERE status = $2 AND next_attempt_at <= now()
+                       ORDER BY next_attempt_at
+                       LIMIT $3
+                       FOR UPDATE SKIP LOCKED
+                   )
+                   RETURNING *"#,
+                [
+                    CLAIM_LEASE_SECONDS.into(),
+                    DeliveryStatus::Pending.as_str().into(),
+                    (limit as i64).into(),
+                ],
+            ))
+            .all(db)
+            .await?)
+    }
+
+    /// Asynchronously records the outcome of an attempt, scheduling the next one on failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `delivery` - The attempted delivery.
+    /// * `outcome` - The outcome of the attempt.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated `Model` on success, or an `ApiError` on failure.
+    pub async fn record(
+        delivery: Model,
+        outcome: DeliveryOutcome,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        let now = Utc::now();
+        let attempts = delivery.attempts + 1;
+
+        // A finished delivery keeps the time of its last attempt
+        let (status, next_attempt_at) = match outcome.is_success() {
+            true => (DeliveryStatus::Delivered, now),
+            false if attempts >= MAX_DELIVERY_ATTEMPTS => (DeliveryStatus::Failed, now),
+            false => (DeliveryStatus::Pending, now + retry_delay(attempts)),
+        };
+
+        let mut delivery: ActiveModel = delivery.into();
+        delivery.status = Set(status.as_str().to_string());
+        delivery.attempts = Set(attempts);
+        delivery.next_attempt_at = Set(next_attempt_at);
+        delivery.last_attempt_at = Set(Some(now));
+        delivery.response_status = Set(outcome.status.map(|status| status as i16));
+        delivery.error = Set(outcome.error);
+
+        Ok(delivery.update(db).await?)
+    }
+
+    /// Asynchronously sends a batch of due deliveries and records their outcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `sender` - The HTTP client posting the deliveries.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the number of attempted deliveries on success, or an `ApiError` on failure.
+    pub async fn deliver_due(sender: &WebhookSender, db: &DbConn) -> Result<usize, ApiError> {
+        let deliveries = Self::claim_due(DELIVERY_BATCH, db).await?;
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let webhooks = webhook::Entity::find()
+            .filter(
+                webhook::Column::Id.is_in(deliveries.iter().map(|delivery| delivery.webhook_id)),
+            )
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|webhook| (webhook.id, webhook))
+            .collect::<HashMap<_, _>>();
+
+        // Send the batch concurrently, a disabled webhook fails its pending deliveries
+        let attempts = deliveries.into_iter().map(|delivery| async {
+            let outcome = match webhooks.get(&delivery.webhook_id) {
+                Some(webhook) if webhook.is_active => sender.send(webhook, &delivery).await,
+                _ => DeliveryOutcome {
+                    error: Some("The webhook is disabled.".to_string()),
+                    ..Default::default()
+                },
+            };
+
+            Self::record(delivery, outcome, db).await
+        });
+        let results = join_all(attempts).await;
+
+        let attempted = results.len();
+        results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        Ok(attempted)
+    }
+
+    /// Asynchronously queues a new delivery of the payload of an earlier one.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_id` - The ID of the webhook.
+    /// * `delivery_id` - The ID of the delivery to send again.
+    /// * `user_id` - The ID of the user owning the webhook.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new pending delivery on success, `ApiError::NotFound` if the
+    /// delivery does not exist or `ApiError::Conflict` if it is still pending.
+    pub async fn redeliver(
+        webhook_id: i32,
+        delivery_id: i32,
+        user_id: i32,
+        db: &DbConn,
+    ) -> Result<Model, ApiError> {
+        WebhookQueries::get_webhook(webhook_id, user_id, db).await?;
+
+        let delivery = Entity::find_by_id(delivery_id)
+            .filter(Column::WebhookId.eq(webhook_id))
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("Delivery not found.")))?;
+
+        if delivery.status == DeliveryStatus::Pending.as_str() {
+            return Err(ApiError::Conflict(String::from(
+                "The delivery is still pending, it will be retried.",
+            )));
+        }
+
+        // The payload is sent as it was, with a new delivery ID and signature
+        let redelivery = ActiveModel {
+            webhook_id: Set(webhook_id),
+            event: Set(delivery.event),
+            payload: Set(delivery.payload),
+            redelivery_of: Set(Some(delivery.id)),
+            ..Default::default()
+        };
+
+        Ok(redelivery.insert(db).await?)
+    }
+}
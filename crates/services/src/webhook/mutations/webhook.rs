@@ -0,0 +1,180 @@
+use crate::error::ApiError;
+use crate::webhook::address;
+use crate::webhook::models::webhook::WEBHOOK_EVENTS;
+use database::entities::webhook::{ActiveModel, Column, Entity, Model};
+use sea_orm::*;
+use serde_json::json;
+use url::Url;
+
+/// Struct for handling mutations (create, update, delete) on webhooks.
+pub struct WebhookMutation;
+
+/// Payload structure for creating or updating a webhook.
+pub struct WebhookPayload {
+    /// The URL the deliveries are posted to.
+    pub url: String,
+    /// The events the webhook subscribes to.
+    pub events: Vec<String>,
+    /// The secret signing the deliveries, kept unchanged on update when missing.
+    pub secret: Option<String>,
+    /// Whether deliveries are sent, defaults to `true` on creation.
+    pub is_active: Option<bool>,
+    /// The ID of the user owning the webhook.
+    pub user_id: i32,
+}
+
+impl WebhookPayload {
+    /// Makes sure the payload describes a webhook that can be delivered to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `()` on success, or an `ApiError::Validation` describing the invalid field.
+    fn validate(&self) -> Result<(), ApiError> {
+        let url = Url::parse(&self.url)
+            .map_err(|_| ApiError::Validation("The URL is invalid.".to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host().is_none() {
+            return Err(ApiError::Validation(
+                "The URL must be an http or https URL.".to_string(),
+            ));
+        }
+
+        if self.events.is_empty() {
+            return Err(ApiError::Validation(
+                "The webhook must subscribe to at least one event.".to_string(),
+            ));
+        }
+        if let Some(event) = self
+            .events
+            .iter()
+            .find(|event| !WEBHOOK_EVENTS.contains(&event.as_str()))
+        {
+            return Err(ApiError::Validation(format!(
+                "The event `{}` does not exist, use one of {}.",
+                event,
+                WEBHOOK_EVENTS.join(", ")
+            )));
+        }
+
+        if self
+            .secret
+            .as_ref()
+            .is_some_and(|secret| !(16..=255).contains(&secret.chars().count()))
+        {
+            return Err(ApiError::Validation(
+                "The secret must be between 16 to 255 characters long.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the subscribed events without duplicates, in the order of `WEBHOOK_EVENTS`.
+    fn events(&self) -> serde_json::Value {
+        json!(WEBHOOK_EVENTS
+            .iter()
+            .filter(|event| self.events.iter().any(|subscribed| subscribed == *event))
+            .collect::<Vec<_>>())
+    }
+}
+
+impl WebhookMutation {
+    /// Asynchronously creates a new webhook with the provided payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - Payload containing the webhook details, the secret is required.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the created `Model` on success, or an `ApiError` on failure.
+    pub async fn create(payload: WebhookPayload, db: &DbConn) -> Result<Model, ApiError> {
+        payload.validate()?;
+        address::check_url(&payload.url)
+            .await
+            .map_err(ApiError::Validation)?;
+
+        let secret = payload
+            .secret
+            .clone()
+            .ok_or(ApiError::Validation("The secret is required.".to_string()))?;
+
+        // Create an ActiveModel instance with the payload data
+        let active_model = ActiveModel {
+            url: Set(payload.url.clone()),
+            events: Set(payload.events()),
+            secret: Set(secret),
+            is_active: Set(payload.is_active.unwrap_or(true)),
+            user_id: Set(payload.user_id),
+            ..Default::default()
+        };
+
+        // Execute the insert operation and return the stored row
+        Ok(active_model.insert(db).await?)
+    }
+
+    /// Asynchronously updates an existing webhook identified by `id` with the provided payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - Payload containing the webhook details.
+    /// * `id` - The ID of the webhook to be updated.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the updated `Model` on success, or an `ApiError` on failure.
+    pub async fn update(payload: WebhookPayload, id: i32, db: &DbConn) -> Result<Model, ApiError> {
+        payload.validate()?;
+        address::check_url(&payload.url)
+            .await
+            .map_err(ApiError::Validation)?;
+
+        // Fetch the webhook of the user and convert it into an ActiveModel
+        let mut webhook: ActiveModel = Entity::find_by_id(id)
+            .filter(Column::UserId.eq(payload.user_id))
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("Webhook not found.")))
+            .map(Into::into)?;
+
+        // Update the fields with the new values from the payload
+        webhook.url = Set(payload.url.clone());
+        webhook.events = Set(payload.events());
+        if let Some(secret) = payload.secret {
+            webhook.secret = Set(secret);
+        }
+        if let Some(is_active) = payload.is_active {
+            webhook.is_active = Set(is_active);
+        }
+
+        // Execute the update operation and await the result
+        Ok(webhook.update(db).await?)
+    }
+
+    /// Asynchronously deletes an existing webhook identified by `id` and `user_id`, with its deliveries.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the webhook to be deleted.
+    /// * `user_id` - The ID of the user deleting the webhook.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion result on success, or an `ApiError` on failure.
+    pub async fn delete(id: i32, user_id: i32, db: &DbConn) -> Result<DeleteResult, ApiError> {
+        // Only delete the webhook if it belongs to the user
+        let result = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound(String::from("Webhook not found.")));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::ApiError;
+use crate::webhook::models::webhook::WebhookDeliveries;
+use database::entities::{webhook, webhook_delivery};
+use sea_orm::*;
+
+/// Struct for handling queries related to webhooks.
+pub struct WebhookQueries;
+
+impl WebhookQueries {
+    /// Asynchronously fetches the webhooks of a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user owning the webhooks.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the webhooks ordered by ID on success, or an `ApiError` on failure.
+    pub async fn get_webhooks(user_id: i32, db: &DbConn) -> Result<Vec<webhook::Model>, ApiError> {
+        Ok(webhook::Entity::find()
+            .filter(webhook::Column::UserId.eq(user_id))
+            .order_by_asc(webhook::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Asynchronously fetches a webhook of a user by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the webhook.
+    /// * `user_id` - The ID of the user owning the webhook.
+    /// * `db` - SeaORM connection or transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the webhook on success, or an `ApiError::NotFound` if the user has no such webhook.
+    pub async fn get_webhook<C: ConnectionTrait>(
+        id: i32,
+        user_id: i32,
+        db: &C,
+    ) -> Result<webhook::Model, ApiError> {
+        webhook::Entity::find_by_id(id)
+            .filter(webhook::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or(ApiError::NotFound(String::from("Webhook not found.")))
+    }
+
+    /// Asynchronously fetches a page of the delivery log of a webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook_id` - The ID of the webhook.
+    /// * `user_id` - The ID of the user owning the webhook.
+    /// * `page` - The page number, starting at `1`.
+    /// * `size` - The number of deliveries per page.
+    /// * `db` - SeaORM database connection (`&DbConn`).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `WebhookDeliveries`, the latest first, or an `ApiError` on failure.
+    pub async fn get_deliveries(
+        webhook_id: i32,
+        user_id: i32,
+        page: u64,
+        size: u64,
+        db: &DbConn,
+    ) -> Result<WebhookDeliveries, ApiError> {
+        if page < 1 || size < 1 {
+            return Err(ApiError::Validation(
+                "The page and size numbers must be greater than 0.".to_string(),
+            ));
+        }
+
+        Self::get_webhook(webhook_id, user_id, db).await?;
+
+        // Set up pagination for the deliveries of the webhook
+        let paginator = webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::WebhookId.eq(webhook_id))
+            .order_by_desc(webhook_delivery::Column::Id)
+            .paginate(db, size);
+
+        // Retrieve the total number of pages and the requested page
+        let num_pages = paginator.num_pages().await?;
+        let items = paginator.fetch_page(page - 1).await?;
+
+        Ok(WebhookDeliveries {
+            items,
+            num_pages,
+            size,
+            page,
+        })
+    }
+}
@@ -0,0 +1,400 @@
+use crate::webhook::address::{self, PublicResolver};
+use chrono::Utc;
+use database::entities::webhook::Model as WebhookEntity;
+use database::entities::webhook_delivery::Model as DeliveryEntity;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use sha2::Sha256;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use url::{Host, Url};
+
+/// How long a receiver has to answer a delivery.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Header carrying the signature of a delivery.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Header carrying the signing time of a delivery, as a Unix timestamp.
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+/// Header carrying the name of the delivered event.
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// Header carrying the ID of the delivery, the same for every attempt.
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Signs a delivery body with the secret of its webhook.
+///
+/// The signed message is the timestamp and the body joined by a `.`, so a receiver checking
+/// the timestamp can reject replayed deliveries.
+///
+/// # Arguments
+///
+/// * `secret` - The secret of the webhook.
+/// * `timestamp` - The signing time, as a Unix timestamp.
+/// * `body` - The body of the delivery, as sent.
+///
+/// # Returns
+///
+/// The signature, as `sha256=` followed by the hex encoded HMAC-SHA256.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    // HMAC accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC key");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Result of an attempt to deliver a webhook.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeliveryOutcome {
+    /// The status answered by the receiver, if it answered. Its body is never kept, as the user reads the log.
+    pub status: Option<u16>,
+    /// Why the receiver could not be reached or rejected the delivery.
+    pub error: Option<String>,
+}
+
+impl DeliveryOutcome {
+    /// Returns whether the receiver accepted the delivery with a `2xx` status.
+    pub fn is_success(&self) -> bool {
+        self.status
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+}
+
+/// HTTP client posting the deliveries to the webhooks.
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: Client,
+    /// Whether deliveries may reach non-public addresses, only for the tests and their local receivers.
+    allow_private: bool,
+}
+
+impl WebhookSender {
+    /// Creates a sender, which does not follow redirects and only reaches public addresses.
+    pub fn new() -> Self {
+        Self::build(false)
+    }
+
+    /// Creates a sender reaching local addresses, for the stand-in receivers of the tests.
+    #[cfg(test)]
+    fn allowing_private_addresses() -> Self {
+        Self::build(true)
+    }
+
+    fn build(allow_private: bool) -> Self {
+        let mut builder = Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .redirect(Policy::none())
+            // A proxy would resolve the names itself, out of reach of the resolver
+            .no_proxy()
+            .user_agent(concat!("todo-api-webhooks/", env!("CARGO_PKG_VERSION")));
+        if !allow_private {
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        Self {
+            client: builder.build().expect("webhook HTTP client"),
+            allow_private,
+        }
+    }
+
+    /// Returns why a delivery cannot be sent to a URL, if its host is an address which is not public.
+    ///
+    /// Names are checked by the `PublicResolver` of the client, when connecting.
+    fn refused(&self, url: &str) -> Option<String> {
+        if self.allow_private {
+            return None;
+        }
+
+        let ip = match Url::parse(url).ok()?.host()? {
+            Host::Ipv4(ip) => IpAddr::V4(ip),
+            Host::Ipv6(ip) => IpAddr::V6(ip),
+            Host::Domain(_) => return None,
+        };
+
+        (!address::is_public(ip))
+            .then(|| format!("The webhook URL points to the non-public address {ip}."))
+    }
+
+    /// Asynchronously posts a delivery to its webhook, signed with the webhook secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `webhook` - The webhook receiving the delivery.
+    /// * `delivery` - The delivery to post.
+    ///
+    /// # Returns
+    ///
+    /// The `DeliveryOutcome` of the attempt, which never fails.
+    pub async fn send(
+        &self,
+        webhook: &WebhookEntity,
+        delivery: &DeliveryEntity,
+    ) -> DeliveryOutcome {
+        // The URL was checked when saved, its addresses may have changed since
+        if let Some(error) = self.refused(&webhook.url) {
+            return DeliveryOutcome {
+                error: Some(error),
+                ..Default::default()
+            };
+        }
+
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(&webhook.secret, timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                return DeliveryOutcome {
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            }
+        };
+
+        let status = response.status();
+
+        DeliveryOutcome {
+            status: Some(status.as_u16()),
+            error: (!status.is_success())
+                .then(|| format!("The receiver answered with status {}.", status.as_u16())),
+        }
+    }
+}
+
+impl Default for WebhookSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpListener;
+    use rocket::tokio::sync::oneshot;
+    use serde_json::json;
+
+    /// Request received by the stand-in receiver.
+    struct Received {
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    impl Received {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Starts a local HTTP receiver answering one request with `status` and `body`.
+    ///
+    /// Returns its URL and the request it receives.
+    async fn receiver(status: u16, body: &'static str) -> (String, oneshot::Receiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, received) = oneshot::channel();
+
+        rocket::tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+
+            // Read the head, then as much body as announced
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            let head_end = loop {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+
+            let head = String::from_utf8_lossy(&request[..head_end]).to_string();
+            let headers = head
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_once(": "))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<_>>();
+            let length = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.parse::<usize>().ok())
+                .unwrap_or_default();
+
+            while request.len() < head_end + length {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let response = format!(
+                "HTTP/1.1 {status} Stand-in\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+
+            let _ = sender.send(Received {
+                headers,
+                body: String::from_utf8_lossy(&request[head_end..]).to_string(),
+            });
+        });
+
+        (url, received)
+    }
+
+    fn webhook(url: String) -> WebhookEntity {
+        WebhookEntity {
+            id: 1,
+            user_id: 1,
+            url,
+            events: json!(["task.created"]),
+            secret: "a-very-secret-secret".to_string(),
+            is_active: true,
+            created_at: DateTime::UNIX_EPOCH,
+            updated_at: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn delivery() -> DeliveryEntity {
+        DeliveryEntity {
+            id: 7,
+            webhook_id: 1,
+            event: "task.created".to_string(),
+            payload: r#"{"event":"task.created","seq":3,"data":{"id":3,"name":"Bread"}}"#
+                .to_string(),
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: DateTime::UNIX_EPOCH,
+            last_attempt_at: None,
+            response_status: None,
+            error: None,
+            redelivery_of: None,
+            created_at: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn signs_the_timestamp_and_body() {
+        let signature = sign("secret", 1700000000, "{}");
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("secret", 1700000000, "{}"));
+        assert_ne!(signature, sign("secret", 1700000001, "{}"));
+        assert_ne!(signature, sign("other", 1700000000, "{}"));
+    }
+
+    #[rocket::async_test]
+    async fn posts_a_signed_delivery() {
+        let (url, received) = receiver(204, "").await;
+        let webhook = webhook(url);
+        let delivery = delivery();
+
+        let outcome = WebhookSender::allowing_private_addresses()
+            .send(&webhook, &delivery)
+            .await;
+        let received = received.await.unwrap();
+
+        assert!(outcome.is_success());
+        assert_eq!(outcome.status, Some(204));
+        assert_eq!(outcome.error, None);
+        assert_eq!(received.body, delivery.payload);
+        assert_eq!(received.header(EVENT_HEADER), Some("task.created"));
+        assert_eq!(received.header(DELIVERY_HEADER), Some("7"));
+        assert_eq!(received.header("Content-Type"), Some("application/json"));
+
+        // The receiver can check the signature with the shared secret
+        let timestamp = received.header(TIMESTAMP_HEADER).unwrap().parse().unwrap();
+        assert_eq!(
+            received.header(SIGNATURE_HEADER),
+            Some(sign(&webhook.secret, timestamp, &received.body).as_str())
+        );
+    }
+
+    #[rocket::async_test]
+    async fn reports_a_rejected_delivery() {
+        let (url, received) = receiver(500, "Something broke").await;
+
+        let outcome = WebhookSender::allowing_private_addresses()
+            .send(&webhook(url), &delivery())
+            .await;
+        received.await.unwrap();
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.status, Some(500));
+        assert!(outcome.error.is_some());
+    }
+
+    #[rocket::async_test]
+    async fn does_not_follow_redirects() {
+        let (url, received) = receiver(302, "").await;
+
+        let outcome = WebhookSender::allowing_private_addresses()
+            .send(&webhook(url), &delivery())
+            .await;
+        received.await.unwrap();
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.status, Some(302));
+    }
+
+    #[rocket::async_test]
+    async fn reports_an_unreachable_receiver() {
+        // Bind then drop a listener, so nothing listens on its port
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        let outcome = WebhookSender::allowing_private_addresses()
+            .send(&webhook(url), &delivery())
+            .await;
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.status, None);
+        assert!(outcome.error.is_some());
+    }
+
+    #[rocket::async_test]
+    async fn refuses_non_public_receivers() {
+        let (url, _received) = receiver(204, "").await;
+        let sender = WebhookSender::new();
+
+        // By address, and by a name resolving to a local address
+        let by_address = sender.send(&webhook(url.clone()), &delivery()).await;
+        let by_name = sender
+            .send(&webhook(url.replace("127.0.0.1", "localhost")), &delivery())
+            .await;
+
+        for outcome in [by_address, by_name] {
+            assert!(!outcome.is_success());
+            assert_eq!(outcome.status, None);
+            assert!(outcome.error.is_some());
+        }
+    }
+}
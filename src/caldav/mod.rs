@@ -0,0 +1,710 @@
+mod props;
+mod xml;
+
+use crate::routes::payload::from_json_value;
+use crate::routes::task::ManageTodo;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use props::{Multistatus, Node, Prop};
+use rocket::serde::json::json;
+use rocket::tokio::net::TcpListener;
+use rocket::Shutdown;
+use sea_orm::DatabaseConnection;
+use services::auth::basic::BasicAuth;
+use services::caldav::models::ical::parse_vtodo;
+use services::caldav::mutations::caldav::CalDavMutation;
+use services::caldav::queries::caldav::CalDavQueries;
+use services::error::ApiError;
+use services::etag::{etag, EntityTags};
+use services::request_id::RequestId;
+use services::task::mutations::task::TaskPayload;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use xml::{escape, Element, CALDAV, DAV};
+
+/// Path of the CalDAV tree, every resource lives below it.
+const PREFIX: &str = "/dav";
+
+/// Largest request body accepted, such as an iCalendar object or a `REPORT`.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Methods supported by the CalDAV resources.
+const ALLOW: &str = "OPTIONS, GET, HEAD, PUT, DELETE, PROPFIND, REPORT";
+
+/// Response of the CalDAV server.
+type DavResponse = Response<Full<Bytes>>;
+
+/// A resource of the CalDAV tree, named after its URL.
+#[derive(Debug, PartialEq, Eq)]
+enum Resource {
+    /// `/dav/`, pointing the clients at the principal of the authenticated user.
+    Root,
+    /// `/dav/principals/<username>/`, the principal of a user.
+    Principal(String),
+    /// `/dav/calendars/<username>/`, the calendar home of a user.
+    Home(String),
+    /// `/dav/calendars/<username>/tasks/`, the task list of a user.
+    Calendar(String),
+    /// `/dav/calendars/<username>/tasks/<name>`, a task of a user.
+    Object(String, String),
+}
+
+impl Resource {
+    /// Parses the path of a request URL, percent-decoding its segments.
+    fn parse(path: &str) -> Option<Resource> {
+        let rest = match path {
+            "/" => "",
+            path => path.strip_prefix(PREFIX)?,
+        };
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+
+        let segments = rest
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect::<Option<Vec<_>>>()?;
+
+        match segments.as_slice() {
+            [] => Some(Resource::Root),
+            [principals, user] if principals == "principals" => {
+                Some(Resource::Principal(user.clone()))
+            }
+            [calendars, user] if calendars == "calendars" => Some(Resource::Home(user.clone())),
+            [calendars, user, tasks] if calendars == "calendars" && tasks == "tasks" => {
+                Some(Resource::Calendar(user.clone()))
+            }
+            [calendars, user, tasks, name] if calendars == "calendars" && tasks == "tasks" => {
+                Some(Resource::Object(user.clone(), name.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the user owning the resource, `None` for the root.
+    fn owner(&self) -> Option<&str> {
+        match self {
+            Resource::Root => None,
+            Resource::Principal(user)
+            | Resource::Home(user)
+            | Resource::Calendar(user)
+            | Resource::Object(user, _) => Some(user),
+        }
+    }
+}
+
+/// Decodes a percent-encoded path segment.
+fn percent_decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Percent-encodes a path segment, keeping the characters that are safe in a URL path.
+fn percent_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Returns the URL of the principal of a user.
+fn principal_href(user: &str) -> String {
+    format!("{}/principals/{}/", PREFIX, percent_encode(user))
+}
+
+/// Returns the URL of the calendar home of a user.
+fn home_href(user: &str) -> String {
+    format!("{}/calendars/{}/", PREFIX, percent_encode(user))
+}
+
+/// Returns the URL of the task list of a user.
+fn calendar_href(user: &str) -> String {
+    format!("{}tasks/", home_href(user))
+}
+
+/// Returns the URL of a task of a user.
+fn object_href(user: &str, name: &str) -> String {
+    format!("{}{}", calendar_href(user), percent_encode(name))
+}
+
+/// Builds the sync token of a position of the change feed.
+fn sync_token(position: i64) -> String {
+    format!("https://todo-api/ns/sync/{}", position)
+}
+
+/// Parses a sync token sent by a client, an empty one asking for every object.
+fn parse_sync_token(token: &str) -> Option<i64> {
+    match token.trim() {
+        "" => Some(0),
+        token => token
+            .strip_prefix("https://todo-api/ns/sync/")?
+            .parse()
+            .ok()
+            .filter(|position| *position >= 0),
+    }
+}
+
+/// Builds a response with a body.
+fn response(status: StatusCode, content_type: &str, body: impl Into<Bytes>) -> DavResponse {
+    let mut response = Response::new(Full::new(body.into()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(content_type).unwrap_or(HeaderValue::from_static("text/plain")),
+    );
+    response
+}
+
+/// Builds a response without a body.
+fn empty(status: StatusCode) -> DavResponse {
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = status;
+    response
+}
+
+/// Builds the `DAV:error` response of a failed precondition, such as `valid-sync-token`.
+///
+/// # Arguments
+///
+/// * `status` - The status of the response.
+/// * `condition` - The prefixed name of the failed precondition, such as `D:valid-sync-token`.
+/// * `message` - A description of the failure for humans.
+///
+/// # Returns
+///
+/// The XML error response.
+fn precondition(status: StatusCode, condition: &str, message: &str) -> DavResponse {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <D:error xmlns:D=\"{DAV}\" xmlns:C=\"{CALDAV}\"><{condition}/>\
+         <D:responsedescription>{}</D:responsedescription></D:error>\n",
+        escape(message)
+    );
+
+    response(status, "application/xml; charset=utf-8", body)
+}
+
+/// Converts a service error into the response with the matching status.
+fn error(error: ApiError) -> DavResponse {
+    let status = match error {
+        ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+        ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+        ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+        ApiError::Conflict(_) => StatusCode::CONFLICT,
+        ApiError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+        ApiError::Unauthorized(_) => return unauthorized(),
+        ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        // The object does not make a valid task, such as a name that is too long
+        ApiError::Validation(message) => {
+            return precondition(
+                StatusCode::FORBIDDEN,
+                "C:valid-calendar-object-resource",
+                &message,
+            )
+        }
+    };
+
+    response(status, "text/plain; charset=utf-8", error.to_string())
+}
+
+/// Builds the `401 Unauthorized` response asking for HTTP Basic credentials.
+fn unauthorized() -> DavResponse {
+    let mut response = response(
+        StatusCode::UNAUTHORIZED,
+        "text/plain; charset=utf-8",
+        "Invalid credentials.",
+    );
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static("Basic realm=\"todo-api\", charset=\"UTF-8\""),
+    );
+    response
+}
+
+/// Returns a header of the request as text, if present.
+fn header_str<'r>(request: &'r Request<Incoming>, name: &str) -> Option<&'r str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Asynchronously reads the body of a request, up to `MAX_BODY_SIZE`.
+async fn read_body(body: Incoming) -> Result<String, DavResponse> {
+    let body = Limited::new(body, MAX_BODY_SIZE)
+        .collect()
+        .await
+        .map_err(|_| empty(StatusCode::PAYLOAD_TOO_LARGE))?
+        .to_bytes();
+
+    String::from_utf8(body.to_vec()).map_err(|_| {
+        response(
+            StatusCode::BAD_REQUEST,
+            "text/plain; charset=utf-8",
+            "The body must be UTF-8 text.",
+        )
+    })
+}
+
+/// Asynchronously reads the XML body of a `PROPFIND` or `REPORT` request, `None` when empty.
+async fn read_xml(body: Incoming) -> Result<Option<Element>, DavResponse> {
+    let body = read_body(body).await?;
+    if body.trim().is_empty() {
+        return Ok(None);
+    }
+
+    xml::parse(&body).map(Some).map_err(|message| {
+        response(
+            StatusCode::BAD_REQUEST,
+            "text/plain; charset=utf-8",
+            format!("Invalid XML body: {message}"),
+        )
+    })
+}
+
+/// The authenticated user of a request.
+struct DavUser {
+    /// The ID of the user.
+    id: i32,
+    /// The username, naming the resources of the user.
+    username: String,
+}
+
+/// Asynchronously serves the CalDAV task lists until Rocket shuts down.
+///
+/// Rocket only routes the standard HTTP methods, so the CalDAV tree, which relies on
+/// `PROPFIND` and `REPORT`, is served by its own HTTP/1.1 listener.
+///
+/// # Arguments
+///
+/// * `address` - The address to listen on.
+/// * `db` - The database connection shared with Rocket.
+/// * `shutdown` - Resolves when Rocket shuts down.
+///
+/// # Returns
+///
+/// A `Result` with `()` once stopped, or the error binding the address.
+///
+pub async fn serve(
+    address: SocketAddr,
+    db: DatabaseConnection,
+    mut shutdown: Shutdown,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+
+    loop {
+        let stream = rocket::tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                // Such as too many open files, the next connection may succeed
+                Err(e) => {
+                    println!("CalDAV connection failed: {e}");
+                    continue;
+                }
+            },
+            _ = &mut shutdown => return Ok(()),
+        };
+
+        let db = db.clone();
+        rocket::tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let db = db.clone();
+                async move { Ok::<_, Infallible>(handle(request, &db).await) }
+            });
+
+            // The client went away or sent an invalid request, nothing to do
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+/// Asynchronously answers a CalDAV request, echoing its request ID.
+async fn handle(request: Request<Incoming>, db: &DatabaseConnection) -> DavResponse {
+    let request_id = RequestId::from_sent(header_str(&request, RequestId::HEADER));
+    let method = request.method().clone();
+
+    let mut response = match dispatch(request, &request_id, db).await {
+        Ok(response) | Err(response) => response,
+    };
+
+    let headers = response.headers_mut();
+    headers.insert("DAV", HeaderValue::from_static("1, 3, calendar-access"));
+    if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+        headers.insert(RequestId::HEADER, value);
+    }
+
+    // A HEAD response has the headers of a GET, without its body
+    if method == Method::HEAD {
+        *response.body_mut() = Full::default();
+    }
+
+    response
+}
+
+/// Asynchronously authenticates a request and routes it to the handler of its method.
+async fn dispatch(
+    request: Request<Incoming>,
+    request_id: &RequestId,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    // Clients discover the CalDAV tree from the well-known URL (RFC 6764)
+    if request.uri().path().starts_with("/.well-known/caldav") {
+        let mut response = empty(StatusCode::MOVED_PERMANENTLY);
+        response
+            .headers_mut()
+            .insert(header::LOCATION, HeaderValue::from_static("/dav/"));
+        return Ok(response);
+    }
+
+    let resource = Resource::parse(request.uri().path()).ok_or(empty(StatusCode::NOT_FOUND))?;
+
+    if request.method() == Method::OPTIONS {
+        let mut response = empty(StatusCode::OK);
+        response
+            .headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static(ALLOW));
+        return Ok(response);
+    }
+
+    // Every other method needs the credentials of the user owning the resource
+    let credentials = header_str(&request, "Authorization")
+        .and_then(BasicAuth::parse)
+        .ok_or_else(unauthorized)?;
+    let user = credentials.authenticate(db).await.map_err(error)?;
+    let user = DavUser {
+        id: user.id,
+        username: user.username,
+    };
+
+    if resource.owner().is_some_and(|owner| owner != user.username) {
+        return Err(response(
+            StatusCode::FORBIDDEN,
+            "text/plain; charset=utf-8",
+            "The resource belongs to another user.",
+        ));
+    }
+
+    match (request.method().as_str(), resource) {
+        ("PROPFIND", resource) => propfind(request, resource, &user, db).await,
+        ("REPORT", Resource::Calendar(_)) => report(request, &user, db).await,
+        ("REPORT", _) => Err(precondition(
+            StatusCode::FORBIDDEN,
+            "D:supported-report",
+            "Reports are only supported on the task list.",
+        )),
+        ("GET" | "HEAD", Resource::Object(_, name)) => get(&name, &user, db).await,
+        ("PUT", Resource::Object(_, name)) => put(request, &name, &user, request_id, db).await,
+        ("DELETE", Resource::Object(_, name)) => {
+            delete(&request, &name, &user, request_id, db).await
+        }
+        _ => {
+            let mut response = empty(StatusCode::METHOD_NOT_ALLOWED);
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static(ALLOW));
+            Err(response)
+        }
+    }
+}
+
+/// Answers a `PROPFIND`, listing the properties of a resource and, at `Depth: 1`, of its members.
+async fn propfind(
+    request: Request<Incoming>,
+    resource: Resource,
+    user: &DavUser,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    let depth_one = header_str(&request, "Depth").is_some_and(|depth| depth.trim() != "0");
+    let body = read_xml(request.into_body()).await?;
+
+    // No body, `allprop` or `propname` ask for every property
+    let props = match body {
+        Some(ref body) if !body.is(DAV, "propfind") => {
+            return Err(empty(StatusCode::BAD_REQUEST));
+        }
+        Some(ref body) => body.child(DAV, "prop").map(Prop::list),
+        None => None,
+    };
+
+    let mut multistatus = Multistatus::new(&user.username, props);
+    match resource {
+        Resource::Root => multistatus.push(Node::Root),
+        Resource::Principal(_) => multistatus.push(Node::Principal),
+        Resource::Home(_) => {
+            multistatus.push(Node::Home);
+            if depth_one {
+                let position = CalDavQueries::get_changes(0, user.id, db)
+                    .await
+                    .map_err(error)?
+                    .position;
+                multistatus.push(Node::Calendar(position));
+            }
+        }
+        Resource::Calendar(_) => {
+            let changes = CalDavQueries::get_changes(0, user.id, db)
+                .await
+                .map_err(error)?;
+            multistatus.push(Node::Calendar(changes.position));
+            if depth_one {
+                for object in &changes.changed {
+                    multistatus.push(Node::Object(object));
+                }
+            }
+        }
+        Resource::Object(_, name) => {
+            let object = CalDavQueries::get_object(&name, user.id, db)
+                .await
+                .map_err(error)?
+                .ok_or(empty(StatusCode::NOT_FOUND))?;
+            multistatus.push(Node::Object(&object));
+        }
+    }
+
+    Ok(multistatus.into_response())
+}
+
+/// Answers a `REPORT` on the task list: `calendar-query`, `calendar-multiget` or `sync-collection`.
+async fn report(
+    request: Request<Incoming>,
+    user: &DavUser,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    let body = read_xml(request.into_body())
+        .await?
+        .ok_or(empty(StatusCode::BAD_REQUEST))?;
+    let props = body.child(DAV, "prop").map(Prop::list);
+    let mut multistatus = Multistatus::new(&user.username, props);
+
+    if body.is(CALDAV, "calendar-query") {
+        // Only to-dos are stored, a query for other components matches nothing
+        let component = body
+            .descendant(CALDAV, "comp-filter")
+            .and_then(|filter| filter.descendant(CALDAV, "comp-filter"))
+            .and_then(|filter| filter.attributes.get("name"));
+        if component.is_some_and(|name| !name.eq_ignore_ascii_case("VTODO")) {
+            return Ok(multistatus.into_response());
+        }
+
+        let objects = CalDavQueries::get_objects(user.id, db)
+            .await
+            .map_err(error)?;
+        for object in &objects {
+            multistatus.push(Node::Object(object));
+        }
+    } else if body.is(CALDAV, "calendar-multiget") {
+        for href in body.children.iter().filter(|child| child.is(DAV, "href")) {
+            let href = href.text.trim();
+            let object = match Resource::parse(href) {
+                Some(Resource::Object(owner, name)) if owner == user.username => {
+                    CalDavQueries::get_object(&name, user.id, db)
+                        .await
+                        .map_err(error)?
+                }
+                _ => None,
+            };
+
+            match object {
+                Some(object) => multistatus.push(Node::Object(&object)),
+                None => multistatus.push_missing(href),
+            }
+        }
+    } else if body.is(DAV, "sync-collection") {
+        let token = body
+            .child(DAV, "sync-token")
+            .map_or("", |t| t.text.as_str());
+        let since = parse_sync_token(token).ok_or_else(|| {
+            precondition(
+                StatusCode::FORBIDDEN,
+                "D:valid-sync-token",
+                "The sync token is invalid.",
+            )
+        })?;
+
+        let changes = CalDavQueries::get_changes(since, user.id, db)
+            .await
+            .map_err(error)?;
+        for object in &changes.changed {
+            multistatus.push(Node::Object(object));
+        }
+        for name in &changes.removed {
+            multistatus.push_missing(&object_href(&user.username, name));
+        }
+        multistatus.sync_token(changes.position);
+    } else {
+        return Err(precondition(
+            StatusCode::FORBIDDEN,
+            "D:supported-report",
+            "Only calendar-query, calendar-multiget and sync-collection are supported.",
+        ));
+    }
+
+    Ok(multistatus.into_response())
+}
+
+/// Answers a `GET` of a task as an iCalendar object.
+async fn get(
+    name: &str,
+    user: &DavUser,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    let object = CalDavQueries::get_object(name, user.id, db)
+        .await
+        .map_err(error)?
+        .ok_or(empty(StatusCode::NOT_FOUND))?;
+
+    let mut response = response(
+        StatusCode::OK,
+        "text/calendar; charset=utf-8; component=VTODO",
+        services::caldav::models::ical::to_ical(&object.task, &object.uid),
+    );
+    if let Ok(value) = HeaderValue::from_str(&etag(object.task.version)) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+
+    Ok(response)
+}
+
+/// Answers a `PUT` of an iCalendar object, creating or updating its task.
+async fn put(
+    request: Request<Incoming>,
+    name: &str,
+    user: &DavUser,
+    request_id: &RequestId,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    let if_match = header_str(&request, "If-Match").map(EntityTags::parse);
+    let if_none_match =
+        header_str(&request, "If-None-Match").is_some_and(|tags| tags.trim() == "*");
+    let body = read_body(request.into_body()).await?;
+
+    let vtodo = parse_vtodo(&body).map_err(|message| {
+        precondition(StatusCode::FORBIDDEN, "C:valid-calendar-data", &message)
+    })?;
+
+    // Validate the fields like `POST /task/create`
+    let task = from_json_value::<ManageTodo>(json!({
+        "name": vtodo.summary.unwrap_or_default(),
+        "description": vtodo.description.unwrap_or_default(),
+        "is_active": vtodo.completed,
+        "due_date": vtodo.due.map(|due| due.to_string()),
+    }))
+    .map_err(|errors| {
+        let message = errors
+            .first()
+            .map_or("The to-do is invalid.", |e| e.message.as_str());
+        precondition(
+            StatusCode::FORBIDDEN,
+            "C:valid-calendar-object-resource",
+            message,
+        )
+    })?;
+
+    // The VTODO mapping leaves out the priority, tags and project, so the stored ones are kept
+    let payload = TaskPayload {
+        priority: None,
+        tags: None,
+        project: None,
+        ..task.into_payload(user.id, request_id.clone(), if_match)
+    };
+
+    let (object, created) = CalDavMutation::put(name, vtodo.uid, payload, if_none_match, db)
+        .await
+        .map_err(error)?;
+
+    let mut response = empty(match created {
+        true => StatusCode::CREATED,
+        false => StatusCode::NO_CONTENT,
+    });
+    if let Ok(value) = HeaderValue::from_str(&etag(object.task.version)) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+
+    Ok(response)
+}
+
+/// Answers a `DELETE` of a task.
+async fn delete(
+    request: &Request<Incoming>,
+    name: &str,
+    user: &DavUser,
+    request_id: &RequestId,
+    db: &DatabaseConnection,
+) -> Result<DavResponse, DavResponse> {
+    let if_match = header_str(request, "If-Match").map(EntityTags::parse);
+
+    CalDavMutation::delete(name, user.id, Some(request_id.0.clone()), if_match, db)
+        .await
+        .map_err(error)?;
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_name_resources() {
+        assert_eq!(Resource::parse("/"), Some(Resource::Root));
+        assert_eq!(Resource::parse("/dav"), Some(Resource::Root));
+        assert_eq!(
+            Resource::parse("/dav/principals/bobby/"),
+            Some(Resource::Principal("bobby".to_string()))
+        );
+        assert_eq!(
+            Resource::parse("/dav/calendars/bobby/tasks/"),
+            Some(Resource::Calendar("bobby".to_string()))
+        );
+        assert_eq!(
+            Resource::parse("/dav/calendars/bobby/tasks/a%20b%40c.ics"),
+            Some(Resource::Object(
+                "bobby".to_string(),
+                "a b@c.ics".to_string()
+            ))
+        );
+        assert_eq!(Resource::parse("/davx"), None);
+        assert_eq!(Resource::parse("/dav/calendars/bobby/other/"), None);
+        assert_eq!(
+            object_href("bobby", "a b@c.ics"),
+            "/dav/calendars/bobby/tasks/a%20b@c.ics"
+        );
+    }
+
+    #[test]
+    fn sync_tokens_round_trip() {
+        assert_eq!(parse_sync_token(&sync_token(42)), Some(42));
+        assert_eq!(parse_sync_token(""), Some(0));
+        assert_eq!(parse_sync_token("https://todo-api/ns/sync/-1"), None);
+        assert_eq!(parse_sync_token("http://example.com/1"), None);
+    }
+}
@@ -0,0 +1,270 @@
+use super::xml::{escape, Element, CALDAV, CALENDARSERVER, DAV};
+use super::{
+    calendar_href, home_href, object_href, principal_href, response, sync_token, DavResponse,
+    PREFIX,
+};
+use hyper::StatusCode;
+use services::caldav::models::ical::to_ical;
+use services::caldav::models::object::CalendarObject;
+use services::etag::etag;
+
+/// A property name requested by a client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prop {
+    /// The namespace URI.
+    pub ns: String,
+    /// The local name.
+    pub name: String,
+}
+
+impl Prop {
+    /// Builds a property name.
+    fn new(ns: &str, name: &str) -> Self {
+        Self {
+            ns: ns.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    /// Lists the property names of a `DAV:prop` element.
+    pub fn list(prop: &Element) -> Vec<Prop> {
+        prop.children
+            .iter()
+            .map(|child| Prop::new(&child.ns, &child.name))
+            .collect()
+    }
+
+    /// Renders the property with `value` as its content, empty when `None`.
+    fn render(&self, value: Option<&str>) -> String {
+        let (tag, declaration) = match self.ns.as_str() {
+            DAV => (format!("D:{}", self.name), String::new()),
+            CALDAV => (format!("C:{}", self.name), String::new()),
+            CALENDARSERVER => (format!("CS:{}", self.name), String::new()),
+            ns => (
+                format!("X:{}", self.name),
+                format!(" xmlns:X=\"{}\"", escape(ns)),
+            ),
+        };
+
+        match value {
+            Some(value) if !value.is_empty() => format!("<{tag}{declaration}>{value}</{tag}>"),
+            _ => format!("<{tag}{declaration}/>"),
+        }
+    }
+}
+
+/// A resource listed in a multistatus response.
+pub enum Node<'a> {
+    /// The root of the CalDAV tree.
+    Root,
+    /// The principal of the user.
+    Principal,
+    /// The calendar home of the user.
+    Home,
+    /// The task list of the user, at a position of the change feed.
+    Calendar(i64),
+    /// A task of the user.
+    Object(&'a CalendarObject),
+}
+
+impl Node<'_> {
+    /// Returns the properties listed by `allprop` for the resource.
+    fn all_props(&self) -> Vec<Prop> {
+        let names: &[(&str, &str)] = match self {
+            Node::Root => &[(DAV, "resourcetype"), (DAV, "current-user-principal")],
+            Node::Principal => &[
+                (DAV, "resourcetype"),
+                (DAV, "displayname"),
+                (DAV, "current-user-principal"),
+                (DAV, "principal-URL"),
+                (CALDAV, "calendar-home-set"),
+            ],
+            Node::Home => &[
+                (DAV, "resourcetype"),
+                (DAV, "displayname"),
+                (DAV, "current-user-principal"),
+            ],
+            Node::Calendar(_) => &[
+                (DAV, "resourcetype"),
+                (DAV, "displayname"),
+                (DAV, "current-user-principal"),
+                (DAV, "owner"),
+                (DAV, "current-user-privilege-set"),
+                (DAV, "supported-report-set"),
+                (DAV, "sync-token"),
+                (CALDAV, "supported-calendar-component-set"),
+                (CALENDARSERVER, "getctag"),
+            ],
+            Node::Object(_) => &[
+                (DAV, "resourcetype"),
+                (DAV, "getetag"),
+                (DAV, "getcontenttype"),
+                (DAV, "getlastmodified"),
+            ],
+        };
+
+        names.iter().map(|(ns, name)| Prop::new(ns, name)).collect()
+    }
+
+    /// Returns the URL of the resource.
+    fn href(&self, username: &str) -> String {
+        match self {
+            Node::Root => format!("{}/", PREFIX),
+            Node::Principal => principal_href(username),
+            Node::Home => home_href(username),
+            Node::Calendar(_) => calendar_href(username),
+            Node::Object(object) => object_href(username, &object.name),
+        }
+    }
+
+    /// Renders the value of a property of the resource, `None` when it has no such property.
+    fn value(&self, prop: &Prop, username: &str) -> Option<String> {
+        let href = |href: String| format!("<D:href>{}</D:href>", escape(&href));
+
+        let value = match (prop.ns.as_str(), prop.name.as_str(), self) {
+            (DAV, "resourcetype", Node::Root | Node::Home) => "<D:collection/>".to_string(),
+            (DAV, "resourcetype", Node::Principal) => "<D:principal/>".to_string(),
+            (DAV, "resourcetype", Node::Calendar(_)) => "<D:collection/><C:calendar/>".to_string(),
+            (DAV, "resourcetype", Node::Object(_)) => String::new(),
+            (DAV, "displayname", Node::Principal | Node::Home) => escape(username),
+            (DAV, "displayname", Node::Calendar(_)) => "Tasks".to_string(),
+            (DAV, "current-user-principal", _) => href(principal_href(username)),
+            (DAV, "principal-URL", Node::Principal) => href(principal_href(username)),
+            (DAV, "owner", Node::Calendar(_) | Node::Object(_)) => href(principal_href(username)),
+            (CALDAV, "calendar-home-set", Node::Root | Node::Principal) => {
+                href(home_href(username))
+            }
+            (DAV, "current-user-privilege-set", Node::Calendar(_) | Node::Object(_)) => {
+                ["all", "read", "write", "write-content", "bind", "unbind"]
+                    .iter()
+                    .map(|privilege| format!("<D:privilege><D:{privilege}/></D:privilege>"))
+                    .collect()
+            }
+            (DAV, "supported-report-set", Node::Calendar(_)) => [
+                "C:calendar-query",
+                "C:calendar-multiget",
+                "D:sync-collection",
+            ]
+            .iter()
+            .map(|report| {
+                format!("<D:supported-report><D:report><{report}/></D:report></D:supported-report>")
+            })
+            .collect(),
+            (DAV, "sync-token", Node::Calendar(position))
+            | (CALENDARSERVER, "getctag", Node::Calendar(position)) => {
+                escape(&sync_token(*position))
+            }
+            (CALDAV, "supported-calendar-component-set", Node::Calendar(_)) => {
+                "<C:comp name=\"VTODO\"/>".to_string()
+            }
+            (DAV, "getetag", Node::Object(object)) => escape(&etag(object.task.version)),
+            (DAV, "getcontenttype", Node::Object(_)) => {
+                "text/calendar; charset=utf-8; component=VTODO".to_string()
+            }
+            (DAV, "getlastmodified", Node::Object(object)) => object
+                .task
+                .updated_at
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+            (CALDAV, "calendar-data", Node::Object(object)) => {
+                escape(&to_ical(&object.task, &object.uid))
+            }
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+/// A `207 Multi-Status` response being built.
+pub struct Multistatus<'u> {
+    /// The username, naming the resources.
+    username: &'u str,
+    /// The requested properties, `None` for every property.
+    props: Option<Vec<Prop>>,
+    /// The rendered `DAV:response` elements.
+    responses: Vec<String>,
+    /// The sync token of a `sync-collection` report.
+    sync_token: Option<i64>,
+}
+
+impl<'u> Multistatus<'u> {
+    /// Starts a multistatus response listing `props`, or every property when `None`.
+    pub fn new(username: &'u str, props: Option<Vec<Prop>>) -> Self {
+        Self {
+            username,
+            props,
+            responses: Vec::new(),
+            sync_token: None,
+        }
+    }
+
+    /// Lists a resource, its properties split between the found and the missing ones.
+    pub fn push(&mut self, node: Node<'_>) {
+        let props = self.props.clone().unwrap_or_else(|| node.all_props());
+        let mut found = String::new();
+        let mut missing = String::new();
+
+        for prop in &props {
+            match node.value(prop, self.username) {
+                Some(value) => found.push_str(&prop.render(Some(&value))),
+                None => missing.push_str(&prop.render(None)),
+            }
+        }
+
+        let mut response = format!(
+            "<D:response><D:href>{}</D:href>",
+            escape(&node.href(self.username))
+        );
+        if !found.is_empty() {
+            response.push_str(&format!(
+                "<D:propstat><D:prop>{found}</D:prop><D:status>HTTP/1.1 200 OK</D:status></D:propstat>"
+            ));
+        }
+        if !missing.is_empty() {
+            response.push_str(&format!(
+                "<D:propstat><D:prop>{missing}</D:prop><D:status>HTTP/1.1 404 Not Found</D:status></D:propstat>"
+            ));
+        }
+        response.push_str("</D:response>");
+
+        self.responses.push(response);
+    }
+
+    /// Lists a resource that does not exist, such as a deleted task.
+    pub fn push_missing(&mut self, href: &str) {
+        self.responses.push(format!(
+            "<D:response><D:href>{}</D:href><D:status>HTTP/1.1 404 Not Found</D:status></D:response>",
+            escape(href)
+        ));
+    }
+
+    /// Ends the response with the sync token of a `sync-collection` report.
+    pub fn sync_token(&mut self, position: i64) {
+        self.sync_token = Some(position);
+    }
+
+    /// Renders the response.
+    pub fn into_response(self) -> DavResponse {
+        let mut body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <D:multistatus xmlns:D=\"{DAV}\" xmlns:C=\"{CALDAV}\" xmlns:CS=\"{CALENDARSERVER}\">"
+        );
+        for response in &self.responses {
+            body.push_str(response);
+        }
+        if let Some(position) = self.sync_token {
+            body.push_str(&format!(
+                "<D:sync-token>{}</D:sync-token>",
+                escape(&sync_token(position))
+            ));
+        }
+        body.push_str("</D:multistatus>\n");
+
+        response(
+            StatusCode::MULTI_STATUS,
+            "application/xml; charset=utf-8",
+            body,
+        )
+    }
+}
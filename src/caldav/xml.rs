@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+/// The `DAV:` namespace of WebDAV (RFC 4918).
+pub const DAV: &str = "DAV:";
+
+/// The namespace of CalDAV (RFC 4791).
+pub const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+
+/// The namespace of the Apple Calendar Server extensions, such as `getctag`.
+pub const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+
+/// An element of an XML request body, with its namespace resolved.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    /// The namespace URI, empty when there is none.
+    pub ns: String,
+    /// The local name.
+    pub name: String,
+    /// The attributes by local name.
+    pub attributes: HashMap<String, String>,
+    /// The child elements, in document order.
+    pub children: Vec<Element>,
+    /// The text content, with the entities decoded.
+    pub text: String,
+}
+
+impl Element {
+    /// Returns whether the element is `name` in the namespace `ns`.
+    pub fn is(&self, ns: &str, name: &str) -> bool {
+        self.ns == ns && self.name == name
+    }
+
+    /// Returns the first child element `name` in the namespace `ns`, if any.
+    pub fn child(&self, ns: &str, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.is(ns, name))
+    }
+
+    /// Returns the first element `name` in the namespace `ns` below this one, depth first.
+    pub fn descendant(&self, ns: &str, name: &str) -> Option<&Element> {
+        self.children
+            .iter()
+            .find_map(|child| match child.is(ns, name) {
+                true => Some(child),
+                false => child.descendant(ns, name),
+            })
+    }
+}
+
+/// Escapes text for an XML element or attribute value.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Decodes the predefined and numeric entities of XML text.
+fn unescape(text: &str) -> Result<String, String> {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or("Unterminated entity.")?;
+        let entity = &rest[start + 1..start + end];
+
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("Unknown entity `&{entity};`."))?,
+        };
+
+        decoded.push(c);
+        rest = &rest[start + end + 1..];
+    }
+
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+/// An element being read, with the namespace prefixes it declares in scope.
+struct Open {
+    /// The prefixed name, matched against the end tag.
+    qualified: String,
+    /// The element, its namespace resolved once it is closed.
+    element: Element,
+    /// The prefix of the element name.
+    prefix: String,
+    /// The namespace prefixes in scope, `""` being the default namespace.
+    namespaces: HashMap<String, String>,
+}
+
+/// Splits a qualified name into its prefix and local name.
+fn split_name(qualified: &str) -> (&str, &str) {
+    qualified.split_once(':').unwrap_or(("", qualified))
+}
+
+/// Parses the attributes of a start tag.
+fn parse_attributes(mut rest: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(attributes);
+        }
+
+        let (name, value) = rest.split_once('=').ok_or("Invalid attribute.")?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let quote = quote.ok_or("Unquoted attribute value.")?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or("Unterminated attribute value.")?;
+
+        attributes.push((name.trim().to_string(), unescape(&value[1..end + 1])?));
+        rest = &value[end + 2..];
+    }
+}
+
+/// Parses an XML document, such as the body of a `PROPFIND` or `REPORT` request.
+///
+/// Only what WebDAV bodies use is supported: elements, attributes, namespaces, text and
+/// CDATA. Document type declarations are rejected, so entities cannot be expanded.
+///
+/// # Arguments
+///
+/// * `xml` - The document.
+///
+/// # Returns
+///
+/// A `Result` with the root element, or a message telling why the document is invalid.
+pub fn parse(xml: &str) -> Result<Element, String> {
+    let mut stack: Vec<Open> = Vec::new();
+    let mut root = None;
+    let mut rest = xml;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            if let Some(open) = stack.last_mut() {
+                open.element.text.push_str(&unescape(rest)?);
+            }
+            break;
+        };
+
+        if let Some(open) = stack.last_mut() {
+            open.element.text.push_str(&unescape(&rest[..start])?);
+        }
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<?") {
+            rest = &after[after.find("?>").ok_or("Unterminated declaration.")? + 2..];
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            rest = &after[after.find("-->").ok_or("Unterminated comment.")? + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("Unterminated CDATA section.")?;
+            if let Some(open) = stack.last_mut() {
+                open.element.text.push_str(&after[..end]);
+            }
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<!") {
+            return Err("Document type declarations are not supported.".to_string());
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or("Unterminated end tag.")?;
+            let qualified = after[..end].trim();
+            let open = stack.pop().ok_or("Unexpected end tag.")?;
+
+            if open.qualified != qualified {
+                return Err(format!("Mismatched end tag `{qualified}`."));
+            }
+
+            let element = close(open)?;
+            match stack.last_mut() {
+                Some(parent) => parent.element.children.push(element),
+                None => root = Some(element),
+            }
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('>').ok_or("Unterminated start tag.")?;
+            let tag = &rest[1..end];
+            let (tag, empty) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+
+            let name_end = tag
+                .find(|c: char| c.is_ascii_whitespace())
+                .unwrap_or(tag.len());
+            let qualified = tag[..name_end].to_string();
+            let mut namespaces = stack
+                .last()
+                .map(|open| open.namespaces.clone())
+                .unwrap_or_default();
+            let mut element = Element::default();
+
+            for (name, value) in parse_attributes(&tag[name_end..])? {
+                match split_name(&name) {
+                    ("", "xmlns") => {
+                        namespaces.insert(String::new(), value);
+                    }
+                    ("xmlns", prefix) => {
+                        namespaces.insert(prefix.to_string(), value);
+                    }
+                    (_, local) => {
+                        element.attributes.insert(local.to_string(), value);
+                    }
+                }
+            }
+
+            let (prefix, local) = split_name(&qualified);
+            element.name = local.to_string();
+            let open = Open {
+                prefix: prefix.to_string(),
+                qualified: qualified.clone(),
+                element,
+                namespaces,
+            };
+
+            if root.is_some() {
+                return Err("Content after the root element.".to_string());
+            }
+
+            match empty {
+                true => {
+                    let element = close(open)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.element.children.push(element),
+                        None => root = Some(element),
+                    }
+                }
+                false => stack.push(open),
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+
+    match (root, stack.is_empty()) {
+        (Some(root), true) => Ok(root),
+        _ => Err("The document has no complete root element.".to_string()),
+    }
+}
+
+/// Resolves the namespace of a closed element.
+fn close(open: Open) -> Result<Element, String> {
+    let mut element = open.element;
+
+    element.ns = match open.namespaces.get(&open.prefix) {
+        Some(ns) => ns.clone(),
+        None if open.prefix.is_empty() => String::new(),
+        None => return Err(format!("Undeclared namespace prefix `{}`.", open.prefix)),
+    };
+
+    Ok(element)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespaces_are_resolved() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8" ?>
+            <d:propfind xmlns:d="DAV:" xmlns:cs="http://calendarserver.org/ns/">
+              <d:prop>
+                <d:resourcetype/>
+                <cs:getctag />
+                <x:color xmlns:x="http://apple.com/ns/ical/"/>
+              </d:prop>
+            </d:propfind>"#;
+
+        let root = parse(xml).unwrap();
+        let names = root
+            .child(DAV, "prop")
+            .unwrap()
+            .children
+            .iter()
+            .map(|child| (child.ns.as_str(), child.name.as_str()))
+            .collect::<Vec<_>>();
+
+        assert!(root.is(DAV, "propfind"));
+        assert_eq!(
+            names,
+            [
+                (DAV, "resourcetype"),
+                (CALENDARSERVER, "getctag"),
+                ("http://apple.com/ns/ical/", "color")
+            ]
+        );
+    }
+
+    #[test]
+    fn text_and_attributes_are_decoded() {
+        let xml = r#"<C:calendar-multiget xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+              <C:filter><C:comp-filter name='VTODO'/></C:filter>
+              <D:href>/dav/a&amp;b.ics</D:href><D:href><![CDATA[/dav/<c>.ics]]></D:href>
+            </C:calendar-multiget>"#;
+
+        let root = parse(xml).unwrap();
+        let hrefs = root
+            .children
+            .iter()
+            .filter(|child| child.is(DAV, "href"))
+            .map(|child| child.text.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(hrefs, ["/dav/a&b.ics", "/dav/<c>.ics"]);
+        assert_eq!(
+            root.descendant(CALDAV, "comp-filter").unwrap().attributes["name"],
+            "VTODO"
+        );
+    }
+
+    #[test]
+    fn invalid_documents_are_rejected() {
+        assert!(parse("").is_err());
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<x:a/>").is_err());
+        assert!(parse("<!DOCTYPE a [<!ENTITY e \"e\">]><a>&e;</a>").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use async_graphql::dataloader::Loader;
+use database::entities::{task_event, user};
+use sea_orm::DatabaseConnection;
+use services::error::ApiError;
+use services::task::queries::event::TaskEventQueries;
+use services::user::queries::user::UserQueries;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Loads the users owning the tasks of a response in one query.
+pub struct UserLoader {
+    /// The database connection of the request.
+    pub db: DatabaseConnection,
+}
+
+impl Loader<i32> for UserLoader {
+    type Value = user::Model;
+    type Error = Arc<ApiError>;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        Ok(UserQueries::get_users_by_ids(keys, &self.db)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user))
+            .collect())
+    }
+}
+
+/// Loads the histories of the tasks of a response in one query.
+pub struct HistoryLoader {
+    /// The database connection of the request.
+    pub db: DatabaseConnection,
+    /// The ID of the authenticated user, whose tasks only are visible.
+    pub user_id: i32,
+}
+
+impl Loader<i32> for HistoryLoader {
+    type Value = Vec<task_event::Model>;
+    type Error = Arc<ApiError>;
+
+    async fn load(&self, keys: &[i32]) -> Result<HashMap<i32, Self::Value>, Self::Error> {
+        let events = TaskEventQueries::get_histories(keys, self.user_id, &self.db).await?;
+
+        // Group the events by task, keeping their order
+        let mut histories: HashMap<i32, Self::Value> = HashMap::new();
+        for event in events {
+            histories.entry(event.task_id).or_default().push(event);
+        }
+
+        Ok(histories)
+    }
+}
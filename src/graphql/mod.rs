@@ -0,0 +1,82 @@
+mod loader;
+mod mutation;
+mod query;
+mod types;
+
+use async_graphql::{EmptySubscription, Error, ErrorExtensions, Schema};
+use services::error::ApiError;
+
+pub use loader::{HistoryLoader, UserLoader};
+pub use mutation::MutationRoot;
+pub use query::QueryRoot;
+
+/// GraphQL schema of the API.
+pub type ApiSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// Deepest selection set accepted in a query.
+const MAX_DEPTH: usize = 8;
+
+/// Highest complexity accepted for a query, every field counting `1` and lists counting their page size.
+const MAX_COMPLEXITY: usize = 2000;
+
+/// The authenticated user running a GraphQL request.
+pub struct Viewer {
+    /// The ID of the user.
+    pub user_id: i32,
+    /// The ID of the HTTP request, recorded in the task history.
+    pub request_id: String,
+}
+
+/// Builds the GraphQL schema, with the depth and complexity limits.
+///
+/// # Returns
+///
+/// The schema, expecting a `Viewer`, a `DatabaseConnection` and the data loaders in the request data.
+///
+pub fn build_schema() -> ApiSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Converts a service error into a GraphQL error, keeping its machine readable `code` as an extension.
+///
+/// # Arguments
+///
+/// * `error` - The service error.
+///
+/// # Returns
+///
+/// The GraphQL error, with the `code` and `status` extensions.
+///
+fn api_error(error: ApiError) -> Error {
+    let code = error.code();
+    let status = error.status().code;
+
+    Error::new(error.to_string()).extend_with(|_, extensions| {
+        extensions.set("code", code);
+        extensions.set("status", status);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rocket::async_test]
+    async fn oversized_pages_count_as_the_largest_page() {
+        let schema = build_schema();
+
+        // Rejected on its complexity, before any resolver needs the request data
+        let response = schema
+            .execute(
+                "{ me { tasks(size: 18446744073709551615) { items { owner { \
+                 tasks(size: 18446744073709551615) { items { id } } } } } } }",
+            )
+            .await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("complex"));
+    }
+}
@@ -0,0 +1,149 @@
+use super::types::Task;
+use super::{api_error, Viewer};
+use crate::routes::payload::from_json_value;
+use crate::routes::task::ManageTodo;
+use async_graphql::{Context, Error, ErrorExtensions, InputObject, Object, Result};
+use rocket::serde::json::to_value;
+use rocket::serde::Serialize;
+use sea_orm::DatabaseConnection;
+use services::error::FieldError;
+use services::request_id::RequestId;
+use services::sync::mutations::sync::version_precondition;
+use services::task::mutations::task::{TaskMutation, TaskPayload};
+
+/// Fields of a task, validated like `POST /task/create`.
+#[derive(InputObject, Serialize)]
+pub struct TaskInput {
+    /// The name of the task, 2 to 20 characters long.
+    pub name: String,
+    /// The description of the task, 5 to 200 characters long.
+    pub description: String,
+    /// Flag indicating whether the task is active or not. Defaults to `false`.
+    pub is_active: Option<bool>,
+    /// The date the task is due (`YYYY-MM-DD`), if any.
+    pub due_date: Option<String>,
+    /// The priority of the task (`low`, `normal`, `high` or `urgent`). Defaults to `normal`.
+    pub priority: Option<String>,
+    /// The tags of the task, at most 20 of at most 30 characters each.
+    pub tags: Option<Vec<String>>,
+    /// The project of the task, if any, at most 50 characters long.
+    pub project: Option<String>,
+}
+
+impl TaskInput {
+    /// Validates the input through `ManageTodo` and converts it into the payload of a task write.
+    ///
+    /// # Arguments
+    ///
+    /// * `viewer` - The authenticated user writing the task.
+    /// * `version` - The version of the task the write is based on, if any.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `TaskPayload`, or a `validation_error` listing the invalid fields.
+    ///
+    fn into_payload(self, viewer: &Viewer, version: Option<i32>) -> Result<TaskPayload> {
+        let task = to_value(self)?;
+
+        from_json_value::<ManageTodo>(task)
+            .map(|task| {
+                task.into_payload(
+                    viewer.user_id,
+                    RequestId(viewer.request_id.clone()),
+                    version_precondition(version),
+                )
+            })
+            .map_err(validation_error)
+    }
+}
+
+/// Builds the GraphQL error listing the invalid fields of an input.
+fn validation_error(errors: Vec<FieldError>) -> Error {
+    let message = errors
+        .first()
+        .map_or("The input is invalid.".to_string(), |e| e.message.clone());
+
+    Error::new(message).extend_with(|_, extensions| {
+        extensions.set("code", "validation_error");
+        extensions.set("status", 422);
+        extensions.set(
+            "fields",
+            errors
+                .iter()
+                .map(|e| e.field.clone().unwrap_or_default())
+                .collect::<Vec<_>>(),
+        );
+    })
+}
+
+/// Root of the GraphQL mutations.
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// Creates a task.
+    async fn create_task(&self, ctx: &Context<'_>, input: TaskInput) -> Result<Task> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+        let payload = input.into_payload(viewer, None)?;
+
+        TaskMutation::create(payload, ctx.data_unchecked::<DatabaseConnection>())
+            .await
+            .map(Task::from)
+            .map_err(api_error)
+    }
+
+    /// Replaces the fields of a task, only if it still has `version` when given.
+    async fn update_task(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        input: TaskInput,
+        #[graphql(desc = "Version the change is based on, a newer task fails the update")] version: Option<i32>,
+    ) -> Result<Task> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+        let payload = input.into_payload(viewer, version)?;
+
+        TaskMutation::update(payload, id, ctx.data_unchecked::<DatabaseConnection>())
+            .await
+            .map(Task::from)
+            .map_err(api_error)
+    }
+
+    /// Deletes a task, only if it still has `version` when given, and returns its ID.
+    async fn delete_task(
+        &self,
+        ctx: &Context<'_>,
+        id: i32,
+        #[graphql(desc = "Version the deletion is based on, a newer task fails the deletion")]
+        version: Option<i32>,
+    ) -> Result<i32> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+
+        TaskMutation::delete(
+            id,
+            viewer.user_id,
+            Some(viewer.request_id.clone()),
+            version_precondition(version),
+            ctx.data_unchecked::<DatabaseConnection>(),
+        )
+        .await
+        .map(|_| id)
+        .map_err(api_error)
+    }
+
+    /// Reverts a task to the state right after the event `revision` of its history.
+    async fn revert_task(&self, ctx: &Context<'_>, id: i32, revision: i32) -> Result<Task> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+
+        TaskMutation::revert(
+            id,
+            revision,
+            viewer.user_id,
+            Some(viewer.request_id.clone()),
+            ctx.data_unchecked::<DatabaseConnection>(),
+        )
+        .await
+        .map(Task::from)
+        .map_err(api_error)
+    }
+}
@@ -0,0 +1,45 @@
+use super::types::{Task, User};
+use super::{api_error, UserLoader, Viewer};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Object, Result};
+use sea_orm::DatabaseConnection;
+use services::error::ApiError;
+use services::task::queries::task::TaskQueries;
+
+/// Root of the GraphQL queries.
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The authenticated user, with access to their tasks.
+    async fn me(&self, ctx: &Context<'_>) -> Result<User> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+
+        ctx.data_unchecked::<DataLoader<UserLoader>>()
+            .load_one(viewer.user_id)
+            .await?
+            .map(User::from)
+            .ok_or_else(|| {
+                api_error(ApiError::Unauthorized(
+                    "The user of this auth token no longer exists.".to_string(),
+                ))
+            })
+    }
+
+    /// A task of the authenticated user, `null` if it does not exist.
+    async fn task(&self, ctx: &Context<'_>, id: i32) -> Result<Option<Task>> {
+        let viewer = ctx.data_unchecked::<Viewer>();
+
+        match TaskQueries::get_task_by_id(
+            id,
+            viewer.user_id,
+            ctx.data_unchecked::<DatabaseConnection>(),
+        )
+        .await
+        {
+            Ok(task) => Ok(Some(task.into())),
+            Err(ApiError::NotFound(_)) => Ok(None),
+            Err(e) => Err(api_error(e)),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use super::{api_error, HistoryLoader, UserLoader, Viewer};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{ComplexObject, Context, Enum, InputObject, Json, Result, SimpleObject};
+use chrono::{DateTime, NaiveDate, Utc};
+use database::entities::{task, task_event, user};
+use rocket::serde::json::Value;
+use sea_orm::DatabaseConnection;
+use services::task::models::task::TaskModel;
+use services::task::queries::task::{GetAllTasks, PaginationPayload, TaskQueries, MAX_PAGE_SIZE};
+
+/// Key used to sort the tasks, ties are always broken by the task ID.
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "services::task::queries::task::TaskSort")]
+pub enum TaskSort {
+    /// Sort by task ID, which follows the creation order.
+    Id,
+    /// Sort by task name.
+    Name,
+    /// Sort by creation time, oldest first.
+    CreatedAt,
+    /// Sort by time of the last change, least recently changed first.
+    UpdatedAt,
+}
+
+/// Filters of a task list, applied like the `GET /task` parameters.
+#[derive(InputObject, Default)]
+pub struct TaskFilter {
+    /// Text the name or description must contain.
+    pub query: Option<String>,
+    /// Structured filter, such as `status:open priority>=high -tag:someday`, over the `status`, `name`,
+    /// `description`, `id`, `due`, `priority`, `tag` and `project` fields like the `q` parameter of `GET /task`.
+    pub filter: Option<String>,
+    /// Only the tasks created after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only the tasks changed at or after this time.
+    pub updated_since: Option<DateTime<Utc>>,
+}
+
+/// A task of the authenticated user.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Task {
+    /// The unique identifier of the task.
+    pub id: i32,
+    /// The name of the task.
+    pub name: String,
+    /// The description of the task.
+    pub description: String,
+    /// Flag indicating whether the task is active or not.
+    pub is_active: bool,
+    /// The date the task is due, if any.
+    pub due_date: Option<NaiveDate>,
+    /// The priority of the task (`low`, `normal`, `high` or `urgent`).
+    pub priority: String,
+    /// The tags of the task.
+    pub tags: Vec<String>,
+    /// The project of the task, if any.
+    pub project: Option<String>,
+    /// Revision number of the task, increased on every change.
+    pub version: i32,
+    /// When the task was created.
+    pub created_at: DateTime<Utc>,
+    /// When the task was last changed.
+    pub updated_at: DateTime<Utc>,
+    #[graphql(skip)]
+    pub user_id: i32,
+}
+
+impl From<task::Model> for Task {
+    fn from(task: task::Model) -> Self {
+        Self {
+            id: task.id,
+            name: task.name,
+            description: task.description,
+            is_active: task.is_active,
+            due_date: task.due_date,
+            priority: task.priority.as_str().to_string(),
+            tags: task.tags,
+            project: task.project,
+            version: task.version,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            user_id: task.user_id,
+        }
+    }
+}
+
+impl From<TaskModel> for Task {
+    fn from(task: TaskModel) -> Self {
+        Self {
+            id: task.id,
+            name: task.name,
+            description: task.description,
+            is_active: task.is_active,
+            due_date: task.due_date,
+            priority: task.priority.as_str().to_string(),
+            tags: task.tags,
+            project: task.project,
+            version: task.version,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            user_id: task.user_id,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Task {
+    /// The user owning the task, loaded in one query for every task of the response.
+    async fn owner(&self, ctx: &Context<'_>) -> Result<User> {
+        ctx.data_unchecked::<DataLoader<UserLoader>>()
+            .load_one(self.user_id)
+            .await?
+            .map(User::from)
+            .ok_or_else(|| "The owner of the task no longer exists.".into())
+    }
+
+    /// Every change of the task, oldest first, loaded in one query for every task of the response.
+    #[graphql(complexity = "child_complexity.saturating_mul(10)")]
+    async fn history(&self, ctx: &Context<'_>) -> Result<Vec<TaskEvent>> {
+        Ok(ctx
+            .data_unchecked::<DataLoader<HistoryLoader>>()
+            .load_one(self.id)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(TaskEvent::from)
+            .collect())
+    }
+}
+
+/// A user of the API.
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct User {
+    /// The unique identifier of the user.
+    pub id: i32,
+    /// The name the user signs in with.
+    pub username: String,
+    /// When the user signed up.
+    pub created_at: DateTime<Utc>,
+    /// When the user was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<user::Model> for User {
+    fn from(user: user::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[ComplexObject]
+impl User {
+    /// A page of the tasks of the user, filtered like `GET /task`.
+    #[graphql(
+        complexity = "(size.unwrap_or(10).min(MAX_PAGE_SIZE) as usize).saturating_mul(child_complexity)"
+    )]
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Page number, starting at 1")] page: Option<u64>,
+        #[graphql(desc = "Page size, up to 100")] size: Option<u64>,
+        #[graphql(desc = "Sort key, defaults to `ID`")] sort: Option<TaskSort>,
+        #[graphql(default)] filter: TaskFilter,
+    ) -> Result<TaskPage> {
+        // Only the authenticated user can list their tasks
+        if ctx.data_unchecked::<Viewer>().user_id != self.id {
+            return Err("The tasks of other users are not visible.".into());
+        }
+
+        let size = size.unwrap_or(10);
+        if size > MAX_PAGE_SIZE {
+            return Err(format!("The size must be at most {MAX_PAGE_SIZE}.").into());
+        }
+
+        let tasks = TaskQueries::get_tasks(
+            PaginationPayload {
+                page: page.unwrap_or(1),
+                size,
+                query: filter.query,
+                filter: filter.filter,
+                created_after: filter.created_after,
+                updated_since: filter.updated_since,
+                sort: sort.map(Into::into).unwrap_or_default(),
+                user_id: self.id,
+            },
+            ctx.data_unchecked::<DatabaseConnection>(),
+        )
+        .await
+        .map_err(api_error)?;
+
+        Ok(tasks.into())
+    }
+}
+
+/// A page of tasks.
+#[derive(SimpleObject)]
+pub struct TaskPage {
+    /// The tasks of the page.
+    pub items: Vec<Task>,
+    /// Total number of pages.
+    pub num_pages: u64,
+    /// Number of tasks per page.
+    pub size: u64,
+    /// Current page number.
+    pub page: u64,
+}
+
+impl From<GetAllTasks> for TaskPage {
+    fn from(tasks: GetAllTasks) -> Self {
+        Self {
+            items: tasks.items.into_iter().map(Task::from).collect(),
+            num_pages: tasks.num_pages,
+            size: tasks.size,
+            page: tasks.page,
+        }
+    }
+}
+
+/// A change recorded in the history of a task.
+#[derive(SimpleObject)]
+pub struct TaskEvent {
+    /// The unique identifier of the event, usable to revert the task.
+    pub id: i32,
+    /// The ID of the task.
+    pub task_id: i32,
+    /// The kind of change: `created`, `updated`, `status_changed`, `deleted` or `reverted`.
+    pub action: String,
+    /// The changed fields, each as `{"old": ..., "new": ...}`.
+    pub changes: Json<Value>,
+    /// The ID of the request making the change, if known.
+    pub request_id: Option<String>,
+    /// When the change was made.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<task_event::Model> for TaskEvent {
+    fn from(event: task_event::Model) -> Self {
+        Self {
+            id: event.id,
+            task_id: event.task_id,
+            action: event.action,
+            changes: Json(event.changes),
+            request_id: event.request_id,
+            created_at: event.created_at,
+        }
+    }
+}
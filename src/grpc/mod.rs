@@ -0,0 +1,137 @@
+// `tonic::Status` is the error type of every gRPC handler, boxing it would only add noise
+#![allow(clippy::result_large_err)]
+
+mod task;
+mod user;
+
+use jsonwebtoken::errors::ErrorKind;
+use rocket::Shutdown;
+use sea_orm::DatabaseConnection;
+use services::auth::jwt::{Claims, JWT};
+use services::error::ApiError;
+use services::user::queries::user::UserQueries;
+use std::net::SocketAddr;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Server;
+use tonic::{Request, Status};
+
+pub use task::TaskGrpc;
+pub use user::UserGrpc;
+
+/// Code generated from `proto/todo/v1/todo.proto`.
+pub mod proto {
+    tonic::include_proto!("todo.v1");
+}
+
+use proto::task_service_server::TaskServiceServer;
+use proto::user_service_server::UserServiceServer;
+
+/// The claims of the bearer token of a gRPC call, checked against the database by `caller`.
+#[derive(Clone)]
+struct Caller(Claims);
+
+/// Checks the `authorization: Bearer <token>` metadata of a call, like the `JWT` request guard.
+///
+/// # Arguments
+///
+/// * `request` - The incoming call.
+///
+/// # Returns
+///
+/// The call, carrying the authenticated `Caller`, or `UNAUTHENTICATED`.
+///
+fn authenticate(mut request: Request<()>) -> Result<Request<()>, Status> {
+    let token = request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| Status::unauthenticated("No auth token provided"))?;
+
+    let token = JWT::decode(token.to_string()).map_err(|e| match e {
+        ErrorKind::ExpiredSignature => Status::unauthenticated("Token has expired"),
+        _ => Status::unauthenticated("Invalid user auth token."),
+    })?;
+
+    request.extensions_mut().insert(Caller(token.claims));
+    Ok(request)
+}
+
+/// Returns the user authenticated by `authenticate`, once its token is checked against the account.
+///
+/// The interceptor cannot reach the database, so tokens revoked by an account deletion are rejected here.
+///
+/// # Arguments
+///
+/// * `request` - The call.
+/// * `db` - SeaORM database connection (`&DatabaseConnection`).
+///
+/// # Returns
+///
+/// The ID of the user, or `UNAUTHENTICATED`.
+///
+async fn caller<T>(request: &Request<T>, db: &DatabaseConnection) -> Result<i32, Status> {
+    let claims = request
+        .extensions()
+        .get::<Caller>()
+        .map(|caller| caller.0.clone())
+        .ok_or_else(|| Status::unauthenticated("No auth token provided"))?;
+
+    let user = UserQueries::get_token_user(&claims, db)
+        .await
+        .map_err(status)?;
+
+    Ok(user.id)
+}
+
+/// Converts a service error into the gRPC status with the closest meaning.
+fn status(error: ApiError) -> Status {
+    let message = error.to_string();
+
+    match error {
+        ApiError::BadRequest(_) | ApiError::Validation(_) => Status::invalid_argument(message),
+        ApiError::NotFound(_) => Status::not_found(message),
+        ApiError::Forbidden(_) => Status::permission_denied(message),
+        ApiError::Conflict(_) => Status::aborted(message),
+        ApiError::PreconditionFailed(_) => Status::failed_precondition(message),
+        ApiError::Unauthorized(_) => Status::unauthenticated(message),
+        ApiError::Internal(_) => Status::internal(message),
+    }
+}
+
+/// Converts a time into its protobuf representation.
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: time.timestamp(),
+        nanos: time.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Asynchronously serves the gRPC services until Rocket shuts down.
+///
+/// # Arguments
+///
+/// * `address` - The address to listen on.
+/// * `db` - The database connection shared with Rocket.
+/// * `shutdown` - Resolves when Rocket shuts down.
+///
+/// # Returns
+///
+/// A `Result` with `()` once stopped, or the transport error that stopped the server.
+///
+pub async fn serve(
+    address: SocketAddr,
+    db: DatabaseConnection,
+    shutdown: Shutdown,
+) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_service(InterceptedService::new(
+            TaskServiceServer::new(TaskGrpc { db: db.clone() }),
+            authenticate,
+        ))
+        .add_service(InterceptedService::new(
+            UserServiceServer::new(UserGrpc { db }),
+            authenticate,
+        ))
+        .serve_with_shutdown(address, shutdown)
+        .await
+}
@@ -0,0 +1,498 @@
+use super::proto::task_change::Kind;
+use super::proto::task_service_server::TaskService;
+use super::proto::{
+    CreateTaskRequest, DeleteTaskRequest, DeleteTaskResponse, GetTaskRequest, ListTasksRequest,
+    ListTasksResponse, Task, TaskChange, TaskFields, TaskPriority, TaskSort, UpdateTaskRequest,
+    WatchTasksRequest,
+};
+use super::{caller, status, timestamp};
+use crate::routes::payload::from_json_value;
+use crate::routes::task::ManageTodo;
+use chrono::{DateTime, Utc};
+use database::entities::task;
+use rocket::serde::json::json;
+use rocket::tokio::sync::mpsc;
+use sea_orm::DatabaseConnection;
+use services::events::TaskWatch;
+use services::request_id::RequestId;
+use services::sync::mutations::sync::version_precondition;
+use services::task::models::change::{TaskChange as ServiceChange, TaskChangeKind};
+use services::task::models::task::TaskModel;
+use services::task::mutations::task::{TaskMutation, TaskPayload};
+use services::task::queries::task::{
+    self as queries, PaginationPayload, TaskQueries, MAX_PAGE_SIZE,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+/// Number of changes buffered for a slow `WatchTasks` client.
+const WATCH_BUFFER: usize = 64;
+
+/// gRPC service over the tasks of the authenticated user.
+pub struct TaskGrpc {
+    /// The database connection shared with Rocket.
+    pub db: DatabaseConnection,
+}
+
+impl From<task::Model> for Task {
+    fn from(task: task::Model) -> Self {
+        Self {
+            id: task.id,
+            name: task.name,
+            description: task.description,
+            is_active: task.is_active,
+            due_date: task.due_date.map(|date| date.to_string()),
+            priority: TaskPriority::from(task.priority).into(),
+            tags: task.tags,
+            project: task.project,
+            version: task.version,
+            user_id: task.user_id,
+            created_at: Some(timestamp(task.created_at)),
+            updated_at: Some(timestamp(task.updated_at)),
+        }
+    }
+}
+
+impl From<TaskModel> for Task {
+    fn from(task: TaskModel) -> Self {
+        Self {
+            id: task.id,
+            name: task.name,
+            description: task.description,
+            is_active: task.is_active,
+            due_date: task.due_date.map(|date| date.to_string()),
+            priority: TaskPriority::from(task.priority).into(),
+            tags: task.tags,
+            project: task.project,
+            version: task.version,
+            user_id: task.user_id,
+            created_at: Some(timestamp(task.created_at)),
+            updated_at: Some(timestamp(task.updated_at)),
+        }
+    }
+}
+
+impl From<ServiceChange> for TaskChange {
+    fn from(change: ServiceChange) -> Self {
+        let kind = match change.kind {
+            TaskChangeKind::Created => Kind::Created,
+            TaskChangeKind::Updated => Kind::Updated,
+            TaskChangeKind::Deleted => Kind::Deleted,
+        };
+
+        Self {
+            kind: kind.into(),
+            seq: change.seq,
+            task_id: change.task_id,
+            task: change.task.map(Task::from),
+        }
+    }
+}
+
+impl From<task::TaskPriority> for TaskPriority {
+    fn from(priority: task::TaskPriority) -> Self {
+        match priority {
+            task::TaskPriority::Low => TaskPriority::Low,
+            task::TaskPriority::Normal => TaskPriority::Normal,
+            task::TaskPriority::High => TaskPriority::High,
+            task::TaskPriority::Urgent => TaskPriority::Urgent,
+        }
+    }
+}
+
+/// Returns the name of a priority as sent to `ManageTodo`, `None` when it is unspecified.
+fn priority_name(priority: TaskPriority) -> Option<&'static str> {
+    match priority {
+        TaskPriority::Unspecified => None,
+        TaskPriority::Low => Some("low"),
+        TaskPriority::Normal => Some("normal"),
+        TaskPriority::High => Some("high"),
+        TaskPriority::Urgent => Some("urgent"),
+    }
+}
+
+impl From<TaskSort> for queries::TaskSort {
+    fn from(sort: TaskSort) -> Self {
+        match sort {
+            TaskSort::Id => queries::TaskSort::Id,
+            TaskSort::Name => queries::TaskSort::Name,
+            TaskSort::CreatedAt => queries::TaskSort::CreatedAt,
+            TaskSort::UpdatedAt => queries::TaskSort::UpdatedAt,
+        }
+    }
+}
+
+/// Returns the ID of a call from its `x-request-id` metadata, recorded in the task history.
+fn request_id<T>(request: &Request<T>) -> RequestId {
+    RequestId::from_sent(
+        request
+            .metadata()
+            .get("x-request-id")
+            .and_then(|id| id.to_str().ok()),
+    )
+}
+
+/// Converts a protobuf time, rejecting the ones out of range.
+fn date_time(time: Option<prost_types::Timestamp>) -> Result<Option<DateTime<Utc>>, Status> {
+    time.map(|time| {
+        u32::try_from(time.nanos)
+            .ok()
+            .and_then(|nanos| DateTime::from_timestamp(time.seconds, nanos))
+            .ok_or_else(|| Status::invalid_argument("The timestamp is out of range."))
+    })
+    .transpose()
+}
+
+/// Applies the defaults of `GET /task` to the page and size of a `ListTasks` call and bounds them.
+///
+/// # Arguments
+///
+/// * `page` - The page number sent by the client, `0` when unset.
+/// * `size` - The page size sent by the client, `0` when unset.
+///
+/// # Returns
+///
+/// A `Result` with the page and size, or `INVALID_ARGUMENT` when one is out of range.
+///
+fn page_bounds(page: u64, size: u64) -> Result<(u64, u64), Status> {
+    let page = if page == 0 { 1 } else { page };
+    let size = if size == 0 { 10 } else { size };
+
+    if size > MAX_PAGE_SIZE {
+        return Err(Status::invalid_argument(format!(
+            "The size must be at most {MAX_PAGE_SIZE}."
+        )));
+    }
+
+    // The offset of the page must fit the signed 64 bit `OFFSET` of the query
+    let max_page = i64::MAX as u64 / size;
+    if page > max_page {
+        return Err(Status::invalid_argument(format!(
+            "The page must be at most {max_page}."
+        )));
+    }
+
+    Ok((page, size))
+}
+
+/// Validates the fields of a task like `ManageTodo` and converts them into the payload of a task write.
+///
+/// # Arguments
+///
+/// * `fields` - The fields sent by the client.
+/// * `user_id` - The ID of the user writing the task.
+/// * `request_id` - The ID of the call, recorded in the task history.
+/// * `version` - The version of the task the write is based on, if any.
+///
+/// # Returns
+///
+/// A `Result` with the `TaskPayload`, or `INVALID_ARGUMENT` naming the first invalid field.
+///
+fn task_payload(
+    fields: Option<TaskFields>,
+    user_id: i32,
+    request_id: RequestId,
+    version: Option<i32>,
+) -> Result<TaskPayload, Status> {
+    let fields = fields.ok_or_else(|| Status::invalid_argument("The task is missing."))?;
+
+    from_json_value::<ManageTodo>(json!({
+        "name": fields.name,
+        "description": fields.description,
+        "is_active": fields.is_active,
+        "due_date": fields.due_date,
+        "priority": priority_name(fields.priority()),
+        "tags": fields.tags,
+        "project": fields.project,
+    }))
+    .map(|task| task.into_payload(user_id, request_id, version_precondition(version)))
+    .map_err(|errors| {
+        let error = errors.into_iter().next();
+        let message = error
+            .as_ref()
+            .map_or("The task is invalid.", |e| &e.message);
+
+        match error.as_ref().and_then(|e| e.field.as_deref()) {
+            Some(field) => Status::invalid_argument(format!("{field}: {message}")),
+            None => Status::invalid_argument(message),
+        }
+    })
+}
+
+#[tonic::async_trait]
+impl TaskService for TaskGrpc {
+    type WatchTasksStream = ReceiverStream<Result<TaskChange, Status>>;
+
+    async fn get_task(&self, request: Request<GetTaskRequest>) -> Result<Response<Task>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+
+        let task = TaskQueries::get_task_by_id(request.get_ref().id, user_id, &self.db)
+            .await
+            .map_err(status)?;
+
+        Ok(Response::new(task.into()))
+    }
+
+    async fn list_tasks(
+        &self,
+        request: Request<ListTasksRequest>,
+    ) -> Result<Response<ListTasksResponse>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+        let request = request.into_inner();
+
+        let (page, size) = page_bounds(request.page, request.size)?;
+
+        let tasks = TaskQueries::get_tasks(
+            PaginationPayload {
+                page,
+                size,
+                sort: request.sort().into(),
+                query: request.query,
+                filter: request.filter,
+                created_after: date_time(request.created_after)?,
+                updated_since: date_time(request.updated_since)?,
+                user_id,
+            },
+            &self.db,
+        )
+        .await
+        .map_err(status)?;
+
+        Ok(Response::new(ListTasksResponse {
+            items: tasks.items.into_iter().map(Task::from).collect(),
+            num_pages: tasks.num_pages,
+            size: tasks.size,
+            page: tasks.page,
+        }))
+    }
+
+    async fn create_task(
+        &self,
+        request: Request<CreateTaskRequest>,
+    ) -> Result<Response<Task>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+        let request_id = request_id(&request);
+        let payload = task_payload(request.into_inner().task, user_id, request_id, None)?;
+
+        let task = TaskMutation::create(payload, &self.db)
+            .await
+            .map_err(status)?;
+
+        Ok(Response::new(task.into()))
+    }
+
+    async fn update_task(
+        &self,
+        request: Request<UpdateTaskRequest>,
+    ) -> Result<Response<Task>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+        let request_id = request_id(&request);
+        let request = request.into_inner();
+        let payload = task_payload(request.task, user_id, request_id, request.version)?;
+
+        let task = TaskMutation::update(payload, request.id, &self.db)
+            .await
+            .map_err(status)?;
+
+        Ok(Response::new(task.into()))
+    }
+
+    async fn delete_task(
+        &self,
+        request: Request<DeleteTaskRequest>,
+    ) -> Result<Response<DeleteTaskResponse>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+        let request_id = request_id(&request);
+        let request = request.into_inner();
+
+        TaskMutation::delete(
+            request.id,
+            user_id,
+            Some(request_id.0),
+            version_precondition(request.version),
+            &self.db,
+        )
+        .await
+        .map_err(status)?;
+
+        Ok(Response::new(DeleteTaskResponse { id: request.id }))
+    }
+
+    async fn watch_tasks(
+        &self,
+        request: Request<WatchTasksRequest>,
+    ) -> Result<Response<Self::WatchTasksStream>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+        let mut watch = TaskWatch::start(user_id, request.get_ref().since, self.db.clone())
+            .await
+            .map_err(status)?;
+
+        let (sender, receiver) = mpsc::channel(WATCH_BUFFER);
+
+        rocket::tokio::spawn(async move {
+            loop {
+                let change = rocket::tokio::select! {
+                    change = watch.next() => change,
+                    // The client went away
+                    _ = sender.closed() => return,
+                };
+
+                // After an error, the client reconnects and resumes from the last change it received
+                let end = change.is_err();
+                if sender
+                    .send(change.map(TaskChange::from).map_err(status))
+                    .await
+                    .is_err()
+                    || end
+                {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    fn fields(name: &str, due_date: Option<&str>) -> Option<TaskFields> {
+        Some(TaskFields {
+            name: name.to_string(),
+            description: "Two litres".to_string(),
+            is_active: true,
+            due_date: due_date.map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    fn request_id() -> RequestId {
+        RequestId("call-1".to_string())
+    }
+
+    #[test]
+    fn task_fields_become_the_payload_of_a_write() {
+        let Ok(payload) = task_payload(
+            fields("Buy milk", Some("2026-11-02")),
+            3,
+            request_id(),
+            Some(2),
+        ) else {
+            panic!("valid fields were rejected");
+        };
+
+        assert_eq!(payload.name, "Buy milk");
+        assert!(payload.is_active);
+        assert_eq!(
+            payload.due_date.map(|date| date.to_string()).as_deref(),
+            Some("2026-11-02")
+        );
+        assert_eq!(payload.user_id, 3);
+        assert_eq!(payload.request_id.as_deref(), Some("call-1"));
+        assert!(payload
+            .if_match
+            .is_some_and(|tags| tags.matches_strong("\"v2\"")));
+    }
+
+    #[test]
+    fn task_priority_defaults_to_normal() {
+        let Ok(payload) = task_payload(fields("Buy milk", None), 3, request_id(), None) else {
+            panic!("valid fields were rejected");
+        };
+        assert_eq!(payload.priority, Some(task::TaskPriority::Normal));
+
+        let Ok(payload) = task_payload(
+            Some(TaskFields {
+                priority: TaskPriority::High.into(),
+                tags: vec!["Home".to_string(), "home".to_string()],
+                project: Some("Garden".to_string()),
+                ..fields("Buy milk", None).unwrap()
+            }),
+            3,
+            request_id(),
+            None,
+        ) else {
+            panic!("valid fields were rejected");
+        };
+        assert_eq!(payload.priority, Some(task::TaskPriority::High));
+        assert_eq!(payload.tags, Some(vec!["home".to_string()]));
+        assert_eq!(payload.project, Some(Some("Garden".to_string())));
+    }
+
+    #[test]
+    fn invalid_task_fields_name_the_field() {
+        let invalid = |fields: Option<TaskFields>| match task_payload(fields, 3, request_id(), None)
+        {
+            Err(status) => (status.code(), status.message().to_string()),
+            Ok(_) => panic!("invalid fields were accepted"),
+        };
+
+        assert_eq!(
+            invalid(fields("a", None)),
+            (
+                Code::InvalidArgument,
+                "name: The name must be between 2 to 20 characters long.".to_string()
+            )
+        );
+        assert_eq!(
+            invalid(fields("Buy milk", Some("tomorrow"))).0,
+            Code::InvalidArgument
+        );
+        assert!(invalid(fields("Buy milk", Some("tomorrow")))
+            .1
+            .starts_with("due_date: "));
+        assert_eq!(
+            invalid(None),
+            (Code::InvalidArgument, "The task is missing.".to_string())
+        );
+    }
+
+    #[test]
+    fn pages_default_like_the_rest_api_and_are_bounded() {
+        assert_eq!(page_bounds(0, 0).unwrap(), (1, 10));
+        assert_eq!(page_bounds(3, MAX_PAGE_SIZE).unwrap(), (3, MAX_PAGE_SIZE));
+        assert_eq!(
+            page_bounds(1, MAX_PAGE_SIZE + 1).unwrap_err().message(),
+            "The size must be at most 100."
+        );
+        assert_eq!(
+            page_bounds(i64::MAX as u64 / 10, 0).unwrap(),
+            (i64::MAX as u64 / 10, 10)
+        );
+        assert_eq!(
+            page_bounds(u64::MAX, 10).unwrap_err().code(),
+            Code::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn timestamps_out_of_range_are_rejected() {
+        let time = |seconds, nanos| date_time(Some(prost_types::Timestamp { seconds, nanos }));
+
+        assert_eq!(date_time(None).unwrap(), None);
+        assert_eq!(
+            time(1_792_000_000, 5).unwrap(),
+            DateTime::from_timestamp(1_792_000_000, 5)
+        );
+        assert_eq!(time(0, -1).unwrap_err().code(), Code::InvalidArgument);
+        assert_eq!(time(i64::MAX, 0).unwrap_err().code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn deleted_tasks_are_sent_without_fields() {
+        let change = TaskChange::from(ServiceChange {
+            kind: TaskChangeKind::Deleted,
+            seq: 9,
+            user_id: 3,
+            task_id: 7,
+            task: None,
+        });
+
+        assert_eq!(change.kind(), Kind::Deleted);
+        assert_eq!((change.seq, change.task_id), (9, 7));
+        assert_eq!(change.task, None);
+    }
+}
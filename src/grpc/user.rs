@@ -0,0 +1,46 @@
+use super::proto::user_service_server::UserService;
+use super::proto::{GetCurrentUserRequest, User};
+use super::{caller, status, timestamp};
+use database::entities::user;
+use sea_orm::DatabaseConnection;
+use services::error::ApiError;
+use services::user::queries::user::UserQueries;
+use tonic::{Request, Response, Status};
+
+/// gRPC service over the users.
+pub struct UserGrpc {
+    /// The database connection shared with Rocket.
+    pub db: DatabaseConnection,
+}
+
+impl From<user::Model> for User {
+    fn from(user: user::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            created_at: Some(timestamp(user.created_at)),
+            updated_at: Some(timestamp(user.updated_at)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl UserService for UserGrpc {
+    async fn get_current_user(
+        &self,
+        request: Request<GetCurrentUserRequest>,
+    ) -> Result<Response<User>, Status> {
+        let user_id = caller(&request, &self.db).await?;
+
+        UserQueries::get_users_by_ids(&[user_id], &self.db)
+            .await
+            .map_err(status)?
+            .pop()
+            .map(|user| Response::new(user.into()))
+            .ok_or_else(|| {
+                status(ApiError::Unauthorized(
+                    "The user of this auth token no longer exists.".to_string(),
+                ))
+            })
+    }
+}
@@ -1,20 +1,419 @@
+mod caldav;
+mod graphql;
+mod grpc;
+mod openapi;
 mod routes;
 
 use database::Db;
-use rocket::routes;
+use migration::{Migrator, MigratorTrait};
+use rocket::fairing::{self, AdHoc};
+use rocket::figment::Figment;
+use rocket::serde::Deserialize;
+use rocket::{catchers, routes, Build, Orbit, Rocket};
 use sea_orm_rocket::Database;
+use services::events::TaskEvents;
+use services::idempotency::mutations::idempotency_key::IdempotencyMutation;
+use services::request_id::RequestIdFairing;
+use services::user::mutations::user::UserMutations;
+use services::webhook::mutations::delivery::{WebhookDeliveryMutation, DELIVERY_BATCH};
+use services::webhook::sender::WebhookSender;
 use std::env;
+use std::net::SocketAddr;
+use std::time::Duration;
+use utoipa::OpenApi;
+use utoipa_rapidoc::RapiDoc;
 
-use crate::routes::auth::{me, sign_in, sign_up};
+use crate::openapi::ApiDoc;
+
+use crate::routes::app_password::{create_app_password, delete_app_password, get_app_passwords};
+use crate::routes::auth::{delete_account, export_account, me, restore_account, sign_in, sign_up};
+use crate::routes::calendar::{
+    create_calendar_feed, delete_calendar_feed, get_calendar_feed, get_calendar_feeds,
+};
+use crate::routes::catchers::{
+    bad_request, default_catcher, internal_error, not_found, unauthorized, unprocessable_entity,
+};
+use crate::routes::events::events;
+use crate::routes::graphql::{graphiql, graphql_request};
+use crate::routes::import::import_from_provider;
 use crate::routes::ping::ping_route;
-use crate::routes::task::{create_task, delete_task, get_task, get_tasks, update_task};
+use crate::routes::sync::{get_sync, post_sync};
+use crate::routes::task::{
+    create_task, delete_task, export_tasks, get_task, get_task_history, get_tasks, import_tasks,
+    patch_task, revert_task, search_tasks, update_task,
+};
+use crate::routes::view::{create_view, delete_view, get_view_tasks, get_views, update_view};
+use crate::routes::webhook::{
+    create_webhook, delete_webhook, get_webhook_deliveries, get_webhooks, redeliver_webhook,
+    update_webhook,
+};
+
+/// What the server does with the pending database migrations when it starts, the `migrate` setting.
+///
+/// Unless told otherwise the server only checks them, so upgrading the binary never changes the schema
+/// by surprise; the Docker image sets `auto`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MigrateMode {
+    /// Apply them before serving.
+    Auto,
+    /// Refuse to start while any is pending.
+    #[default]
+    Check,
+    /// Leave the schema alone.
+    Off,
+}
+
+/// Applies or checks the database migrations before the server starts, as set by `migrate`.
+///
+/// # Arguments
+///
+/// * `rocket` - The Rocket instance being ignited, owning the database connection.
+///
+/// # Returns
+///
+/// The Rocket instance, or an error stopping the launch if the setting is invalid, a migration fails,
+/// or migrations are pending in `check` mode.
+///
+async fn run_migrations(rocket: Rocket<Build>) -> fairing::Result {
+    let mode = match rocket.figment().extract_inner::<MigrateMode>("migrate") {
+        Ok(mode) => mode,
+        Err(e) if e.missing() => MigrateMode::default(),
+        Err(e) => {
+            println!("Invalid `migrate` setting, expected `auto`, `check` or `off`: {e}");
+            return Err(rocket);
+        }
+    };
+    if mode == MigrateMode::Off {
+        return Ok(rocket);
+    }
+
+    let Some(db) = Db::fetch(&rocket) else {
+        return Err(rocket);
+    };
+    let conn = &db.conn;
+
+    match mode {
+        MigrateMode::Auto => match Migrator::up_locked(conn).await {
+            Ok(applied) => {
+                for name in &applied {
+                    println!("Applied migration {name}.");
+                }
+            }
+            Err(e) => {
+                println!("The database migrations failed: {e}");
+                return Err(rocket);
+            }
+        },
+        MigrateMode::Check => match Migrator::get_pending_migrations(conn).await {
+            Ok(pending) if pending.is_empty() => {}
+            Ok(pending) => {
+                let names = pending
+                    .iter()
+                    .map(|migration| migration.name())
+                    .collect::<Vec<_>>();
+                println!(
+                    "Refusing to start with pending migrations: {}. Apply them with `todo-admin migrate up`.",
+                    names.join(", ")
+                );
+                return Err(rocket);
+            }
+            Err(e) => {
+                println!("The database migrations cannot be checked: {e}");
+                return Err(rocket);
+            }
+        },
+        MigrateMode::Off => {}
+    }
 
-/*async fn run_migrations(rocket: Rocket<Build>) -> fairing::Result {
-    let conn = &Db::fetch(&rocket).unwrap().conn;
-    let _ = migration::Migrator::up(conn, None).await;
     Ok(rocket)
 }
-*/
+
+/// How often the expired idempotency keys are swept.
+const IDEMPOTENCY_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Spawns the background job deleting the expired idempotency keys, once the server is up.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+fn sweep_idempotency_keys(rocket: &Rocket<Orbit>) {
+    let Some(db) = Db::fetch(rocket) else {
+        return;
+    };
+    let conn = db.conn.clone();
+
+    rocket::tokio::spawn(async move {
+        let mut interval = rocket::tokio::time::interval(IDEMPOTENCY_SWEEP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            // Errors are already logged, the next sweep tries again
+            if let Ok(swept) = IdempotencyMutation::sweep_expired(&conn).await {
+                if swept > 0 {
+                    println!("Swept {swept} expired idempotency keys.");
+                }
+            }
+        }
+    });
+}
+
+/// How often the accounts whose grace period is over are deleted.
+const ACCOUNT_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Spawns the background job deleting the accounts scheduled for deletion, once the server is up.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+fn purge_deleted_accounts(rocket: &Rocket<Orbit>) {
+    let Some(db) = Db::fetch(rocket) else {
+        return;
+    };
+    let conn = db.conn.clone();
+
+    rocket::tokio::spawn(async move {
+        let mut interval = rocket::tokio::time::interval(ACCOUNT_PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            // Errors are already logged, the next purge tries again
+            if let Ok(purged) = UserMutations::purge_deleted(&conn).await {
+                if purged > 0 {
+                    println!("Deleted {purged} accounts scheduled for deletion.");
+                }
+            }
+        }
+    });
+}
+
+/// How often the outbox of the webhook deliveries is checked for due deliveries.
+const WEBHOOK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spawns the background worker sending the due webhook deliveries, once the server is up.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+fn deliver_webhooks(rocket: &Rocket<Orbit>) {
+    let Some(db) = Db::fetch(rocket) else {
+        return;
+    };
+    let conn = db.conn.clone();
+    let sender = WebhookSender::new();
+
+    rocket::tokio::spawn(async move {
+        let mut interval = rocket::tokio::time::interval(WEBHOOK_POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            // Keep draining while full batches come back, errors are retried on the next tick
+            while let Ok(attempted) = WebhookDeliveryMutation::deliver_due(&sender, &conn).await {
+                if attempted < DELIVERY_BATCH as usize {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Relays the task changes of every API instance through Postgres `LISTEN/NOTIFY`, once the
+/// server is up, when the `events_notify` setting is enabled.
+///
+/// Without it, the event stream only sees the changes made through this instance.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+async fn listen_task_events(rocket: &Rocket<Orbit>) {
+    let enabled = rocket
+        .figment()
+        .extract_inner::<bool>("events_notify")
+        .unwrap_or(false);
+    let Some(db) = Db::fetch(rocket).filter(|_| enabled) else {
+        return;
+    };
+
+    if let Err(e) = TaskEvents::listen(&db.conn).await {
+        println!("Could not listen to the task changes, streaming local changes only: {e}");
+    }
+}
+
+/// Serves the gRPC services alongside Rocket, once the server is up, when the `grpc_address`
+/// setting is set.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+fn serve_grpc(rocket: &Rocket<Orbit>) {
+    let Ok(address) = rocket.figment().extract_inner::<SocketAddr>("grpc_address") else {
+        return;
+    };
+    let Some(db) = Db::fetch(rocket) else {
+        return;
+    };
+    let conn = db.conn.clone();
+    let shutdown = rocket.shutdown();
+
+    rocket::tokio::spawn(async move {
+        println!("gRPC server listening on {address}.");
+
+        if let Err(e) = grpc::serve(address, conn, shutdown).await {
+            println!("The gRPC server stopped: {e}");
+        }
+    });
+}
+
+/// Serves the CalDAV task lists alongside Rocket, once the server is up, when the `dav_address`
+/// setting is set.
+///
+/// # Arguments
+///
+/// * `rocket` - The launched Rocket instance, owning the database connection.
+///
+fn serve_caldav(rocket: &Rocket<Orbit>) {
+    let Ok(address) = rocket.figment().extract_inner::<SocketAddr>("dav_address") else {
+        return;
+    };
+    let Some(db) = Db::fetch(rocket) else {
+        return;
+    };
+    let conn = db.conn.clone();
+    let shutdown = rocket.shutdown();
+
+    rocket::tokio::spawn(async move {
+        println!("CalDAV server listening on {address}.");
+
+        if let Err(e) = caldav::serve(address, conn, shutdown).await {
+            println!("The CalDAV server stopped: {e}");
+        }
+    });
+}
+
+/// Builds the Rocket instance with every route, catcher and fairing of the API.
+///
+/// # Arguments
+///
+/// * `figment` - The Rocket configuration to build the instance with.
+///
+/// # Returns
+///
+/// The Rocket instance, ready to be launched.
+///
+fn build_rocket(figment: Figment) -> Rocket<Build> {
+    rocket::custom(figment)
+        .mount("/", routes![ping_route])
+        .mount(
+            "/task",
+            routes![
+                create_task,
+                update_task,
+                patch_task,
+                delete_task,
+                get_tasks,
+                search_tasks,
+                get_task,
+                get_task_history,
+                revert_task,
+                export_tasks,
+                import_tasks
+            ],
+        )
+        .mount(
+            "/task/views",
+            routes![
+                get_views,
+                create_view,
+                update_view,
+                delete_view,
+                get_view_tasks
+            ],
+        )
+        .mount("/import", routes![import_from_provider])
+        .mount("/sync", routes![get_sync, post_sync])
+        .mount("/events", routes![events])
+        .mount("/graphql", routes![graphql_request, graphiql])
+        .mount(
+            "/webhooks",
+            routes![
+                get_webhooks,
+                create_webhook,
+                update_webhook,
+                delete_webhook,
+                get_webhook_deliveries,
+                redeliver_webhook
+            ],
+        )
+        .mount(
+            "/auth",
+            routes![
+                sign_in,
+                sign_up,
+                me,
+                export_account,
+                delete_account,
+                restore_account
+            ],
+        )
+        .mount(
+            "/auth/app-passwords",
+            routes![get_app_passwords, create_app_password, delete_app_password],
+        )
+        .mount(
+            "/calendar/feeds",
+            routes![
+                get_calendar_feeds,
+                create_calendar_feed,
+                delete_calendar_feed,
+                get_calendar_feed
+            ],
+        )
+        .mount(
+            "/",
+            RapiDoc::with_openapi("/openapi.json", ApiDoc::openapi()).path("/docs"),
+        )
+        .register(
+            "/",
+            catchers![
+                bad_request,
+                unauthorized,
+                not_found,
+                unprocessable_entity,
+                internal_error,
+                default_catcher
+            ],
+        )
+        .manage(graphql::build_schema()) // Share the GraphQL schema between requests
+        .attach(Db::init()) // Initialize database connection
+        .attach(AdHoc::try_on_ignite("Migrations", run_migrations)) // Apply or check the database migrations
+        .attach(RequestIdFairing) // Echo the request ID in every response
+        .attach(AdHoc::on_liftoff("Idempotency key sweep", |rocket| {
+            Box::pin(async move { sweep_idempotency_keys(rocket) })
+        })) // Delete the expired idempotency keys in the background
+        .attach(AdHoc::on_liftoff("Account deletion purge", |rocket| {
+            Box::pin(async move { purge_deleted_accounts(rocket) })
+        })) // Delete the accounts whose grace period is over in the background
+        .attach(AdHoc::on_liftoff("Task event listener", |rocket| {
+            Box::pin(listen_task_events(rocket))
+        })) // Share the task changes between instances
+        .attach(AdHoc::on_liftoff("Webhook delivery worker", |rocket| {
+            Box::pin(async move { deliver_webhooks(rocket) })
+        })) // Send the queued webhook deliveries in the background
+        .attach(AdHoc::on_liftoff("gRPC server", |rocket| {
+            Box::pin(async move { serve_grpc(rocket) })
+        })) // Serve the gRPC services alongside the HTTP API
+        .attach(AdHoc::on_liftoff("CalDAV server", |rocket| {
+            Box::pin(async move { serve_caldav(rocket) })
+        })) // Serve the task lists to calendar clients alongside the HTTP API
+}
 
 /// Asynchronously starts the Rocket API server.
 ///
@@ -25,6 +424,7 @@ use crate::routes::task::{create_task, delete_task, get_task, get_tasks, update_
 ///
 /// A `Result` indicating success (`Ok(())`) or failure (`Err(rocket::Error)`).
 ///
+#[allow(clippy::result_large_err)]
 #[tokio::main]
 async fn start_api() -> Result<(), rocket::Error> {
     // Configure Rocket with database URL from environment variable
@@ -34,15 +434,7 @@ async fn start_api() -> Result<(), rocket::Error> {
     ));
 
     // Custom Rocket instance with configured routes and database attachment
-    rocket::custom(figment)
-        .mount("/", routes![ping_route])
-        .mount(
-            "/task",
-            routes![create_task, update_task, delete_task, get_tasks, get_task],
-        )
-        .mount("/auth", routes![sign_in, sign_up, me])
-        .attach(Db::init()) // Initialize database connection
-        // .attach(AdHoc::try_on_ignite("Migrations", run_migrations)) // Run database migrations
+    build_rocket(figment)
         .launch() // Launch the Rocket server
         .await // Await server launch completion
         .map(|_| ()) // Map launch result to Ok(())
@@ -64,3 +456,77 @@ fn main() {
         println!("Error: {err}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every mounted route, except the documentation itself, must be described by the OpenAPI document.
+    #[test]
+    fn every_route_is_documented() {
+        let rocket = build_rocket(rocket::Config::figment());
+        let spec = ApiDoc::openapi();
+
+        for route in rocket.routes() {
+            let path = route.uri.path().to_string();
+            if path == "/docs" || path == "/openapi.json" {
+                continue;
+            }
+
+            // Rocket writes dynamic segments as `<id>` or `<path..>`, OpenAPI as `{id}`
+            let path = path
+                .split('/')
+                .map(|segment| match segment.strip_prefix('<') {
+                    Some(name) => {
+                        format!("{{{}}}", name.trim_end_matches('>').trim_end_matches(".."))
+                    }
+                    None => segment.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+
+            let item = spec
+                .paths
+                .paths
+                .iter()
+                .find(|(documented, _)| {
+                    documented.trim_end_matches('/') == path.trim_end_matches('/')
+                })
+                .map(|(_, item)| item);
+
+            let operation = item.and_then(|item| match route.method.as_str() {
+                "GET" => item.get.as_ref(),
+                "POST" => item.post.as_ref(),
+                "PATCH" => item.patch.as_ref(),
+                "PUT" => item.put.as_ref(),
+                "DELETE" => item.delete.as_ref(),
+                _ => None,
+            });
+
+            assert!(
+                operation.is_some(),
+                "{} {} is missing from the OpenAPI document",
+                route.method,
+                path
+            );
+        }
+    }
+
+    /// The `migrate` setting accepts its three modes in lowercase, and nothing else.
+    #[test]
+    fn migrate_mode_is_read_from_the_config() {
+        let mode = |value: &str| {
+            Figment::new()
+                .merge(("migrate", value))
+                .extract_inner::<MigrateMode>("migrate")
+                .ok()
+        };
+
+        assert_eq!(MigrateMode::default(), MigrateMode::Check);
+        assert_eq!(mode("auto"), Some(MigrateMode::Auto));
+        assert_eq!(mode("check"), Some(MigrateMode::Check));
+        assert_eq!(mode("off"), Some(MigrateMode::Off));
+        assert_eq!(mode("Check"), None);
+        assert_eq!(mode("sometimes"), None);
+    }
+}
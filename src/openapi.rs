@@ -0,0 +1,88 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::routes::{
+    app_password, auth, calendar, events, graphql, import, ping, sync, task, view, webhook,
+};
+
+/// OpenAPI document of the API, generated from the route definitions.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Simple TODO API"),
+    paths(
+        ping::ping_route,
+        task::create_task,
+        task::update_task,
+        task::delete_task,
+        task::get_tasks,
+        task::search_tasks,
+        task::get_task,
+        task::get_task_history,
+        task::revert_task,
+        task::export_tasks,
+        task::import_tasks,
+        import::import_from_provider,
+        view::get_views,
+        view::create_view,
+        view::update_view,
+        view::delete_view,
+        view::get_view_tasks,
+        sync::get_sync,
+        sync::post_sync,
+        events::events,
+        webhook::get_webhooks,
+        webhook::create_webhook,
+        webhook::update_webhook,
+        webhook::delete_webhook,
+        webhook::get_webhook_deliveries,
+        webhook::redeliver_webhook,
+        graphql::graphql_request,
+        graphql::graphiql,
+        auth::sign_in,
+        auth::sign_up,
+        auth::me,
+        auth::export_account,
+        auth::delete_account,
+        auth::restore_account,
+        app_password::get_app_passwords,
+        app_password::create_app_password,
+        app_password::delete_app_password,
+        calendar::get_calendar_feeds,
+        calendar::create_calendar_feed,
+        calendar::delete_calendar_feed,
+        calendar::get_calendar_feed,
+    ),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "Task", description = "Tasks of the current auth user"),
+        (name = "Import", description = "Imports of the tasks exported from other services"),
+        (name = "View", description = "Built-in views and saved filters of the current auth user"),
+        (name = "Sync", description = "Delta sync of the tasks for offline clients"),
+        (name = "Events", description = "Live stream of the task changes"),
+        (name = "GraphQL", description = "GraphQL schema over the tasks and users"),
+        (name = "Webhook", description = "Signed task event deliveries to the URLs of the current auth user"),
+        (name = "Calendar", description = "Read-only iCalendar feeds of the tasks, for calendar apps"),
+        (name = "Auth", description = "Sign in, sign up, current user and app passwords"),
+        (name = "Misc", description = "API status")
+    )
+)]
+pub struct ApiDoc;
+
+/// Adds the bearer token scheme used by the `JWT` guard to the document.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::routes::payload::FormOrJson;
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::entities::app_password as AppPassword;
+use database::Db;
+use rocket::form::validate::msg;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{delete, get, post, FromForm};
+use sea_orm_rocket::Connection;
+use services::app_password::models::app_password::NewAppPassword;
+use services::app_password::mutations::app_password::AppPasswordMutation;
+use services::app_password::queries::app_password::AppPasswordQueries;
+use services::auth::jwt::JWT;
+use services::error::FieldError;
+use utoipa::ToSchema;
+
+/// Payload structure for creating an app password.
+#[derive(Serialize, Deserialize, FromForm, ToSchema)]
+pub struct ManageAppPassword {
+    /// The name of the app password, such as the client using it.
+    #[field(validate = len(1..=100).or_else(msg!("The name must be between 1 to 100 characters long.")))]
+    pub name: String,
+}
+
+/// Endpoint for listing the app passwords of the current user.
+///
+/// This function handles the HTTP GET request returning the app passwords of the user, without the passwords.
+///
+/// # Arguments
+///
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<AppPassword::Model>>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/auth/app-passwords",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "App passwords of the current user", body = ResponseRequest<Vec<AppPassword::Model>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn get_app_passwords(
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Vec<AppPassword::Model>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Fetch the app passwords of the user
+    let app_passwords = AppPasswordQueries::get_app_passwords(user.claims.sub, db).await?;
+
+    // Return a successful response with the app passwords
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: app_passwords,
+        }),
+    ))
+}
+
+/// Endpoint for creating an app password.
+///
+/// This function handles the HTTP POST request generating a password that clients
+/// authenticating with HTTP Basic, such as CalDAV clients, can use in place of the account password.
+/// The password is only returned in this response.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageAppPassword` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<NewAppPassword>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/auth/app-passwords",
+    tag = "Auth",
+    request_body(content(
+        (ManageAppPassword = "application/json"),
+        (ManageAppPassword = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "App password created successfully", body = ResponseRequest<NewAppPassword>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid name", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/create", data = "<form>")]
+pub async fn create_app_password(
+    form: FormOrJson<ManageAppPassword>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<NewAppPassword> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to create the app password
+    let created = AppPasswordMutation::create(form.into_inner().name, user.claims.sub, db).await?;
+
+    // Return a successful response with the password, shown only once
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("App password created successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: created,
+        }),
+    ))
+}
+
+/// Endpoint for revoking an app password.
+///
+/// This function handles the HTTP DELETE request deleting the app password identified by `id`,
+/// the clients using it can no longer authenticate.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the app password.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<u64>`) with the number of deleted app passwords and status `200 OK` on success,
+/// `404 Not Found` if the app password is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/auth/app-passwords",
+    tag = "Auth",
+    params(
+        ("id" = i32, Path, description = "ID of the app password")
+    ),
+    responses(
+        (status = 200, description = "App password revoked successfully", body = ResponseRequest<u64>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "App password not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/delete/<id>")]
+pub async fn delete_app_password(id: i32, user: JWT, conn: Connection<'_, Db>) -> ApiResponse<u64> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to revoke the app password
+    let deleted = AppPasswordMutation::delete(id, user.claims.sub, db).await?;
+
+    // Return a successful response with the number of rows affected
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("App password revoked successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: deleted.rows_affected,
+        }),
+    ))
+}
@@ -1,21 +1,24 @@
-use crate::routes::{Response, ResponseRequest};
+use crate::routes::payload::FormOrJson;
+use crate::routes::task::ExportResponse;
+use crate::routes::{ApiResponse, ResponseRequest};
 use database::entities::user as User;
 use database::Db;
 use rocket::form::validate::msg;
-use rocket::form::Form;
-use rocket::http::Status;
+use rocket::http::{ContentType, Header, Status};
 use rocket::response::status::Custom;
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
-use rocket::{get, post, FromForm};
+use rocket::{delete, get, post, FromForm};
 use sea_orm_rocket::Connection;
 use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
 use services::user::models::user::UserModel;
-use services::user::mutations::user::{SignIn, UserMutations};
+use services::user::mutations::user::{AccountDeletion, SignIn, UserMutations};
 use services::user::queries::user::UserQueries;
+use utoipa::ToSchema;
 
 /// Payload structure for signing in a user.
-#[derive(Serialize, Deserialize, FromForm)]
+#[derive(Serialize, Deserialize, FromForm, ToSchema)]
 pub struct SignInPayload {
     /// Username input for sign-in.
     #[field(validate = len(5..).or_else(msg!("The username must be at least 5 characters long.")))]
@@ -25,25 +28,48 @@ pub struct SignInPayload {
     pub password: String,
 }
 
+/// Payload structure for deleting the account of the current user.
+#[derive(Serialize, Deserialize, FromForm, ToSchema)]
+pub struct DeleteAccountPayload {
+    /// Password of the user, confirming the deletion.
+    #[field(validate = len(1..).or_else(msg!("The password is required.")))]
+    pub password: String,
+}
+
 /// Endpoint for user sign-in.
 ///
 /// This function handles the HTTP POST request to authenticate and sign in a user.
-/// It expects a JSON payload `SignInPayload` containing username and password.
+/// It expects a form or JSON payload `SignInPayload` containing username and password.
 ///
 /// # Arguments
 ///
-/// * `payload` - JSON payload containing `SignInPayload` data.
+/// * `payload` - Form or JSON payload containing `SignInPayload` data.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<SignIn>>`) with status `200 OK` on success or `401 Unauthorized` on failure.
+/// A custom response (`ApiResponse<SignIn>`) with status `200 OK` on success, `401 Unauthorized` if the credentials are invalid,
+/// or `403 Forbidden` if the account is scheduled for deletion or deactivated.
 ///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    request_body(content(
+        (SignInPayload = "application/json"),
+        (SignInPayload = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Sign in successful", body = ResponseRequest<SignIn>),
+        (status = 401, description = "Invalid credentials", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 403, description = "The account is scheduled for deletion or deactivated", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid credentials format", body = ResponseRequest<Option<Vec<FieldError>>>)
+    )
+)]
 #[post("/sign-in", data = "<payload>")]
 pub async fn sign_in(
-    payload: Form<SignInPayload>,
+    payload: FormOrJson<SignInPayload>,
     conn: Connection<'_, Db>,
-) -> Response<Option<SignIn>> {
+) -> ApiResponse<SignIn> {
     // Extract database connection and payload data
     let db = conn.into_inner();
 
@@ -51,49 +77,52 @@ pub async fn sign_in(
     let payload = payload.into_inner();
 
     // Attempt to sign in the user using provided credentials
-    let sign_in_result = UserMutations::sign_in(payload.username, payload.password, db).await;
-
-    match sign_in_result {
-        // Return a successful response with sign-in details
-        Ok(sign_in) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                status: Status::Ok,
-                message: Some("Sign in successful".to_string()),
-                data: Some(sign_in),
-            }),
-        ),
-        // Return an unauthorized response with the error message
-        Err(e) => Custom(
-            Status::Unauthorized,
-            Json(ResponseRequest {
-                status: Status::Unauthorized,
-                message: Some(e.to_string()),
-                data: None,
-            }),
-        ),
-    }
+    let sign_in = UserMutations::sign_in(payload.username, payload.password, db).await?;
+
+    // Return a successful response with sign-in details
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            status: Status::Ok,
+            message: Some("Sign in successful".to_string()),
+            code: None,
+            data: sign_in,
+        }),
+    ))
 }
 
 /// Endpoint for user sign-up.
 ///
 /// This function handles the HTTP POST request to create a new user account.
-/// It expects a JSON payload `SignInPayload` containing username and password.
+/// It expects a form or JSON payload `SignInPayload` containing username and password.
 ///
 /// # Arguments
 ///
-/// * `payload` - JSON payload containing `SignInPayload` data.
+/// * `payload` - Form or JSON payload containing `SignInPayload` data.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<User::Model>>`) with status `200 OK` on success or `401 Unauthorized` on failure.
+/// A custom response (`ApiResponse<User::Model>`) with status `200 OK` on success or `409 Conflict` if the username is taken.
 ///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    request_body(content(
+        (SignInPayload = "application/json"),
+        (SignInPayload = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Sign up successful", body = ResponseRequest<User::Model>),
+        (status = 409, description = "The username is taken", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid credentials format", body = ResponseRequest<Option<Vec<FieldError>>>)
+    )
+)]
 #[post("/sign-up", data = "<payload>")]
 pub async fn sign_up(
-    payload: Form<SignInPayload>,
+    payload: FormOrJson<SignInPayload>,
     conn: Connection<'_, Db>,
-) -> Response<Option<User::Model>> {
+) -> ApiResponse<User::Model> {
     // Extract database connection and payload data
     let db = conn.into_inner();
 
@@ -101,28 +130,18 @@ pub async fn sign_up(
     let payload = payload.into_inner();
 
     // Attempt to create a new user account using provided credentials
-    let sign_up_result = UserMutations::create(payload.username, payload.password, db).await;
-
-    match sign_up_result {
-        // Return a successful response with sign-up details
-        Ok(sign_up) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                status: Status::Ok,
-                message: Some("Sign up successful".to_string()),
-                data: Some(sign_up),
-            }),
-        ),
-        // Return an unauthorized response with the error message
-        Err(e) => Custom(
-            Status::Unauthorized,
-            Json(ResponseRequest {
-                status: Status::Unauthorized,
-                message: Some(e.to_string()),
-                data: None,
-            }),
-        ),
-    }
+    let sign_up = UserMutations::create(payload.username, payload.password, db).await?;
+
+    // Return a successful response with sign-up details
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            status: Status::Ok,
+            message: Some("Sign up successful".to_string()),
+            code: None,
+            data: sign_up,
+        }),
+    ))
 }
 
 /// Endpoint to fetch current user information.
@@ -137,34 +156,195 @@ pub async fn sign_up(
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<UserModel>>`) with status `200 OK` on success or `401 Unauthorized` on failure.
+/// A custom response (`ApiResponse<UserModel>`) with status `200 OK` on success or `401 Unauthorized` on failure.
 ///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Current user and tasks", body = ResponseRequest<UserModel>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
 #[get("/me")]
-pub async fn me(user: JWT, conn: Connection<'_, Db>) -> Response<Option<UserModel>> {
+pub async fn me(user: JWT, conn: Connection<'_, Db>) -> ApiResponse<UserModel> {
     // Extract database connection
     let db = conn.into_inner();
 
     // Retrieve current user information using the provided JWT
-    let user = UserQueries::get_current_user(user, db).await;
-
-    match user {
-        // Return a successful response with user details
-        Ok(u) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                status: Status::Ok,
-                message: Some("Sign up successful".to_string()),
-                data: Some(u),
-            }),
-        ),
-        // Return an unauthorized response with the error message
-        Err(e) => Custom(
-            Status::Unauthorized,
-            Json(ResponseRequest {
-                status: Status::Unauthorized,
-                message: Some(e.to_string()),
-                data: None,
-            }),
+    let u = UserQueries::get_current_user(user, db).await?;
+
+    // Return a successful response with user details
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            status: Status::Ok,
+            message: Some("User fetched successfully".to_string()),
+            code: None,
+            data: u,
+        }),
+    ))
+}
+
+/// Endpoint for exporting every piece of data of the current user.
+///
+/// This function handles the HTTP GET request writing the account, tasks, task history, saved filters,
+/// webhooks and their deliveries, app passwords, calendar feeds, CalDAV objects and idempotency keys of the
+/// current user as a zip archive of JSON files. Password hashes, webhook secrets and feed tokens are left out.
+///
+/// # Arguments
+///
+/// * `user` - JWT containing user claims.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// An `ExportResponse` with the archive and status `200 OK` on success, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    responses(
+        (status = 200, description = "Zip archive of the data of the current user", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/me/export")]
+pub async fn export_account(
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> Result<ExportResponse<Vec<u8>>, ApiError> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Gather the data of the user and write the archive
+    let export = UserQueries::get_account_export(user.claims.sub, db).await?;
+    let filename = format!("todo-export-{}.zip", export.user.username);
+    let archive = export.into_archive()?;
+
+    Ok(ExportResponse {
+        body: (ContentType::ZIP, archive),
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{filename}\""),
         ),
-    }
+    })
+}
+
+/// Endpoint for deleting the account of the current user.
+///
+/// This function handles the HTTP DELETE request scheduling the deletion of the account, confirmed by
+/// the password of the user. The account is deleted for good after a grace period of 30 days, and until then
+/// its auth tokens are revoked and signing in requires restoring it through `POST /auth/restore`.
+/// Its app passwords and calendar feeds are deleted right away.
+///
+/// # Arguments
+///
+/// * `payload` - Form or JSON payload containing `DeleteAccountPayload` data.
+/// * `user` - JWT containing user claims.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<AccountDeletion>`) with status `202 Accepted` on success,
+/// `403 Forbidden` if the password is wrong, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    request_body(content(
+        (DeleteAccountPayload = "application/json"),
+        (DeleteAccountPayload = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 202, description = "Account deletion scheduled", body = ResponseRequest<AccountDeletion>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 403, description = "Invalid password", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Missing password", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/me", data = "<payload>")]
+pub async fn delete_account(
+    payload: FormOrJson<DeleteAccountPayload>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<AccountDeletion> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Extract payload data
+    let payload = payload.into_inner();
+
+    // Attempt to schedule the deletion of the account
+    let deletion = UserMutations::schedule_deletion(user.claims.sub, payload.password, db).await?;
+
+    // Return an accepted response with the date of the deletion
+    Ok(Custom(
+        Status::Accepted,
+        Json(ResponseRequest {
+            status: Status::Accepted,
+            message: Some("Account deletion scheduled".to_string()),
+            code: None,
+            data: deletion,
+        }),
+    ))
+}
+
+/// Endpoint for restoring an account scheduled for deletion.
+///
+/// This function handles the HTTP POST request cancelling the deletion of an account during its grace period.
+/// It expects a form or JSON payload `SignInPayload` containing username and password, and signs the user in.
+///
+/// # Arguments
+///
+/// * `payload` - Form or JSON payload containing `SignInPayload` data.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<SignIn>`) with status `200 OK` on success, `401 Unauthorized` if the credentials
+/// are invalid, `403 Forbidden` if the account is deactivated, or `409 Conflict` if the account is not scheduled for deletion.
+///
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "Auth",
+    request_body(content(
+        (SignInPayload = "application/json"),
+        (SignInPayload = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Account restored", body = ResponseRequest<SignIn>),
+        (status = 401, description = "Invalid credentials", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 403, description = "The account is deactivated", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 409, description = "The account is not scheduled for deletion", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid credentials format", body = ResponseRequest<Option<Vec<FieldError>>>)
+    )
+)]
+#[post("/restore", data = "<payload>")]
+pub async fn restore_account(
+    payload: FormOrJson<SignInPayload>,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<SignIn> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Extract payload data
+    let payload = payload.into_inner();
+
+    // Attempt to cancel the deletion and sign the user in
+    let sign_in = UserMutations::restore(payload.username, payload.password, db).await?;
+
+    // Return a successful response with sign-in details
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            status: Status::Ok,
+            message: Some("Account restored".to_string()),
+            code: None,
+            data: sign_in,
+        }),
+    ))
 }
@@ -0,0 +1,296 @@
+use crate::routes::payload::{FormOrJson, QueryForm};
+use crate::routes::task::FilterTasks;
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::entities::calendar_feed as CalendarFeed;
+use database::Db;
+use rocket::form::validate::msg;
+use rocket::http::{ContentType, Header, Status};
+use rocket::response::status::Custom;
+use rocket::serde::json::{from_value, Json};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{delete, get, post, FromForm, Responder};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::calendar_feed::models::calendar_feed::NewCalendarFeed;
+use services::calendar_feed::models::feed::{to_feed, FeedComponents};
+use services::calendar_feed::mutations::calendar_feed::CalendarFeedMutation;
+use services::calendar_feed::queries::calendar_feed::{CalendarFeedQueries, FeedPayload};
+use services::error::{ApiError, FieldError};
+use services::etag::{content_etag, IfNoneMatch, Tagged};
+use services::saved_filter::queries::saved_filter::SavedFilterQueries;
+use utoipa::{IntoParams, ToSchema};
+
+/// How long calendar apps and proxies may reuse a fetched feed without revalidating it.
+const FEED_CACHE_CONTROL: &str = "private, max-age=300";
+
+/// Payload structure for creating a calendar feed.
+#[derive(Serialize, Deserialize, FromForm, ToSchema)]
+pub struct ManageCalendarFeed {
+    /// The name of the feed, shown by the calendar apps.
+    #[field(validate = len(1..=100).or_else(msg!("The name must be between 1 to 100 characters long.")))]
+    pub name: String,
+}
+
+/// Struct representing the filters of a calendar feed, sent in the query string of its URL.
+#[derive(FromForm, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FeedFilter {
+    /// Filter query applied to the tasks, as the `q` of `GET /task` (e.g. `status:open due<30d`).
+    pub q: Option<String>,
+    /// Text the name or the description of the tasks must contain.
+    pub query: Option<String>,
+    /// A saved filter ID, or `today`, `upcoming`, `overdue` or `no-due-date`, whose filters are applied as well.
+    pub view: Option<String>,
+    /// The components listed for each task. Defaults to `all`.
+    #[field(default = FeedComponents::All)]
+    #[param(inline, required = false)]
+    pub components: FeedComponents,
+}
+
+/// Response carrying a calendar feed, cacheable by the calendar apps.
+#[derive(Responder)]
+pub struct FeedResponse {
+    /// The iCalendar object.
+    body: (ContentType, String),
+    /// The `Cache-Control` header.
+    cache_control: Header<'static>,
+}
+
+/// Endpoint for listing the calendar feeds of the current user.
+///
+/// This function handles the HTTP GET request returning the calendar feeds of the user, without their tokens.
+///
+/// # Arguments
+///
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<CalendarFeed::Model>>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/calendar/feeds",
+    tag = "Calendar",
+    responses(
+        (status = 200, description = "Calendar feeds of the current user", body = ResponseRequest<Vec<CalendarFeed::Model>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn get_calendar_feeds(
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Vec<CalendarFeed::Model>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Fetch the calendar feeds of the user
+    let calendar_feeds = CalendarFeedQueries::get_calendar_feeds(user.claims.sub, db).await?;
+
+    // Return a successful response with the calendar feeds
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: calendar_feeds,
+        }),
+    ))
+}
+
+/// Endpoint for creating a calendar feed.
+///
+/// This function handles the HTTP POST request generating the secret token of a read-only feed,
+/// which calendar apps subscribe to at `/calendar/feeds/<token>.ics`. The token is only returned in this response.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageCalendarFeed` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<NewCalendarFeed>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/calendar/feeds",
+    tag = "Calendar",
+    request_body(content(
+        (ManageCalendarFeed = "application/json"),
+        (ManageCalendarFeed = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Calendar feed created successfully", body = ResponseRequest<NewCalendarFeed>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid name", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/create", data = "<form>")]
+pub async fn create_calendar_feed(
+    form: FormOrJson<ManageCalendarFeed>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<NewCalendarFeed> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to create the calendar feed
+    let created = CalendarFeedMutation::create(form.into_inner().name, user.claims.sub, db).await?;
+
+    // Return a successful response with the token, shown only once
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Calendar feed created successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: created,
+        }),
+    ))
+}
+
+/// Endpoint for revoking a calendar feed.
+///
+/// This function handles the HTTP DELETE request deleting the calendar feed identified by `id`,
+/// its URL stops serving the tasks.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the calendar feed.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<u64>`) with the number of deleted calendar feeds and status `200 OK` on success,
+/// `404 Not Found` if the calendar feed is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/calendar/feeds",
+    tag = "Calendar",
+    params(
+        ("id" = i32, Path, description = "ID of the calendar feed")
+    ),
+    responses(
+        (status = 200, description = "Calendar feed revoked successfully", body = ResponseRequest<u64>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Calendar feed not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/delete/<id>")]
+pub async fn delete_calendar_feed(
+    id: i32,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<u64> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to revoke the calendar feed
+    let deleted = CalendarFeedMutation::delete(id, user.claims.sub, db).await?;
+
+    // Return a successful response with the number of rows affected
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Calendar feed revoked successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: deleted.rows_affected,
+        }),
+    ))
+}
+
+/// Endpoint serving a calendar feed.
+///
+/// This function handles the HTTP GET request of a calendar app subscribed to the feed, returning the tasks
+/// of its owner as an iCalendar object. The URL is the credential, so no auth token is needed.
+/// The feed carries an `ETag` computed from its content, for the apps to revalidate it with `If-None-Match`.
+///
+/// # Arguments
+///
+/// * `token` - The token of the feed, optionally followed by `.ics`.
+/// * `filter` - Struct containing the filters of the feed (`FeedFilter`).
+/// * `if_none_match` - The `If-None-Match` header, the `ETag` of the copy held by the app.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A `Tagged<FeedResponse>` with status `200 OK` on success, `304 Not Modified` if the copy of the app is current,
+/// `404 Not Found` if the feed is unknown or revoked, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/calendar/feeds",
+    tag = "Calendar",
+    params(
+        ("token" = String, Path, description = "Token of the calendar feed, optionally followed by `.ics`"),
+        FeedFilter,
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the copy held by the calendar app")
+    ),
+    responses(
+        (status = 200, description = "Tasks of the feed owner", body = String, content_type = "text/calendar",
+            headers(("ETag" = String, description = "Revision of the feed"))),
+        (status = 304, description = "The copy held by the calendar app is current",
+            headers(("ETag" = String, description = "Revision of the feed"))),
+        (status = 404, description = "Calendar feed or view not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid filters", body = ResponseRequest<Option<Vec<FieldError>>>)
+    )
+)]
+#[get("/<token>")]
+pub async fn get_calendar_feed(
+    token: &str,
+    filter: QueryForm<FeedFilter>,
+    if_none_match: IfNoneMatch,
+    conn: Connection<'_, Db>,
+) -> Result<Tagged<FeedResponse>, ApiError> {
+    // Extract database connection
+    let db = conn.into_inner();
+    let filter = filter.into_inner();
+
+    // Find the feed, calendar apps expect the URL to end with the extension
+    let token = token.strip_suffix(".ics").unwrap_or(token);
+    let calendar_feed = CalendarFeedQueries::get_calendar_feed_by_token(token, db).await?;
+    let user_id = calendar_feed.user_id;
+
+    let mut payload = FeedPayload {
+        queries: filter.query.into_iter().collect(),
+        filters: filter.q.into_iter().collect(),
+        user_id,
+    };
+
+    // Apply the stored filters of the view as well
+    if let Some(view) = filter.view {
+        let view = SavedFilterQueries::get_view(&view, user_id, db).await?;
+        let view: FilterTasks = from_value(view.filters).map_err(|_| {
+            ApiError::Internal("The stored view parameters are invalid.".to_string())
+        })?;
+
+        payload.queries.extend(view.query);
+        payload.filters.extend(view.q);
+    }
+
+    // Render the tasks and record that the feed is in use
+    let tasks = CalendarFeedQueries::get_feed_tasks(payload, db).await?;
+    let ical = to_feed(&calendar_feed.name, &tasks, filter.components);
+    CalendarFeedMutation::touch(calendar_feed, db).await?;
+
+    // Return the feed, or `304 Not Modified` if the copy of the app is current
+    Ok(Tagged::conditional(
+        content_etag(ical.as_bytes()),
+        if_none_match,
+        || FeedResponse {
+            body: (
+                ContentType::new("text", "calendar").with_params(("charset", "utf-8")),
+                ical,
+            ),
+            cache_control: Header::new("Cache-Control", FEED_CACHE_CONTROL),
+        },
+    ))
+}
@@ -0,0 +1,232 @@
+use crate::routes::{Response, ResponseRequest};
+use rocket::catch;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::Request;
+use services::error::{ApiError, FieldError, GuardError};
+
+/// Builds the error envelope for a catcher.
+///
+/// The message and field errors cached by the failing guard are used when available,
+/// falling back to the message of `error` otherwise.
+///
+/// # Arguments
+///
+/// * `error` - The error matching the status of the catcher.
+/// * `request` - The request that failed.
+///
+/// # Returns
+///
+/// A custom response (`Response<Option<Vec<FieldError>>>`) with the per-field errors as `data`, if any.
+///
+fn render(error: ApiError, request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    let status = error.status();
+    let cached = GuardError::cached(request);
+
+    Custom(
+        status,
+        Json(ResponseRequest {
+            message: Some(cached.message.clone().unwrap_or(error.to_string())),
+            status,
+            code: Some(error.code().to_string()),
+            data: match cached.fields.is_empty() {
+                true => None,
+                false => Some(cached.fields.clone()),
+            },
+        }),
+    )
+}
+
+/// Catcher for `400 Bad Request`, raised by malformed request bodies.
+#[catch(400)]
+pub fn bad_request(request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    render(
+        ApiError::BadRequest("The request is malformed.".to_string()),
+        request,
+    )
+}
+
+/// Catcher for `401 Unauthorized`, raised by the `JWT` guard.
+#[catch(401)]
+pub fn unauthorized(request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    render(
+        ApiError::Unauthorized("Authentication is required.".to_string()),
+        request,
+    )
+}
+
+/// Catcher for `404 Not Found`, raised when no route matches the request.
+#[catch(404)]
+pub fn not_found(request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    render(
+        ApiError::NotFound(format!("The path {} was not found.", request.uri().path())),
+        request,
+    )
+}
+
+/// Catcher for `422 Unprocessable Entity`, raised by payload and query validation failures.
+#[catch(422)]
+pub fn unprocessable_entity(request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    render(
+        ApiError::Validation("The request payload is invalid.".to_string()),
+        request,
+    )
+}
+
+/// Catcher for `500 Internal Server Error`.
+#[catch(500)]
+pub fn internal_error(request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    render(
+        ApiError::Internal("An internal error occurred.".to_string()),
+        request,
+    )
+}
+
+/// Catcher for any other error status, keeping the envelope consistent.
+#[catch(default)]
+pub fn default_catcher(status: Status, request: &Request<'_>) -> Response<Option<Vec<FieldError>>> {
+    let cached = GuardError::cached(request);
+
+    Custom(
+        status,
+        Json(ResponseRequest {
+            message: cached
+                .message
+                .clone()
+                .or(status.reason().map(str::to_string)),
+            status,
+            code: None,
+            data: None,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::payload::{FormOrJson, QueryForm};
+    use crate::routes::task::{FilterTasks, ManageTodo};
+    use rocket::http::{ContentType, Header};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Value;
+    use rocket::{catchers, get, post, routes};
+    use services::auth::jwt::JWT;
+
+    #[post("/payload", data = "<payload>")]
+    fn payload(payload: FormOrJson<ManageTodo>) -> String {
+        payload.into_inner().name
+    }
+
+    #[get("/tasks")]
+    fn tasks(filter: QueryForm<FilterTasks>) -> String {
+        format!("{:?}", filter.into_inner().page)
+    }
+
+    #[get("/private")]
+    fn private(user: JWT) -> String {
+        user.claims.sub.to_string()
+    }
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .mount("/", routes![payload, tasks, private])
+            .register(
+                "/",
+                catchers![
+                    bad_request,
+                    unauthorized,
+                    not_found,
+                    unprocessable_entity,
+                    internal_error,
+                    default_catcher
+                ],
+            );
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    /// Returns the status and the JSON body of a response.
+    fn body(response: rocket::local::blocking::LocalResponse<'_>) -> (Status, Value) {
+        let status = response.status();
+        (status, response.into_json().unwrap())
+    }
+
+    #[test]
+    fn malformed_bodies_get_a_400_envelope() {
+        let client = client();
+        let (status, body) = body(
+            client
+                .post("/payload")
+                .header(ContentType::JSON)
+                .body("{\"name\": ")
+                .dispatch(),
+        );
+
+        assert_eq!(status, Status::BadRequest);
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["code"], "bad_request");
+        assert!(body["message"].as_str().is_some_and(|m| !m.is_empty()));
+    }
+
+    #[test]
+    fn missing_tokens_get_a_401_envelope() {
+        let (status, body) = body(client().get("/private").dispatch());
+
+        assert_eq!(status, Status::Unauthorized);
+        assert_eq!(body["code"], "unauthorized");
+        assert_eq!(body["message"], "No auth token provided");
+        assert_eq!(body["data"], Value::Null);
+    }
+
+    #[test]
+    fn unknown_paths_get_a_404_envelope() {
+        let (status, body) = body(client().get("/nowhere").dispatch());
+
+        assert_eq!(status, Status::NotFound);
+        assert_eq!(body["code"], "not_found");
+        assert_eq!(body["message"], "The path /nowhere was not found.");
+    }
+
+    #[test]
+    fn invalid_payloads_get_a_422_envelope_with_their_fields() {
+        let client = client();
+        let (status, body) = body(
+            client
+                .post("/payload")
+                .header(ContentType::JSON)
+                .body(r#"{"name": "a", "description": "Two litres"}"#)
+                .dispatch(),
+        );
+
+        assert_eq!(status, Status::UnprocessableEntity);
+        assert_eq!(body["code"], "validation_error");
+        assert_eq!(body["data"][0]["field"], "name");
+        assert_eq!(body["message"], body["data"][0]["message"]);
+    }
+
+    #[test]
+    fn invalid_queries_get_a_422_envelope_with_their_fields() {
+        let client = client();
+
+        for (query, field, message) in [
+            ("page=0", "page", "The page number must be greater than 0."),
+            ("size=abc", "size", "The size must be a whole number."),
+        ] {
+            let (status, body) = body(
+                client
+                    .get(format!("/tasks?{query}"))
+                    .header(Header::new("Accept", "application/json"))
+                    .dispatch(),
+            );
+
+            assert_eq!(status, Status::UnprocessableEntity, "{query}");
+            assert_eq!(body["code"], "validation_error");
+            assert_eq!(body["message"], message);
+            assert_eq!(body["data"][0]["field"], field);
+        }
+
+        let response = client.get("/tasks?size=5").dispatch();
+        assert_eq!(response.into_string().unwrap(), "QueryInt(Some(1))");
+    }
+}
@@ -0,0 +1,82 @@
+use crate::routes::ResponseRequest;
+use database::Db;
+use rocket::get;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::json;
+use rocket::tokio::select;
+use rocket::Shutdown;
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
+use services::events::{LastEventId, TaskWatch};
+use services::task::models::change::TaskChange;
+
+/// Renders a task change as a Server-Sent Event.
+///
+/// The event is named after the kind of change, carries the task (only its `id` once deleted)
+/// and is identified by its position in the change feed.
+fn task_event(change: &TaskChange) -> Event {
+    let data = match &change.task {
+        Some(task) => json!(task),
+        None => json!({ "id": change.task_id }),
+    };
+
+    Event::json(&data)
+        .event(change.kind.event_name())
+        .id(change.seq.to_string())
+}
+
+/// Endpoint streaming the changes of the tasks of the authenticated user.
+///
+/// This function handles the HTTP GET request opening a Server-Sent Events stream, pushing a
+/// `task.created`, `task.updated` or `task.deleted` event whenever one of the user's tasks changes.
+/// A client reconnecting with `Last-Event-ID` first receives the changes it missed, in order.
+///
+/// # Arguments
+///
+/// * `user` - JWT token representing the authenticated user.
+/// * `last_event_id` - The ID of the last event received before reconnecting, if any.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+/// * `shutdown` - Ends the stream when the server shuts down.
+///
+/// # Returns
+///
+/// The event stream with status `200 OK` on success, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "Events",
+    params(
+        ("Last-Event-ID" = Option<i64>, Header, description = "ID of the last event received, the missed changes are replayed first")
+    ),
+    responses(
+        (status = 200, description = "Stream of `task.created`, `task.updated` and `task.deleted` events", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn events(
+    user: JWT,
+    last_event_id: LastEventId,
+    conn: Connection<'_, Db>,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![], ApiError> {
+    // The stream outlives the request, so it owns a handle to the pool
+    let db = conn.into_inner().clone();
+    let mut watch = TaskWatch::start(user.claims.sub, last_event_id.0, db).await?;
+
+    Ok(EventStream! {
+        loop {
+            select! {
+                change = watch.next() => match change {
+                    Ok(change) => yield task_event(&change),
+                    // The client reconnects and resumes from the last event it received
+                    Err(_) => return,
+                },
+                _ = &mut shutdown => return,
+            }
+        }
+    })
+}
@@ -0,0 +1,95 @@
+use crate::graphql::{ApiSchema, HistoryLoader, UserLoader, Viewer};
+use crate::routes::ResponseRequest;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::http::GraphiQLSource;
+use database::Db;
+use rocket::response::content::RawHtml;
+use rocket::serde::json::Json;
+use rocket::{get, post, State};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::FieldError;
+use services::request_id::RequestId;
+
+/// Endpoint running a GraphQL query or mutation.
+///
+/// This function handles the HTTP POST request executing a GraphQL request against the task and
+/// user schema, as the authenticated user. The owners and histories of the tasks are batched into
+/// one query each, and queries deeper than 8 levels or too complex are rejected.
+///
+/// # Arguments
+///
+/// * `request` - JSON payload containing the GraphQL `query`, `variables` and `operationName`.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `schema` - The GraphQL schema, managed by Rocket.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// The GraphQL response with status `200 OK`, errors being listed in its `errors` member.
+///
+#[utoipa::path(
+    post,
+    path = "/graphql",
+    tag = "GraphQL",
+    request_body(content = Object, description = "GraphQL request, with `query`, `variables` and `operationName`", content_type = "application/json"),
+    responses(
+        (status = 200, description = "GraphQL response, with `data` and `errors`", body = Object),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/", format = "json", data = "<request>")]
+pub async fn graphql_request(
+    request: Json<async_graphql::Request>,
+    user: JWT,
+    request_id: RequestId,
+    schema: &State<ApiSchema>,
+    conn: Connection<'_, Db>,
+) -> Json<async_graphql::Response> {
+    // The resolvers outlive the borrowed connection, so they get a handle to the pool
+    let db = conn.into_inner().clone();
+    let user_id = user.claims.sub;
+
+    // Data loaders are per request, so their cache never leaks between users
+    let request = request
+        .into_inner()
+        .data(Viewer {
+            user_id,
+            request_id: request_id.0,
+        })
+        .data(DataLoader::new(
+            UserLoader { db: db.clone() },
+            rocket::tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            HistoryLoader {
+                db: db.clone(),
+                user_id,
+            },
+            rocket::tokio::spawn,
+        ))
+        .data(db);
+
+    Json(schema.execute(request).await)
+}
+
+/// Endpoint serving GraphiQL, an in-browser editor for the GraphQL endpoint.
+///
+/// # Returns
+///
+/// The GraphiQL page with status `200 OK`.
+///
+#[utoipa::path(
+    get,
+    path = "/graphql",
+    tag = "GraphQL",
+    responses(
+        (status = 200, description = "GraphiQL page", content_type = "text/html", body = String)
+    )
+)]
+#[get("/")]
+pub async fn graphiql() -> RawHtml<String> {
+    RawHtml(GraphiQLSource::build().endpoint("/graphql").finish())
+}
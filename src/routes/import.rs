@@ -0,0 +1,87 @@
+use crate::routes::payload::{QueryForm, TextFile};
+use crate::routes::task::import_rows;
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::Db;
+use rocket::{post, FromForm};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
+use services::request_id::RequestId;
+use services::task::models::import::ImportReport;
+use services::task::models::provider::{self, ImportProvider};
+use utoipa::IntoParams;
+
+/// Struct representing the query parameters of an import from another service.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProviderImportOptions {
+    /// Whether to only check the file, without importing anything. Defaults to `false`.
+    #[field(default = false)]
+    #[param(required = false)]
+    pub dry_run: bool,
+}
+
+/// Endpoint for importing the tasks exported from another service.
+///
+/// This function handles the HTTP POST request reading the export file of Todoist, Trello or Microsoft To Do
+/// sent as the request body. Projects, labels and subtasks are kept in the description of the tasks,
+/// which are then imported like `POST /task/import` does.
+///
+/// # Arguments
+///
+/// * `provider` - The service the file was exported from: `todoist`, `trello` or `microsoft-todo`.
+/// * `options` - Struct containing whether the import is a dry run (`ProviderImportOptions`).
+/// * `file` - The content of the file.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - The ID of the request, recorded in the history of the created tasks.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<ImportReport>`) with status `200 OK` on success, `404 Not Found` if the
+/// provider is unknown, `422 Unprocessable Entity` with the report if any row is invalid, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/import",
+    tag = "Import",
+    params(
+        ("provider" = String, Path, description = "Service the file was exported from: `todoist`, `trello` or `microsoft-todo`"),
+        ProviderImportOptions
+    ),
+    request_body(content(
+        (String = "application/json"),
+        (String = "text/csv")
+    ), description = "Todoist: the JSON of a full sync of its Sync API, or the CSV export of a project. \
+        Trello: the JSON export of a board. Microsoft To Do: the response of `GET /me/todo/lists` of the \
+        Microsoft Graph API, each list holding its `tasks` with their `checklistItems`."),
+    responses(
+        (status = 200, description = "Tasks imported successfully", body = ResponseRequest<ImportReport>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Unknown provider", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 413, description = "The file is too large", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "The file cannot be read, or has invalid rows", body = ResponseRequest<ImportReport>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/<provider>", data = "<file>")]
+pub async fn import_from_provider(
+    provider: &str,
+    options: QueryForm<ProviderImportOptions>,
+    file: TextFile,
+    user: JWT,
+    request_id: RequestId,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<ImportReport> {
+    // Extract database connection and query parameters
+    let db = conn.into_inner();
+    let dry_run = options.into_inner().dry_run;
+
+    // Read the tasks of the export
+    let provider = provider
+        .parse::<ImportProvider>()
+        .map_err(ApiError::NotFound)?;
+    let rows = provider::parse(provider, &file.0).map_err(ApiError::Validation)?;
+
+    // Attempt to import the tasks
+    import_rows(rows, dry_run, user.claims.sub, request_id, db).await
+}
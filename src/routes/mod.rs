@@ -1,17 +1,28 @@
-use rocket::http::Status;
 use rocket::response::status::Custom;
 use rocket::serde::json::Json;
-use rocket::serde::{Deserialize, Serialize};
+use services::error::ApiError;
+use services::etag::Tagged;
 
+pub use services::response::ResponseRequest;
+
+pub mod app_password;
 pub mod auth;
+pub mod calendar;
+pub mod catchers;
+pub mod events;
+pub mod graphql;
+pub mod import;
+pub mod payload;
 pub mod ping;
+pub mod sync;
 pub mod task;
-
-#[derive(Serialize, Deserialize)]
-pub struct ResponseRequest<T> {
-    message: Option<String>,
-    status: Status,
-    data: T,
-}
+pub mod view;
+pub mod webhook;
 
 pub type Response<T> = Custom<Json<ResponseRequest<T>>>;
+
+/// Result returned by the API routes, rendering `ApiError` inside the same envelope.
+pub type ApiResponse<T> = Result<Response<T>, ApiError>;
+
+/// Result returned by the API routes sending the `ETag` of the resource.
+pub type TaggedResponse<T> = Result<Tagged<Response<T>>, ApiError>;
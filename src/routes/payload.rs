@@ -0,0 +1,382 @@
+use rocket::data::{self, Data, FromData, Limits, ToByteUnit};
+use rocket::form::{self, Error, Errors, Form, FromForm, FromFormField, ValueField};
+use rocket::http::Status;
+use rocket::outcome::Outcome;
+use rocket::request::{self, FromRequest};
+use rocket::serde::json::{self, Value};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::Request;
+use services::error::{FieldError, GuardError};
+
+/// Data guard accepting a request body either as a form or as JSON.
+///
+/// Form bodies (`application/x-www-form-urlencoded` and `multipart/form-data`) are parsed
+/// exactly like `Form<T>`. JSON bodies are flattened into form fields and pushed through the
+/// same `FromForm` implementation, so the `#[field(validate = ...)]` rules and their messages
+/// apply to both content types.
+///
+pub struct FormOrJson<T>(pub T);
+
+impl<T> FormOrJson<T> {
+    /// Consumes the guard, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Form fields flattened from a JSON body, cached on the request so they live for `'r`.
+struct JsonFields(Vec<(String, String)>);
+
+/// Flattens a JSON value into `(name, value)` form fields.
+///
+/// Nested objects use the dotted form syntax (`parent.child`), array items repeat the field
+/// name and `null` members are skipped so the field defaults apply.
+///
+/// # Arguments
+///
+/// * `name` - Name of the field holding `value`, empty for the root object.
+/// * `value` - The JSON value to flatten.
+/// * `fields` - The list the flattened fields are pushed to.
+///
+fn flatten_json(name: String, value: Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::Object(members) => {
+            for (key, member) in members {
+                let key = match name.is_empty() {
+                    true => key,
+                    false => format!("{}.{}", name, key),
+                };
+
+                flatten_json(key, member, fields);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                flatten_json(name.clone(), item, fields);
+            }
+        }
+        Value::String(value) => fields.push((name, value)),
+        value => fields.push((name, value.to_string())),
+    }
+}
+
+/// Converts form errors into the `FieldError` list rendered in the envelope.
+fn field_errors(errors: &Errors<'_>) -> Vec<FieldError> {
+    errors
+        .iter()
+        .map(|e| FieldError {
+            field: e.name.as_ref().map(|name| name.to_string()),
+            message: e.to_string(),
+        })
+        .collect()
+}
+
+/// Parses a JSON object through the `FromForm` implementation of `T`, like a JSON body of `FormOrJson<T>`.
+///
+/// Used for values nested in a larger JSON document, such as the items of a batch.
+///
+/// # Arguments
+///
+/// * `value` - The JSON object to parse.
+///
+/// # Returns
+///
+/// The validated value, or the errors of the invalid fields.
+///
+pub fn from_json_value<T>(value: Value) -> Result<T, Vec<FieldError>>
+where
+    T: for<'v> FromForm<'v>,
+{
+    if !value.is_object() {
+        return Err(vec![FieldError {
+            field: None,
+            message: "The value must be a JSON object.".to_string(),
+        }]);
+    }
+
+    let mut fields = Vec::new();
+    flatten_json(String::new(), value, &mut fields);
+
+    Form::<T>::parse_iter(
+        fields
+            .iter()
+            .map(|(name, value)| ValueField::from((name.as_str(), value.as_str()))),
+    )
+    .map_err(|errors| field_errors(&errors))
+}
+
+/// Caches form errors on the request so the catchers can render them.
+///
+/// # Arguments
+///
+/// * `req` - The incoming Rocket request.
+/// * `errors` - The form errors of the payload or the query.
+///
+fn cache_errors(req: &Request<'_>, errors: &Errors<'_>) {
+    let fields = field_errors(errors);
+
+    let message = match fields.first() {
+        Some(field) => field.message.clone(),
+        None => "The request payload is invalid.".to_string(),
+    };
+
+    GuardError::cache(req, message, fields);
+}
+
+/// Fails the data guard, caching the errors so the catchers can render them.
+///
+/// # Arguments
+///
+/// * `req` - The incoming Rocket request.
+/// * `status` - The status of the failure.
+/// * `errors` - The form errors of the payload.
+///
+/// # Returns
+///
+/// An `Outcome::Error` with the provided status and errors.
+///
+fn fail<'r, T>(
+    req: &'r Request<'_>,
+    status: Status,
+    errors: Errors<'r>,
+) -> data::Outcome<'r, T, Errors<'r>> {
+    cache_errors(req, &errors);
+    Outcome::Error((status, errors))
+}
+
+#[rocket::async_trait]
+impl<'r, T: FromForm<'r>> FromData<'r> for FormOrJson<T> {
+    type Error = Errors<'r>;
+
+    /// Parses the request body according to its `Content-Type`.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The incoming Rocket request.
+    /// * `data` - The request body.
+    ///
+    /// # Returns
+    ///
+    /// An `Outcome` containing the validated value or the form errors.
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        // Anything that is not JSON is handled by the regular form guard
+        if !req.content_type().is_some_and(|c| c.is_json()) {
+            return match Form::<T>::from_data(req, data).await {
+                Outcome::Success(form) => Outcome::Success(FormOrJson(form.into_inner())),
+                Outcome::Error((status, errors)) => fail(req, status, errors),
+                Outcome::Forward(forward) => Outcome::Forward(forward),
+            };
+        }
+
+        // Read the body within the configured JSON limit
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_string().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                let error = Error::validation(format!(
+                    "The request body is larger than {}.",
+                    limit.as_u64().bytes()
+                ));
+                return fail(req, Status::PayloadTooLarge, error.into());
+            }
+            Err(e) => return fail(req, Status::BadRequest, Error::custom(e).into()),
+        };
+
+        // Only JSON objects can be mapped to form fields
+        let fields = match json::from_str::<Value>(&body) {
+            Ok(value @ Value::Object(_)) => {
+                let mut fields = Vec::new();
+                flatten_json(String::new(), value, &mut fields);
+                fields
+            }
+            Ok(_) => {
+                let error = Error::validation("The request body must be a JSON object.");
+                return fail(req, Status::BadRequest, error.into());
+            }
+            Err(e) => return fail(req, Status::BadRequest, Error::custom(e).into()),
+        };
+
+        // Run the fields through the same validation as a form submission
+        let fields = &req.local_cache(|| JsonFields(fields)).0;
+        let value = Form::<T>::parse_iter(
+            fields
+                .iter()
+                .map(|(name, value)| ValueField::from((name.as_str(), value.as_str()))),
+        );
+
+        match value {
+            Ok(value) => Outcome::Success(FormOrJson(value)),
+            Err(e) => fail(req, e.status(), e),
+        }
+    }
+}
+
+/// Request guard parsing the query string as `T`, like a `?<param..>` route parameter.
+///
+/// A `?<param..>` parameter failing to parse leaves the catcher without its errors, while this
+/// guard caches them like `FormOrJson`, so the `422` envelope names the invalid parameters.
+///
+pub struct QueryForm<T>(pub T);
+
+impl<T> QueryForm<T> {
+    /// Consumes the guard, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: FromForm<'r>> FromRequest<'r> for QueryForm<T> {
+    type Error = Errors<'r>;
+
+    /// Parses the query string of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The incoming Rocket request.
+    ///
+    /// # Returns
+    ///
+    /// An `Outcome` containing the validated value or the form errors.
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match Form::<T>::parse_iter(req.query_fields()) {
+            Ok(value) => Outcome::Success(QueryForm(value)),
+            Err(errors) => {
+                cache_errors(req, &errors);
+                Outcome::Error((errors.status(), errors))
+            }
+        }
+    }
+}
+
+/// Optional integer of a query string, such as a page number.
+///
+/// A malformed `Option<i32>` is parsed as `None`, so `size=abc` would fall back to the default size.
+/// This field rejects it instead, and is serialized as the `Option<i32>` it wraps.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct QueryInt(pub Option<i32>);
+
+impl<'v> FromFormField<'v> for QueryInt {
+    fn from_value(field: ValueField<'v>) -> form::Result<'v, Self> {
+        match field.value.trim().parse() {
+            Ok(value) => Ok(QueryInt(Some(value))),
+            Err(_) => Err(Error::validation(format!(
+                "The {} must be a whole number.",
+                field.name.source().rsplit('.').next().unwrap_or_default()
+            )))?,
+        }
+    }
+
+    fn default() -> Option<Self> {
+        Some(QueryInt(None))
+    }
+}
+
+/// Data guard reading the request body as a text file, such as an imported file.
+///
+/// The body must be valid UTF-8 and fit in the `file` limit (1 MiB by default).
+///
+pub struct TextFile(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for TextFile {
+    type Error = Errors<'r>;
+
+    /// Reads the request body within the configured file limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The incoming Rocket request.
+    /// * `data` - The request body.
+    ///
+    /// # Returns
+    ///
+    /// An `Outcome` containing the text of the body, or the error.
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = req.limits().get("file").unwrap_or(Limits::FILE);
+
+        match data.open(limit).into_string().await {
+            Ok(body) if body.is_complete() => Outcome::Success(TextFile(body.into_inner())),
+            Ok(_) => {
+                let error = Error::validation(format!(
+                    "The request body is larger than {}.",
+                    limit.as_u64().bytes()
+                ));
+                fail(req, Status::PayloadTooLarge, error.into())
+            }
+            Err(e) => fail(req, Status::BadRequest, Error::custom(e).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::task::ManageTodo;
+    use rocket::serde::json::json;
+
+    fn flatten(value: Value) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        flatten_json(String::new(), value, &mut fields);
+        fields
+    }
+
+    #[test]
+    fn json_members_become_form_fields() {
+        let fields = flatten(json!({
+            "name": "Buy milk",
+            "is_active": true,
+            "size": 3,
+            "due_date": null,
+            "filters": { "q": "status:open", "page": 2 },
+            "tags": ["home", "shop"]
+        }));
+
+        assert_eq!(
+            fields,
+            [
+                ("filters.page", "2"),
+                ("filters.q", "status:open"),
+                ("is_active", "true"),
+                ("name", "Buy milk"),
+                ("size", "3"),
+                ("tags", "home"),
+                ("tags", "shop"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+
+    #[test]
+    fn json_objects_are_validated_like_forms() {
+        let Ok(todo) = from_json_value::<ManageTodo>(json!({
+            "name": "Buy milk",
+            "description": "Two litres",
+            "due_date": null
+        })) else {
+            panic!("a valid todo was rejected");
+        };
+        assert_eq!(todo.name, "Buy milk");
+        assert!(!todo.is_active);
+        assert_eq!(todo.due_date, None);
+
+        let errors =
+            from_json_value::<ManageTodo>(json!({ "name": "a", "description": "Two litres" }))
+                .err()
+                .expect("a too short name");
+        assert_eq!(errors[0].field.as_deref(), Some("name"));
+    }
+
+    #[test]
+    fn only_json_objects_are_accepted() {
+        let errors = from_json_value::<ManageTodo>(json!(["Buy milk"]))
+            .err()
+            .expect("an array body");
+
+        assert_eq!(errors[0].field, None);
+        assert_eq!(errors[0].message, "The value must be a JSON object.");
+    }
+}
@@ -7,11 +7,12 @@ use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
 use sea_orm::EntityTrait;
 use sea_orm_rocket::Connection;
+use utoipa::ToSchema;
 
 use crate::routes::ResponseRequest;
 
 /// Struct representing the response data for the ping API endpoint.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct PingApi {
     /// Indicates the status of the database connection (`true` for successful ping, `false` otherwise).
     db_status: bool,
@@ -34,6 +35,10 @@ pub struct PingApi {
 ///
 /// A JSON response containing `ResponseRequest<PingApi>` with status `200 OK`.
 ///
+#[utoipa::path(
+    tag = "Misc",
+    responses((status = 200, description = "API status", body = ResponseRequest<PingApi>))
+)]
 #[get("/")]
 pub async fn ping_route(conn: Connection<'_, Db>) -> Json<ResponseRequest<PingApi>> {
     let db = conn.into_inner();
@@ -52,6 +57,7 @@ pub async fn ping_route(conn: Connection<'_, Db>) -> Json<ResponseRequest<PingAp
     Json(ResponseRequest {
         message: None,
         status: Status::Ok,
+        code: None,
         data: PingApi {
             db_status: db_ping.is_ok(),
             tasks_total: total_tasks.unwrap().len(),
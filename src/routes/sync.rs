@@ -0,0 +1,250 @@
+use crate::routes::payload::{from_json_value, QueryForm, QueryInt};
+use crate::routes::task::{validate_min_params, ManageTodo};
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::Db;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::{Json, Value};
+use rocket::serde::Deserialize;
+use rocket::{get, post, FromForm};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
+use services::request_id::RequestId;
+use services::sync::models::sync::{SyncChanges, SyncResult};
+use services::sync::mutations::sync::{version_precondition, SyncMutation, SyncOperation};
+use services::sync::queries::sync::SyncQueries;
+use utoipa::{IntoParams, ToSchema};
+
+/// Maximum number of changes returned by a single `GET /sync`.
+const MAX_SYNC_LIMIT: u64 = 500;
+
+/// Maximum number of client changes accepted by a single `POST /sync`.
+const MAX_SYNC_BATCH: usize = 100;
+
+/// Struct representing a change made by the client while offline.
+///
+/// The `op` member selects the kind of change. `version` is the version of the task the client
+/// based its change on: when the task changed on the server since, the change is reported as a conflict.
+///
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ClientChange {
+    /// Create a task.
+    Create {
+        /// Identifier of the task on the client, echoed back in the result.
+        client_id: Option<String>,
+        /// The fields of the task, validated like `POST /task/create`.
+        #[schema(value_type = ManageTodo)]
+        task: Value,
+    },
+    /// Replace the fields of a task.
+    Update {
+        /// The ID of the task.
+        id: i32,
+        /// The version of the task the change is based on, skips the conflict check when missing.
+        version: Option<i32>,
+        /// The fields of the task, validated like `PATCH /task/update/<id>`.
+        #[schema(value_type = ManageTodo)]
+        task: Value,
+    },
+    /// Delete a task.
+    Delete {
+        /// The ID of the task.
+        id: i32,
+        /// The version of the task the change is based on, skips the conflict check when missing.
+        version: Option<i32>,
+    },
+}
+
+/// Struct representing a batch of changes made by the client while offline.
+#[derive(Deserialize, ToSchema)]
+pub struct SyncBatch {
+    /// The changes, applied in order.
+    pub changes: Vec<ClientChange>,
+}
+
+/// Struct representing the query parameters of a sync.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncParams {
+    /// Token returned by the previous sync, every task is returned when missing.
+    pub since: Option<String>,
+    /// Maximum number of changes to return, capped at 500. Defaults to `100`.
+    #[field(default = QueryInt(Some(100)), validate = validate_min_params(String::from("limit")))]
+    #[param(value_type = Option<i32>)]
+    pub limit: QueryInt,
+}
+
+/// Endpoint for fetching the task changes since the last sync.
+///
+/// This function handles the HTTP GET request returning the tasks created, updated and deleted
+/// since the token returned by the previous sync, in the order they changed. Without a token
+/// every task is returned as created.
+///
+/// # Arguments
+///
+/// * `params` - Struct containing the sync token and the limit (`SyncParams`).
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<SyncChanges>`) with status `200 OK` on success,
+/// `422 Unprocessable Entity` if the token is invalid or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/sync",
+    tag = "Sync",
+    params(SyncParams),
+    responses(
+        (status = 200, description = "Changes since the token", body = ResponseRequest<SyncChanges>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid sync token or limit", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn get_sync(
+    params: QueryForm<SyncParams>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<SyncChanges> {
+    // Extract database connection and query parameters
+    let db = conn.into_inner();
+    let params = params.into_inner();
+
+    // Fetch the changes since the token
+    let changes = SyncQueries::get_changes(
+        params.since.as_deref(),
+        (params.limit.0.unwrap_or(100) as u64).min(MAX_SYNC_LIMIT),
+        user.claims.sub,
+        db,
+    )
+    .await?;
+
+    // Return a successful response with the changes
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: changes,
+        }),
+    ))
+}
+
+/// Endpoint for uploading the changes made by the client while offline.
+///
+/// This function handles the HTTP POST request applying a batch of client changes in order.
+/// Each change succeeds or fails on its own, and its result tells whether it was applied,
+/// conflicts with a newer server copy, targets a missing task or is invalid.
+///
+/// # Arguments
+///
+/// * `batch` - JSON payload containing the `SyncBatch`.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<SyncResult>>`) with the result of every change and status `200 OK`,
+/// `422 Unprocessable Entity` if the batch is too large or an `ApiError` on failure.
+///
+#[utoipa::path(
+    post,
+    path = "/sync",
+    tag = "Sync",
+    request_body(content = SyncBatch, content_type = "application/json"),
+    responses(
+        (status = 200, description = "Result of every change, in the order of the batch", body = ResponseRequest<Vec<SyncResult>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid or too large batch", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/", format = "json", data = "<batch>")]
+pub async fn post_sync(
+    batch: Json<SyncBatch>,
+    user: JWT,
+    request_id: RequestId,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Vec<SyncResult>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Extract payload data
+    let batch = batch.into_inner();
+
+    if batch.changes.len() > MAX_SYNC_BATCH {
+        return Err(ApiError::Validation(format!(
+            "A sync batch cannot hold more than {} changes.",
+            MAX_SYNC_BATCH
+        )));
+    }
+
+    let user_id = user.claims.sub;
+    let mut results = Vec::with_capacity(batch.changes.len());
+
+    // Apply the changes in order, an invalid change does not stop the next ones
+    for (index, change) in batch.changes.into_iter().enumerate() {
+        let (client_id, operation) = match change {
+            ClientChange::Create { client_id, task } => match from_json_value::<ManageTodo>(task) {
+                Ok(task) => (
+                    client_id,
+                    SyncOperation::Create(task.into_payload(user_id, request_id.clone(), None)),
+                ),
+                Err(errors) => {
+                    results.push(SyncResult::invalid(index, client_id, None, errors));
+                    continue;
+                }
+            },
+            ClientChange::Update { id, version, task } => {
+                match from_json_value::<ManageTodo>(task) {
+                    Ok(task) => (
+                        None,
+                        SyncOperation::Update(
+                            id,
+                            task.into_payload(
+                                user_id,
+                                request_id.clone(),
+                                version_precondition(version),
+                            ),
+                        ),
+                    ),
+                    Err(errors) => {
+                        results.push(SyncResult::invalid(index, None, Some(id), errors));
+                        continue;
+                    }
+                }
+            }
+            ClientChange::Delete { id, version } => (None, SyncOperation::Delete { id, version }),
+        };
+
+        results.push(
+            SyncMutation::apply(
+                index,
+                client_id,
+                operation,
+                user_id,
+                Some(request_id.0.clone()),
+                db,
+            )
+            .await?,
+        );
+    }
+
+    // Return a successful response with the result of every change
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: results,
+        }),
+    ))
+}
@@ -1,119 +1,437 @@
-use crate::routes::{Response, ResponseRequest};
+use crate::routes::payload::{from_json_value, FormOrJson, QueryForm, QueryInt, TextFile};
+use crate::routes::{ApiResponse, Response, ResponseRequest, TaggedResponse};
+use chrono::{DateTime, NaiveDate, Utc};
 use database::entities::task as Task;
+use database::entities::task_event as TaskEvent;
 use database::Db;
-use rocket::form::{Error, Form};
-use rocket::http::Status;
+use rocket::form::Error;
+use rocket::http::{ContentType, Header, Status};
 use rocket::response::status::Custom;
-use rocket::serde::json::Json;
-use rocket::serde::{Deserialize, Serialize};
-use rocket::{delete, form, get, patch, post, FromForm};
+use rocket::response::stream::TextStream;
+use rocket::serde::json::{to_string, to_value, Json};
+use rocket::serde::{Deserialize, Deserializer, Serialize};
+use rocket::{delete, form, get, patch, post, Either, FromForm, Responder};
+use sea_orm::DatabaseConnection;
 use sea_orm_rocket::Connection;
 use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
+use services::etag::{etag, EntityTags, IfMatch, IfNoneMatch, Tagged};
+use services::idempotency::models::idempotency_key::{IdempotencyKey, StoredResponse};
+use services::idempotency::mutations::idempotency_key::IdempotencyMutation;
+use services::request_id::RequestId;
+use services::task::models::import::{ImportCandidate, ImportReport};
 use services::task::models::task::TaskModel;
-use services::task::mutations::task::{TaskMutation, TaskPayload};
-use services::task::queries::task::{GetAllTasks, PaginationPayload, TaskQueries};
+use services::task::models::transfer::{self, ImportRow, TaskFormat, TaskWriter};
+use services::task::mutations::import::TaskImportMutation;
+use services::task::mutations::task::{TaskMutation, TaskPatchPayload, TaskPayload};
+use services::task::queries::event::TaskEventQueries;
+use services::task::queries::task::{
+    CursorPayload, PaginationPayload, SearchPayload, SearchTasks, TaskList, TaskQueries, TaskSort,
+};
+use utoipa::{IntoParams, ToSchema};
+
+/// Number of tasks read at once while streaming an export.
+const EXPORT_PAGE: u64 = 500;
 
 /// Struct representing the payload for managing a TODO task.
 ///
 /// This struct is used for handling form data and validating the input for creating or updating a task.
 ///
-#[derive(Deserialize, Serialize, FromForm)]
+#[derive(Deserialize, Serialize, FromForm, ToSchema)]
 pub struct ManageTodo {
     /// The name of the task.
-    #[field(validate = len(2..=20).or_else(msg!("The name must be between 2 to 10 characters long.")))]
+    #[field(validate = validate_name())]
     pub name: String,
     /// The description of the task.
-    #[field(validate = len(5..=200).or_else(msg!("The description must be at least 5 characters long.")))]
+    #[field(validate = validate_description())]
     pub description: String,
     /// Flag indicating whether the task is active or not. Defaults to `false`.
     #[field(default = false)]
     pub is_active: bool,
+    /// The date the task is due (`YYYY-MM-DD`), if any.
+    #[field(validate = validate_due_date())]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub due_date: Option<String>,
+    /// The priority of the task (`low`, `normal`, `high` or `urgent`). Defaults to `normal`.
+    #[field(default = Task::TaskPriority::Normal)]
+    #[serde(default)]
+    pub priority: Task::TaskPriority,
+    /// The tags of the task, stored in lowercase without duplicates.
+    #[field(default = Vec::new(), validate = validate_tags())]
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The project of the task, if any.
+    #[field(validate = validate_project())]
+    pub project: Option<String>,
+}
+
+impl ManageTodo {
+    /// Converts the validated form into the payload of a task write.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the user writing the task.
+    /// * `request_id` - The ID of the request, recorded in the task history.
+    /// * `if_match` - The `If-Match` precondition of the request, if any.
+    ///
+    /// # Returns
+    ///
+    /// The `TaskPayload`, with the name and description trimmed.
+    ///
+    pub fn into_payload(
+        self,
+        user_id: i32,
+        request_id: RequestId,
+        if_match: Option<EntityTags>,
+    ) -> TaskPayload {
+        TaskPayload {
+            name: self.name.trim().to_owned(),
+            description: self.description.trim().to_owned(),
+            is_active: self.is_active,
+            due_date: self.due_date.as_deref().and_then(parse_due_date),
+            priority: Some(self.priority),
+            tags: Some(normalize_tags(self.tags)),
+            project: Some(normalize_project(self.project)),
+            user_id,
+            request_id: Some(request_id.0),
+            if_match,
+        }
+    }
+}
+
+/// Parses a due date sent as `YYYY-MM-DD`.
+///
+/// # Arguments
+///
+/// * `value` - The due date as sent by the client.
+///
+/// # Returns
+///
+/// The parsed date, or `None` if the value is not a valid date.
+///
+fn parse_due_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+/// Trims and lowercases the tags of a task, dropping the empty and repeated ones.
+///
+/// # Arguments
+///
+/// * `tags` - The tags as sent by the client.
+///
+/// # Returns
+///
+/// The tags to store, in the order they were first sent.
+///
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized = Vec::<String>::new();
+
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    normalized
+}
+
+/// Trims the project of a task, an empty project meaning none.
+fn normalize_project(project: Option<String>) -> Option<String> {
+    project
+        .map(|project| project.trim().to_owned())
+        .filter(|project| !project.is_empty())
+}
+
+/// Validates the tags of a task: at most 20 tags of at most 30 characters each.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated.
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_tags<'v>(value: &[String]) -> form::Result<'v, ()> {
+    if value.len() > 20 {
+        Err(Error::validation("A task can have at most 20 tags."))?;
+    }
+
+    if value.iter().any(|tag| tag.trim().chars().count() > 30) {
+        Err(Error::validation(
+            "The tags must be at most 30 characters long.",
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Validates the length of the project of a task, if any.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated (`Option<String>`).
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_project<'v>(value: &Option<String>) -> form::Result<'v, ()> {
+    if value
+        .as_deref()
+        .is_some_and(|project| project.trim().chars().count() > 50)
+    {
+        Err(Error::validation(
+            "The project must be at most 50 characters long.",
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Validates the length of the name of a task, as sent by the client.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated.
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_name<'v>(value: &str) -> form::Result<'v, ()> {
+    if !(2..=20).contains(&value.len()) {
+        Err(Error::validation(
+            "The name must be between 2 to 20 characters long.",
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Validates the length of the description of a task, as sent by the client.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated.
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_description<'v>(value: &str) -> form::Result<'v, ()> {
+    if !(5..=200).contains(&value.len()) {
+        Err(Error::validation(
+            "The description must be at least 5 characters long.",
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Validates that the provided due date, if any, is a valid `YYYY-MM-DD` date.
+///
+/// An empty value is accepted and leaves the task without a due date.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated (`Option<String>`).
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_due_date<'v>(value: &Option<String>) -> form::Result<'v, ()> {
+    if value
+        .as_deref()
+        .is_some_and(|date| !date.trim().is_empty() && parse_due_date(date).is_none())
+    {
+        Err(Error::validation(
+            "The due date must be a valid date (YYYY-MM-DD).",
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Builds the body of the response to a task creation.
+///
+/// # Arguments
+///
+/// * `task` - The created task.
+///
+/// # Returns
+///
+/// The body, stored as is for the retries sharing the idempotency key of the request.
+///
+fn task_created(task: Task::Model) -> ResponseRequest<Task::Model> {
+    ResponseRequest {
+        message: Some("Task created successfully".to_string()),
+        status: Status::Ok,
+        code: None,
+        data: task,
+    }
 }
 
 /// Endpoint for creating a new task.
 ///
 /// This function handles the HTTP POST request to create a new task.
-/// It expects a JSON payload `ManageTodo` containing task details.
+/// It expects a form or JSON payload `ManageTodo` containing task details.
+///
+/// When an `Idempotency-Key` header is sent, the response is stored for 24 hours and replayed
+/// verbatim to the retries sending the same key, so a retried request never creates a second task.
 ///
 /// # Arguments
 ///
-/// * `form` - JSON payload containing `ManageTodo` data.
+/// * `form` - Form or JSON payload containing `ManageTodo` data.
 /// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `idempotency_key` - The `Idempotency-Key` header, if any.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<Task::Model>>`) with status `200 OK` on success or `500 Internal Server Error` on failure.
+/// A custom response (`TaggedResponse<Task::Model>`), carrying the `ETag` of the task, with status `200 OK` on success,
+/// the stored response when the request is a retry, or an `ApiError` on failure.
 ///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    request_body(content(
+        (ManageTodo = "application/json"),
+        (ManageTodo = "application/x-www-form-urlencoded")
+    )),
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Unique key of the request, retries sending the same key within 24 hours get the first response back")
+    ),
+    responses(
+        (status = 200, description = "Task created successfully, or the response of the first attempt with `Idempotent-Replayed: true`", body = ResponseRequest<Task::Model>,
+            headers(
+                ("ETag" = String, description = "Revision of the task"),
+                ("Idempotent-Replayed" = Option<String>, description = "`true` when the response is replayed")
+            )),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 409, description = "A request with the same idempotency key is still in progress", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid task fields, or idempotency key reused with a different payload", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
 #[post("/create", data = "<form>")]
 pub async fn create_task(
-    form: Form<ManageTodo>,
+    form: FormOrJson<ManageTodo>,
     user: JWT,
+    request_id: RequestId,
+    idempotency_key: IdempotencyKey,
     conn: Connection<'_, Db>,
-) -> Response<Option<Task::Model>> {
+) -> Result<Either<Tagged<Response<Task::Model>>, StoredResponse>, ApiError> {
     // Extract database connection
     let db = conn.into_inner();
 
     // Extract payload data
     let todo = form.into_inner();
 
-    // Attempt to create a new task using provided payload
-    let task = TaskMutation::create(
-        TaskPayload {
-            name: todo.name.trim().to_owned(),
-            description: todo.description.trim().to_owned(),
-            is_active: todo.is_active,
-            user_id: user.claims.sub,
-        },
-        db,
-    )
-    .await;
+    // Replay the response of an earlier attempt sharing the idempotency key
+    if let Some(key) = &idempotency_key.0 {
+        let request = to_value(&todo).map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    match task {
-        // Return a successful response with the created task details
-        Ok(created_task) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                message: Some("Task created successfully".to_string()),
-                status: Status::Ok,
-                data: Some(created_task),
-            }),
-        ),
-        // Return an internal server error response with the error message
-        Err(_) => Custom(
-            Status::InternalServerError,
-            Json(ResponseRequest {
-                message: Some("Failed to create task".to_string()),
-                status: Status::InternalServerError,
-                data: None,
-            }),
-        ),
+        if let Some(stored) =
+            IdempotencyMutation::reserve(key, request, user.claims.sub, db).await?
+        {
+            return Ok(Either::Right(stored));
+        }
     }
+
+    // Attempt to create a new task using provided payload, storing the response for the retries
+    // sharing the idempotency key in the same transaction
+    let payload = todo.into_payload(user.claims.sub, request_id, None);
+    let created_task = match &idempotency_key.0 {
+        Some(key) => {
+            let created_task = TaskMutation::create_with_key(
+                payload,
+                key,
+                |task| {
+                    Ok(StoredResponse {
+                        status: Status::Ok,
+                        body: to_string(&task_created(task.clone()))
+                            .map_err(|e| ApiError::Internal(e.to_string()))?,
+                        etag: Some(etag(task.version)),
+                    })
+                },
+                db,
+            )
+            .await;
+
+            if created_task.is_err() {
+                // Let a retry run the request again, the lease frees the key if this fails too
+                if let Err(e) = IdempotencyMutation::release(key, user.claims.sub, db).await {
+                    println!("Could not release the idempotency key of a failed request: {e}");
+                }
+            }
+            created_task?
+        }
+        None => TaskMutation::create(payload, db).await?,
+    };
+
+    let body = task_created(created_task);
+
+    // Return a successful response with the created task details
+    Ok(Either::Left(Tagged::Modified {
+        etag: etag(body.data.version),
+        response: Custom(Status::Ok, Json(body)),
+    }))
 }
 
 /// Endpoint for updating an existing task.
 ///
 /// This function handles the HTTP PATCH request to update an existing task identified by `id`.
-/// It expects a JSON payload `ManageTodo` containing updated task details.
+/// It expects a form or JSON payload `ManageTodo` containing updated task details.
 ///
 /// # Arguments
 ///
-/// * `form` - JSON payload containing `ManageTodo` data.
+/// * `form` - Form or JSON payload containing `ManageTodo` data.
 /// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `if_match` - The `If-Match` header, the `ETag` the client expects the task to have.
 /// * `id` - The ID of the task to be updated.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<Task::Model>>`) with status `200 OK` on success or `500 Internal Server Error` on failure.
+/// A custom response (`TaggedResponse<Task::Model>`), carrying the `ETag` of the task, with status `200 OK` on success,
+/// `404 Not Found` if the task is not found, `412 Precondition Failed` if it changed since it was read
+/// or an `ApiError` on failure.
 ///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    request_body(content(
+        (ManageTodo = "application/json"),
+        (ManageTodo = "application/x-www-form-urlencoded"),
+        (MergePatchTodo = "application/merge-patch+json")
+    )),
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag the task must still have, `412` otherwise")
+    ),
+    responses(
+        (status = 200, description = "Task updated successfully", body = ResponseRequest<Task::Model>,
+            headers(("ETag" = String, description = "Revision of the task"))),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Task not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 412, description = "The task changed since it was read", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid task fields", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
 #[patch("/update/<id>", data = "<form>")]
 pub async fn update_task(
-    form: Form<ManageTodo>,
+    form: FormOrJson<ManageTodo>,
     user: JWT,
+    request_id: RequestId,
+    if_match: IfMatch,
     id: i32,
     conn: Connection<'_, Db>,
-) -> Response<Option<Task::Model>> {
+) -> TaggedResponse<Task::Model> {
     // Extract database connection
     let db = conn.into_inner();
 
@@ -121,38 +439,230 @@ pub async fn update_task(
     let todo = form.into_inner();
 
     // Attempt to update an existing task using provided payload
-    let task = TaskMutation::update(
-        TaskPayload {
-            name: todo.name.trim().to_owned(),
-            description: todo.description.trim().to_owned(),
-            is_active: todo.is_active,
-            user_id: user.claims.sub,
-        },
+    let updated_task = TaskMutation::update(
+        todo.into_payload(user.claims.sub, request_id, if_match.0),
         id,
         db,
     )
-    .await;
+    .await?;
 
-    match task {
-        // Return a successful response with the updated task details
-        Ok(updated_task) => Custom(
+    // Return a successful response with the updated task details
+    Ok(Tagged::Modified {
+        etag: etag(updated_task.version),
+        response: Custom(
             Status::Ok,
             Json(ResponseRequest {
-                message: Some("Task created successfully".to_string()),
+                message: Some("Task updated successfully".to_string()),
                 status: Status::Ok,
-                data: Some(updated_task),
+                code: None,
+                data: updated_task,
             }),
         ),
-        // Return an internal server error response with the error message
-        Err(_) => Custom(
-            Status::InternalServerError,
+    })
+}
+
+/// Struct representing a JSON Merge Patch (RFC 7396) document for a TODO task.
+///
+/// Every field is optional: a missing member is left untouched, while an explicit `null`
+/// asks to remove the value, which is only allowed for the due date, the tags and the project. Other members, such as
+/// the `id` or `version` of a task sent back whole, are ignored.
+///
+#[derive(Deserialize, ToSchema)]
+pub struct MergePatchTodo {
+    /// The new name of the task.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<String>)]
+    pub name: Option<Option<String>>,
+    /// The new description of the task.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<String>)]
+    pub description: Option<Option<String>>,
+    /// The new active flag of the task.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<bool>)]
+    pub is_active: Option<Option<bool>>,
+    /// The new due date of the task, `null` removes it.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<String>, format = Date)]
+    pub due_date: Option<Option<NaiveDate>>,
+    /// The new priority of the task.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<Task::TaskPriority>)]
+    pub priority: Option<Option<Task::TaskPriority>>,
+    /// The new tags of the task, `null` removes them all.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<Vec<String>>)]
+    pub tags: Option<Option<Vec<String>>>,
+    /// The new project of the task, `null` removes it.
+    #[serde(default, deserialize_with = "deserialize_patch_member")]
+    #[schema(value_type = Option<String>)]
+    pub project: Option<Option<String>>,
+}
+
+/// Deserializes a merge patch member, keeping an explicit `null` apart from a missing member.
+///
+/// Missing members fall back to `None` through `#[serde(default)]`, so this function only
+/// runs for members present in the document and always wraps the value in `Some`.
+///
+fn deserialize_patch_member<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Extracts a non-nullable member from a merge patch and validates it.
+///
+/// # Arguments
+///
+/// * `member` - The member as found in the merge patch document.
+/// * `field_name` - Name of the field being validated (e.g., "name", "description").
+/// * `validate` - The validator of the field in `ManageTodo`, so both payloads follow the same rules.
+///
+/// # Returns
+///
+/// A `Result` with the trimmed value (`None` when the member is missing) or a validation message.
+///
+fn validate_patch_member(
+    member: Option<Option<String>>,
+    field_name: &str,
+    validate: fn(&str) -> form::Result<'static, ()>,
+) -> Result<Option<String>, String> {
+    match member {
+        // The member is missing, so the stored value is kept
+        None => Ok(None),
+        // The member is `null`, which would remove a required value
+        Some(None) => Err(format!("The {} cannot be removed.", field_name)),
+        // The member has a value, which must follow the same rules as the form
+        Some(Some(value)) => match validate(&value) {
+            Ok(()) => Ok(Some(value.trim().to_owned())),
+            Err(errors) => Err(errors.first().map_or_else(
+                || format!("The {} is invalid.", field_name),
+                ToString::to_string,
+            )),
+        },
+    }
+}
+
+/// Endpoint for partially updating an existing task.
+///
+/// This function handles the HTTP PATCH request with an `application/merge-patch+json` body,
+/// applying only the fields present in the `MergePatchTodo` document to the task identified by `id`.
+///
+/// # Arguments
+///
+/// * `patch` - JSON Merge Patch document containing `MergePatchTodo` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `if_match` - The `If-Match` header, the `ETag` the client expects the task to have.
+/// * `id` - The ID of the task to be updated.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`TaggedResponse<Task::Model>`), carrying the `ETag` of the task, with status `200 OK` on success,
+/// `412 Precondition Failed` if the task changed since it was read,
+/// `422 Unprocessable Entity` if a field is invalid or an `ApiError` on failure.
+///
+#[patch(
+    "/update/<id>",
+    format = "application/merge-patch+json",
+    data = "<patch>",
+    rank = 2
+)]
+pub async fn patch_task(
+    patch: Json<MergePatchTodo>,
+    user: JWT,
+    request_id: RequestId,
+    if_match: IfMatch,
+    id: i32,
+    conn: Connection<'_, Db>,
+) -> TaggedResponse<Task::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Extract payload data
+    let patch = patch.into_inner();
+
+    // Validate every member present in the document
+    let name = validate_patch_member(patch.name, "name", validate_name);
+    let description = validate_patch_member(patch.description, "description", validate_description);
+    let is_active = match patch.is_active {
+        Some(None) => Err("The is_active cannot be removed.".to_string()),
+        is_active => Ok(is_active.flatten()),
+    };
+    let priority = match patch.priority {
+        Some(None) => Err("The priority cannot be removed.".to_string()),
+        priority => Ok(priority.flatten()),
+    };
+    let tags = patch
+        .tags
+        .map(Option::unwrap_or_default)
+        .map(|tags| match validate_tags(&tags) {
+            Ok(()) => Ok(normalize_tags(tags)),
+            Err(errors) => Err(errors
+                .first()
+                .map_or_else(|| "The tags are invalid.".to_string(), ToString::to_string)),
+        })
+        .transpose();
+    let project = patch
+        .project
+        .map(|project| match validate_project(&project) {
+            Ok(()) => Ok(normalize_project(project)),
+            Err(errors) => Err(errors.first().map_or_else(
+                || "The project is invalid.".to_string(),
+                ToString::to_string,
+            )),
+        })
+        .transpose();
+
+    let (name, description, is_active, priority, tags, project) =
+        match (name, description, is_active, priority, tags, project) {
+            (Ok(name), Ok(description), Ok(is_active), Ok(priority), Ok(tags), Ok(project)) => {
+                (name, description, is_active, priority, tags, project)
+            }
+            // Return a validation error with the first invalid field
+            (Err(e), _, _, _, _, _)
+            | (_, Err(e), _, _, _, _)
+            | (_, _, Err(e), _, _, _)
+            | (_, _, _, Err(e), _, _)
+            | (_, _, _, _, Err(e), _)
+            | (_, _, _, _, _, Err(e)) => return Err(ApiError::Validation(e)),
+        };
+
+    // Attempt to apply the partial update to the task
+    let updated_task = TaskMutation::patch(
+        TaskPatchPayload {
+            name,
+            description,
+            is_active,
+            due_date: patch.due_date,
+            priority,
+            tags,
+            project,
+            user_id: user.claims.sub,
+            request_id: Some(request_id.0),
+            if_match: if_match.0,
+        },
+        id,
+        db,
+    )
+    .await?;
+
+    // Return a successful response with the updated task details
+    Ok(Tagged::Modified {
+        etag: etag(updated_task.version),
+        response: Custom(
+            Status::Ok,
             Json(ResponseRequest {
-                message: Some("Failed to update task".to_string()),
-                status: Status::InternalServerError,
-                data: None,
+                message: Some("Task updated successfully".to_string()),
+                status: Status::Ok,
+                code: None,
+                data: updated_task,
             }),
         ),
-    }
+    })
 }
 
 /// Endpoint for deleting a task.
@@ -163,76 +673,130 @@ pub async fn update_task(
 ///
 /// * `id` - The ID of the task to be deleted.
 /// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `if_match` - The `If-Match` header, the `ETag` the client expects the task to have.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<u64>`) with status `200 OK` on success or `500 Internal Server Error` on failure.
+/// A custom response (`ApiResponse<u64>`) with status `200 OK` on success,
+/// `404 Not Found` if the task is not found, `412 Precondition Failed` if it changed since it was read
+/// or an `ApiError` on failure.
 ///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    params(
+        ("If-Match" = Option<String>, Header, description = "ETag the task must still have, `412` otherwise")
+    ),
+    responses(
+        (status = 200, description = "Task deleted successfully", body = ResponseRequest<u64>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Task not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 412, description = "The task changed since it was read", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
 #[delete("/delete/<id>")]
-pub async fn delete_task(id: i32, user: JWT, conn: Connection<'_, Db>) -> Response<u64> {
+pub async fn delete_task(
+    id: i32,
+    user: JWT,
+    request_id: RequestId,
+    if_match: IfMatch,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<u64> {
     // Extract database connection
     let db = conn.into_inner();
 
     // Attempt to delete the task using provided ID
-    let result = TaskMutation::delete(id, user.claims.sub, db).await;
+    let deleted_task =
+        TaskMutation::delete(id, user.claims.sub, Some(request_id.0), if_match.0, db).await?;
 
-    match result {
-        // Return a successful response with the number of rows affected
-        Ok(deleted_task) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                message: Some("Task created successfully".to_string()),
-                status: Status::Ok,
-                data: deleted_task.rows_affected,
-            }),
-        ),
-        // Return an internal server error response with the error message
-        Err(_) => Custom(
-            Status::InternalServerError,
-            Json(ResponseRequest {
-                message: Some("Failed to delete the task".to_string()),
-                status: Status::InternalServerError,
-                data: 0,
-            }),
-        ),
-    }
+    // Return a successful response with the number of rows affected
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Task deleted successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: deleted_task.rows_affected,
+        }),
+    ))
 }
 
 /// Struct representing the filters for querying tasks.
 ///
 /// This struct is used for handling query parameters and validating them for fetching tasks.
 ///
-#[derive(FromForm, Serialize)]
+#[derive(FromForm, Serialize, Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
 pub struct FilterTasks {
     /// The page number for pagination. Defaults to `1`.
-    #[field(default = Some(1), validate = validate_min_params(String::from("page")))]
-    pub page: Option<i32>,
+    #[field(default = QueryInt(Some(1)), validate = validate_min_params(String::from("page")))]
+    #[serde(default)]
+    #[param(value_type = Option<i32>)]
+    #[schema(value_type = Option<i32>)]
+    pub page: QueryInt,
     /// The number of tasks per page. Defaults to `10`.
-    #[field(default = Some(10), validate = validate_min_params(String::from("size")))]
-    pub size: Option<i32>,
-    /// Optional query string for filtering tasks by name or description.
+    #[field(default = QueryInt(Some(10)), validate = validate_min_params(String::from("size")))]
+    #[serde(default)]
+    #[param(value_type = Option<i32>)]
+    #[schema(value_type = Option<i32>)]
+    pub size: QueryInt,
+    /// Optional query string for filtering tasks by name or description, the only text of a task
+    /// as tasks have no comments.
     pub query: Option<String>,
+    /// Structured filter query, such as `status:open priority>=high due<7d -tag:someday project:"Home"`.
+    ///
+    /// Every term must match, `-` negates a term and bare words match a part of the name or description.
+    /// The fields are:
+    /// - `status` with `:`, `=` or `!=` and `open` or `done`.
+    /// - `name` and `description`, `:` matching a part of the text ignoring case, `=` and `!=` the whole text.
+    /// - `id` with `:`, `=`, `!=`, `>`, `>=`, `<` or `<=` and a number.
+    /// - `due` with the same operators and a `YYYY-MM-DD` date, `today`, `tomorrow`, `yesterday`
+    ///   or an offset from today such as `7d`, `-3d` or `2w`; `due:none` matches the tasks without a due date.
+    /// - `priority` with the same operators and `low`, `normal`, `high` or `urgent`, from lowest to highest.
+    /// - `tag` with `:`, `=` or `!=` and a tag; `tag:none` matches the tasks without tags.
+    /// - `project` like `name`; `project:none` matches the tasks without a project.
+    pub q: Option<String>,
+    /// Only list the tasks created after this time, as an RFC 3339 timestamp or a `YYYY-MM-DD` date.
+    #[field(validate = validate_timestamp())]
+    pub created_after: Option<String>,
+    /// Only list the tasks changed at or after this time, as an RFC 3339 timestamp or a `YYYY-MM-DD` date.
+    #[field(validate = validate_timestamp())]
+    pub updated_since: Option<String>,
+    /// The key used to sort the tasks (`id`, `name`, `created_at` or `updated_at`). Defaults to `id`.
+    pub sort: Option<TaskSort>,
+    /// Opaque cursor returned as `next_cursor`, switches to cursor pagination.
+    pub after: Option<String>,
+    /// Opaque cursor returned as `prev_cursor`, switches to cursor pagination.
+    pub before: Option<String>,
+    /// The number of tasks per cursor page, switches to cursor pagination. Defaults to `10`.
+    #[field(validate = validate_min_params(String::from("limit")))]
+    #[serde(default)]
+    #[param(value_type = Option<i32>)]
+    #[schema(value_type = Option<i32>)]
+    pub limit: QueryInt,
 }
 
 /// Validates that the provided value is greater than 0.
 ///
-/// This function is used to validate page and size parameters in `FilterTasks`.
+/// This function is used to validate page and size parameters in `FilterTasks`, `SearchFilter` and `ViewPage`.
 ///
 /// # Arguments
 ///
-/// * `value` - Reference to the value to be validated (`Option<i32>`).
+/// * `value` - Reference to the value to be validated (`QueryInt`).
 /// * `field_name` - Name of the field being validated (e.g., "page", "size").
 ///
 /// # Returns
 ///
 /// A `form::Result` indicating success or a validation error.
 ///
-fn validate_min_params<'v>(value: &Option<i32>, field_name: String) -> form::Result<'v, ()> {
+pub fn validate_min_params<'v>(value: &QueryInt, field_name: String) -> form::Result<'v, ()> {
     // Check if the value is less than 1
-    if let Some(val) = value {
+    if let Some(val) = value.0 {
         // Return an error if the value is less than 1
-        if *val < 1 {
+        if val < 1 {
             // Return a validation error with a custom message
             Err(Error::validation(format!(
                 "The {} number must be greater than 0.",
@@ -245,10 +809,54 @@ fn validate_min_params<'v>(value: &Option<i32>, field_name: String) -> form::Res
     Ok(())
 }
 
+/// Parses a point in time sent as an RFC 3339 timestamp, or as a `YYYY-MM-DD` date meaning its midnight UTC.
+///
+/// # Arguments
+///
+/// * `value` - The time as sent by the client.
+///
+/// # Returns
+///
+/// The parsed time, or `None` if the value is neither a timestamp nor a date.
+///
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .ok()
+        .or_else(|| parse_due_date(value).map(|date| date.and_time(Default::default()).and_utc()))
+}
+
+/// Validates that the provided time, if any, is an RFC 3339 timestamp or a `YYYY-MM-DD` date.
+///
+/// # Arguments
+///
+/// * `value` - Reference to the value to be validated (`Option<String>`).
+///
+/// # Returns
+///
+/// A `form::Result` indicating success or a validation error.
+///
+fn validate_timestamp<'v>(value: &Option<String>) -> form::Result<'v, ()> {
+    if value
+        .as_deref()
+        .is_some_and(|time| parse_timestamp(time).is_none())
+    {
+        Err(Error::validation(
+            "The time must be an RFC 3339 timestamp or a date (YYYY-MM-DD).",
+        ))?;
+    }
+
+    Ok(())
+}
+
 /// Endpoint for fetching a list of tasks.
 ///
 /// This function handles the HTTP GET request to fetch a list of tasks based on optional filters.
-/// It accepts query parameters `page`, `size`, and `query` to paginate and filter tasks.
+/// It accepts query parameters `page`, `size`, `query`, `q`, `created_after`, `updated_since` and `sort`
+/// to paginate and filter tasks.
+/// When any of `after`, `before` or `limit` is present the tasks are paginated by cursor instead of by page.
 ///
 /// # Arguments
 ///
@@ -258,48 +866,158 @@ fn validate_min_params<'v>(value: &Option<i32>, field_name: String) -> form::Res
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<GetAllTasks>>`) with status `200 OK` on success or `500 Internal Server Error` on failure.
+/// A custom response (`ApiResponse<TaskList>`) with status `200 OK` on success or an `ApiError` on failure.
 ///
-#[get("/?<filter..>")]
+#[utoipa::path(
+    get,
+    path = "/task",
+    tag = "Task",
+    params(FilterTasks),
+    responses(
+        (status = 200, description = "Tasks of the current user", body = ResponseRequest<TaskList>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid filters", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
 pub async fn get_tasks(
-    filter: FilterTasks,
+    filter: QueryForm<FilterTasks>,
     user: JWT,
     conn: Connection<'_, Db>,
-) -> Response<Option<GetAllTasks>> {
-    // Construct pagination payload from query parameters
-    let payload = PaginationPayload {
-        page: filter.page.unwrap_or(1) as u64,
-        size: filter.size.unwrap_or(10) as u64,
-        query: filter.query.clone(),
-        user_id: user.claims.sub,
+) -> ApiResponse<TaskList> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Extract query parameters
+    let filter = filter.into_inner();
+
+    let sort = filter.sort.unwrap_or_default();
+
+    // Attempt to fetch tasks using provided filters, by cursor if any cursor parameter is present
+    let tasks_result = match (&filter.after, &filter.before, filter.limit.0) {
+        (None, None, None) => {
+            // Construct pagination payload from query parameters
+            let payload = PaginationPayload {
+                page: filter.page.0.unwrap_or(1) as u64,
+                size: filter.size.0.unwrap_or(10) as u64,
+                query: filter.query.clone(),
+                filter: filter.q.clone(),
+                created_after: filter.created_after.as_deref().and_then(parse_timestamp),
+                updated_since: filter.updated_since.as_deref().and_then(parse_timestamp),
+                sort,
+                user_id: user.claims.sub,
+            };
+
+            TaskList::Page(TaskQueries::get_tasks(payload, db).await?)
+        }
+        _ => {
+            // Construct cursor payload from query parameters
+            let payload = CursorPayload {
+                after: filter.after.clone(),
+                before: filter.before.clone(),
+                limit: filter.limit.0.unwrap_or(10) as u64,
+                query: filter.query.clone(),
+                filter: filter.q.clone(),
+                created_after: filter.created_after.as_deref().and_then(parse_timestamp),
+                updated_since: filter.updated_since.as_deref().and_then(parse_timestamp),
+                sort,
+                user_id: user.claims.sub,
+            };
+
+            TaskList::Cursor(TaskQueries::get_tasks_by_cursor(payload, db).await?)
+        }
     };
 
+    // Return a successful response with the list of tasks
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: tasks_result,
+        }),
+    ))
+}
+
+/// Struct representing the parameters of a full-text search of tasks.
+#[derive(FromForm, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchFilter {
+    /// The search query, matched against the name and the description of the tasks, which have no comments.
+    /// Supports `"phrases"`, `-negated` terms and `prefix*` matching.
+    #[field(validate = len(1..=200).or_else(msg!("The search query must be between 1 to 200 characters long.")))]
+    pub q: String,
+    /// The page number for pagination. Defaults to `1`.
+    #[field(default = QueryInt(Some(1)), validate = validate_min_params(String::from("page")))]
+    #[param(value_type = Option<i32>)]
+    pub page: QueryInt,
+    /// The number of tasks per page. Defaults to `10`.
+    #[field(default = QueryInt(Some(10)), validate = validate_min_params(String::from("size")))]
+    #[param(value_type = Option<i32>)]
+    pub size: QueryInt,
+}
+
+/// Endpoint for searching tasks.
+///
+/// This function handles the HTTP GET request to search the tasks of the current user by name and description,
+/// returning the most relevant tasks first along with highlighted snippets.
+///
+/// # Arguments
+///
+/// * `search` - Struct containing the search query and pagination parameters (`SearchFilter`).
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<SearchTasks>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    params(SearchFilter),
+    responses(
+        (status = 200, description = "Matching tasks of the current user", body = ResponseRequest<SearchTasks>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid search query", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/search")]
+pub async fn search_tasks(
+    search: QueryForm<SearchFilter>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<SearchTasks> {
     // Extract database connection
     let db = conn.into_inner();
 
-    // Attempt to fetch tasks using provided filters
-    let tasks = TaskQueries::get_tasks(payload, db).await;
+    // Extract query parameters
+    let search = search.into_inner();
 
-    match tasks {
-        // Return a successful response with the list of tasks
-        Ok(tasks_result) => Custom(
-            Status::Ok,
-            Json(ResponseRequest {
-                message: None,
-                status: Status::Ok,
-                data: Some(tasks_result),
-            }),
-        ),
-        // Return an internal server error response with the error message
-        Err(_) => Custom(
-            Status::InternalServerError,
-            Json(ResponseRequest {
-                message: Some("Failed to fetch tasks".to_string()),
-                status: Status::InternalServerError,
-                data: None,
-            }),
-        ),
-    }
+    // Construct search payload from query parameters
+    let payload = SearchPayload {
+        query: search.q,
+        page: search.page.0.unwrap_or(1) as u64,
+        size: search.size.0.unwrap_or(10) as u64,
+        user_id: user.claims.sub,
+    };
+
+    // Attempt to search the tasks
+    let search_result = TaskQueries::search_tasks(payload, db).await?;
+
+    // Return a successful response with the matching tasks
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: search_result,
+        }),
+    ))
 }
 
 /// Endpoint for fetching a single task by ID.
@@ -310,39 +1028,483 @@ pub async fn get_tasks(
 ///
 /// * `id` - The ID of the task to fetch.
 /// * `user` - JWT token representing the authenticated user.
+/// * `if_none_match` - The `If-None-Match` header, the `ETag` of the copy held by the client.
 /// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
 ///
 /// # Returns
 ///
-/// A custom response (`Response<Option<TaskModel>>`) with status `200 OK` on success,
-/// `404 Not Found` if the task is not found, or `500 Internal Server Error` on failure.
+/// A custom response (`TaggedResponse<TaskModel>`), carrying the `ETag` of the task, with status `200 OK` on success,
+/// `304 Not Modified` if the copy of the client is current, `404 Not Found` if the task is not found,
+/// or an `ApiError` on failure.
 ///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    params(
+        ("If-None-Match" = Option<String>, Header, description = "ETag of the copy held by the client")
+    ),
+    responses(
+        (status = 200, description = "Task of the current user", body = ResponseRequest<TaskModel>,
+            headers(("ETag" = String, description = "Revision of the task"))),
+        (status = 304, description = "The copy held by the client is current",
+            headers(("ETag" = String, description = "Revision of the task"))),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Task not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
 #[get("/<id>")]
-pub async fn get_task(id: i32, user: JWT, conn: Connection<'_, Db>) -> Response<Option<TaskModel>> {
+pub async fn get_task(
+    id: i32,
+    user: JWT,
+    if_none_match: IfNoneMatch,
+    conn: Connection<'_, Db>,
+) -> TaggedResponse<TaskModel> {
     // Extract database connection
     let db = conn.into_inner();
 
     // Attempt to fetch a task by ID using the provided user ID
-    let result = TaskQueries::get_task_by_id(id, user.claims.sub, db).await;
+    let task = TaskQueries::get_task_by_id(id, user.claims.sub, db).await?;
+
+    // Return the task details, or `304 Not Modified` if the client copy is current
+    Ok(Tagged::conditional(
+        etag(task.version),
+        if_none_match,
+        || {
+            Custom(
+                Status::Ok,
+                Json(ResponseRequest {
+                    message: None,
+                    status: Status::Ok,
+                    code: None,
+                    data: task,
+                }),
+            )
+        },
+    ))
+}
+
+/// Endpoint for fetching the change history of a task.
+///
+/// This function handles the HTTP GET request returning every recorded event of the task identified by `id`
+/// (creation, updates, status changes, deletion and reverts), oldest first. The history is kept after the task is deleted.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the task.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<TaskEvent::Model>>`) with status `200 OK` on success,
+/// `404 Not Found` if the task has no history, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    responses(
+        (status = 200, description = "History of the task", body = ResponseRequest<Vec<TaskEvent::Model>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Task not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/<id>/history")]
+pub async fn get_task_history(
+    id: i32,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Vec<TaskEvent::Model>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to fetch the history of the task
+    let history = TaskEventQueries::get_history(id, user.claims.sub, db).await?;
+
+    // Return a successful response with the events
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: history,
+        }),
+    ))
+}
+
+/// Endpoint for reverting a task to an earlier revision.
+///
+/// This function handles the HTTP POST request restoring the task identified by `id` to the state
+/// it had right after the history event `revision`. Deleted tasks are restored with the same ID.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the task to be reverted.
+/// * `revision` - The ID of the history event to revert to.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - ID of the request, recorded in the task history.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`TaggedResponse<Task::Model>`), carrying the `ETag` of the task, with status `200 OK` on success,
+/// `404 Not Found` if the task or the revision is not found, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    responses(
+        (status = 200, description = "Task reverted successfully", body = ResponseRequest<Task::Model>,
+            headers(("ETag" = String, description = "Revision of the task"))),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Task or revision not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "The task did not exist at this revision", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/<id>/revert/<revision>")]
+pub async fn revert_task(
+    id: i32,
+    revision: i32,
+    user: JWT,
+    request_id: RequestId,
+    conn: Connection<'_, Db>,
+) -> TaggedResponse<Task::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to revert the task
+    let reverted_task =
+        TaskMutation::revert(id, revision, user.claims.sub, Some(request_id.0), db).await?;
 
-    match result {
-        // Return a successful response with the task details
-        Ok(task) => Custom(
+    // Return a successful response with the reverted task details
+    Ok(Tagged::Modified {
+        etag: etag(reverted_task.version),
+        response: Custom(
             Status::Ok,
             Json(ResponseRequest {
-                message: None,
+                message: Some("Task reverted successfully".to_string()),
                 status: Status::Ok,
-                data: Some(task),
+                code: None,
+                data: reverted_task,
             }),
         ),
-        // Return a not found response if the task is not found
-        Err(e) => Custom(
-            Status::NotFound,
-            Json(ResponseRequest {
-                message: Some(e.to_string()),
-                status: Status::NotFound,
-                data: None,
-            }),
+    })
+}
+
+/// Response carrying an exported file, downloaded as an attachment.
+#[derive(Responder)]
+pub struct ExportResponse<R> {
+    /// The content of the file.
+    pub(crate) body: (ContentType, R),
+    /// The `Content-Disposition` header, naming the file.
+    pub(crate) disposition: Header<'static>,
+}
+
+/// Struct representing the query parameters of an export.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportOptions {
+    /// The format of the file: `json` (default), `csv`, `md` or `todotxt`.
+    #[field(default = TaskFormat::Json)]
+    #[param(inline, required = false)]
+    pub format: TaskFormat,
+}
+
+/// Struct representing the query parameters of an import.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportOptions {
+    /// The format of the file: `json` (default), `csv`, `md` or `todotxt`.
+    #[field(default = TaskFormat::Json)]
+    #[param(inline, required = false)]
+    pub format: TaskFormat,
+    /// Whether to only check the file, without importing anything. Defaults to `false`.
+    #[field(default = false)]
+    #[param(required = false)]
+    pub dry_run: bool,
+}
+
+/// Endpoint for exporting the tasks of the current user.
+///
+/// This function handles the HTTP GET request streaming every task of the user as a file,
+/// in pages so large exports are not held in memory.
+///
+/// # Arguments
+///
+/// * `options` - Struct containing the format of the file (`ExportOptions`).
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// An `ExportResponse` streaming the file with status `200 OK` on success, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    params(ExportOptions),
+    responses(
+        (status = 200, description = "The tasks of the current user", content(
+            (String = "application/json"),
+            (String = "text/csv"),
+            (String = "text/markdown"),
+            (String = "text/plain")
+        ), headers(("Content-Disposition" = String, description = "Name of the file"))),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Unknown format", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/export")]
+pub async fn export_tasks(
+    options: QueryForm<ExportOptions>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> Result<ExportResponse<TextStream![String]>, ApiError> {
+    // The stream outlives the request, so it owns a handle to the pool
+    let db = conn.into_inner().clone();
+    let user_id = user.claims.sub;
+    let format = options.into_inner().format;
+
+    // Read the first page before answering, so a failing database is reported as an error
+    let mut page = TaskQueries::get_tasks_after(0, EXPORT_PAGE, user_id, &db).await?;
+    let mut writer = TaskWriter::new(format);
+
+    let stream = TextStream! {
+        yield writer.begin();
+
+        loop {
+            for task in &page {
+                yield writer.write(task);
+            }
+
+            let after = match page.last() {
+                Some(task) if page.len() as u64 == EXPORT_PAGE => task.id,
+                _ => break,
+            };
+            page = match TaskQueries::get_tasks_after(after, EXPORT_PAGE, user_id, &db).await {
+                Ok(page) => page,
+                // The response has started, the file is left truncated
+                Err(_) => return,
+            };
+        }
+
+        yield writer.finish();
+    };
+
+    Ok(ExportResponse {
+        body: (
+            ContentType::parse_flexible(format.media_type()).unwrap_or(ContentType::Binary),
+            stream,
+        ),
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"tasks.{}\"", format.extension()),
+        ),
+    })
+}
+
+/// Endpoint for importing tasks from a file.
+///
+/// This function handles the HTTP POST request reading the tasks of a file sent as the request body.
+/// Every row is validated like a created task. Rows matching a task of the user by name and due date are
+/// skipped as duplicates, and when any row is invalid nothing is imported.
+///
+/// # Arguments
+///
+/// * `options` - Struct containing the format of the file and whether the import is a dry run (`ImportOptions`).
+/// * `file` - The content of the file.
+/// * `user` - JWT token representing the authenticated user.
+/// * `request_id` - The ID of the request, recorded in the history of the created tasks.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<ImportReport>`) with status `200 OK` on success,
+/// `422 Unprocessable Entity` with the report if any row is invalid, or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task",
+    tag = "Task",
+    params(ImportOptions),
+    request_body(content(
+        (String = "application/json"),
+        (String = "text/csv"),
+        (String = "text/markdown"),
+        (String = "text/plain")
+    )),
+    responses(
+        (status = 200, description = "Tasks imported successfully", body = ResponseRequest<ImportReport>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 413, description = "The file is too large", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "The file cannot be read, or has invalid rows", body = ResponseRequest<ImportReport>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/import", data = "<file>")]
+pub async fn import_tasks(
+    options: QueryForm<ImportOptions>,
+    file: TextFile,
+    user: JWT,
+    request_id: RequestId,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<ImportReport> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Read the rows of the file
+    let options = options.into_inner();
+    let rows = transfer::parse(options.format, &file.0).map_err(ApiError::Validation)?;
+
+    // Attempt to import the tasks
+    import_rows(rows, options.dry_run, user.claims.sub, request_id, db).await
+}
+
+/// Validates the rows of an imported file like created tasks, and imports them.
+///
+/// # Arguments
+///
+/// * `rows` - The rows read from the file.
+/// * `dry_run` - Whether to only check the rows, without importing anything.
+/// * `user_id` - The ID of the user importing the tasks.
+/// * `request_id` - The ID of the request, recorded in the history of the created tasks.
+/// * `db` - SeaORM database connection (`&DatabaseConnection`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<ImportReport>`) with status `200 OK` on success,
+/// `422 Unprocessable Entity` with the report if any row is invalid, or an `ApiError` on failure.
+///
+pub(crate) async fn import_rows(
+    rows: Vec<ImportRow>,
+    dry_run: bool,
+    user_id: i32,
+    request_id: RequestId,
+    db: &DatabaseConnection,
+) -> ApiResponse<ImportReport> {
+    // Validate every row like a created task
+    let candidates = rows
+        .into_iter()
+        .map(|row| ImportCandidate {
+            row: row.row,
+            task: row
+                .fields
+                .map_err(|message| {
+                    vec![FieldError {
+                        field: None,
+                        message,
+                    }]
+                })
+                .and_then(from_json_value::<ManageTodo>)
+                .map(|task| task.into_payload(user_id, request_id.clone(), None)),
+        })
+        .collect();
+
+    // Attempt to import the tasks
+    let report = TaskImportMutation::import(candidates, dry_run, user_id, db).await?;
+
+    let (status, message, code) = match (report.invalid, report.dry_run) {
+        (0, false) => (Status::Ok, "Tasks imported successfully", None),
+        (0, true) => (Status::Ok, "The file is valid, nothing was imported.", None),
+        _ => (
+            Status::UnprocessableEntity,
+            "The file has invalid rows, nothing was imported.",
+            Some("validation_error".to_string()),
         ),
+    };
+
+    // Return the report of the import
+    Ok(Custom(
+        status,
+        Json(ResponseRequest {
+            message: Some(message.to_string()),
+            status,
+            code,
+            data: report,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::form::Form;
+    use rocket::serde::json;
+
+    #[test]
+    fn merge_patches_keep_null_apart_from_missing_members() {
+        let patch: MergePatchTodo = json::from_str(r#"{"due_date": null}"#).unwrap();
+
+        assert_eq!(patch.due_date, Some(None));
+        assert_eq!(patch.name, None);
+        assert_eq!(patch.description, None);
+        assert_eq!(patch.is_active, None);
+    }
+
+    #[test]
+    fn merge_patches_ignore_unknown_members() {
+        let patch: MergePatchTodo =
+            json::from_str(r#"{"id": 4, "version": 2, "name": "Buy milk"}"#).unwrap();
+
+        assert_eq!(patch.name, Some(Some("Buy milk".to_string())));
+        assert_eq!(patch.due_date, None);
+    }
+
+    #[test]
+    fn merge_patches_cannot_remove_required_fields() {
+        assert_eq!(
+            validate_patch_member(Some(None), "name", validate_name),
+            Err("The name cannot be removed.".to_string())
+        );
+        assert_eq!(validate_patch_member(None, "name", validate_name), Ok(None));
+    }
+
+    #[test]
+    fn merge_patches_follow_the_form_rules() {
+        let name = |value: &str| {
+            validate_patch_member(Some(Some(value.to_string())), "name", validate_name)
+        };
+        let out_of_range = Err("The name must be between 2 to 20 characters long.".to_string());
+
+        assert_eq!(name("  Buy milk "), Ok(Some("Buy milk".to_string())));
+        assert_eq!(name("a"), out_of_range);
+        assert_eq!(name(&"a".repeat(21)), out_of_range);
+        assert!(Form::<ManageTodo>::parse("name=a&description=Two litres").is_err());
+        assert!(Form::<ManageTodo>::parse(&format!(
+            "name={}&description=Two litres",
+            "a".repeat(21)
+        ))
+        .is_err());
+        assert!(Form::<ManageTodo>::parse("name=Buy milk&description=Two litres").is_ok());
+    }
+
+    #[test]
+    fn tags_are_stored_in_lowercase_once() {
+        let tags = ["Home", " home ", "", "Weekly"]
+            .map(str::to_string)
+            .to_vec();
+
+        assert_eq!(normalize_tags(tags), ["home", "weekly"]);
+        assert_eq!(normalize_project(Some("  ".to_string())), None);
+        assert!(validate_tags(&vec!["tag".to_string(); 21]).is_err());
+        assert!(validate_tags(&["a".repeat(31)]).is_err());
+    }
+
+    #[test]
+    fn forms_default_the_priority_tags_and_project() {
+        let Ok(todo) = Form::<ManageTodo>::parse("name=Buy milk&description=Two litres") else {
+            panic!("a valid form was rejected");
+        };
+
+        assert_eq!(todo.priority, Task::TaskPriority::Normal);
+        assert!(todo.tags.is_empty());
+        assert_eq!(todo.project, None);
+        assert!(Form::<ManageTodo>::parse(
+            "name=Buy milk&description=Two litres&priority=high&tags=home&tags=garden"
+        )
+        .is_ok_and(|todo| todo.priority == Task::TaskPriority::High && todo.tags.len() == 2));
     }
 }
@@ -0,0 +1,358 @@
+use crate::routes::payload::{FormOrJson, QueryForm, QueryInt};
+use crate::routes::task::{parse_timestamp, validate_min_params, FilterTasks};
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::entities::saved_filter as SavedFilter;
+use database::Db;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::{from_value, to_value, Json, Value};
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{delete, get, patch, post, FromForm};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::{ApiError, FieldError};
+use services::saved_filter::models::saved_filter::TaskView;
+use services::saved_filter::mutations::saved_filter::{SavedFilterMutation, SavedFilterPayload};
+use services::saved_filter::queries::saved_filter::SavedFilterQueries;
+use services::task::queries::task::{GetAllTasks, PaginationPayload, TaskQueries};
+use utoipa::{IntoParams, ToSchema};
+
+/// Struct representing the payload for managing a saved filter.
+///
+/// This struct is used for handling form data and validating the input for creating or updating a saved filter.
+///
+#[derive(Deserialize, Serialize, FromForm, ToSchema)]
+pub struct ManageView {
+    /// The display name of the saved filter.
+    #[field(validate = len(1..=50).or_else(msg!("The name must be between 1 to 50 characters long.")))]
+    pub name: String,
+    /// The task list parameters applied by the saved filter, as accepted by `GET /task`.
+    pub filters: FilterTasks,
+}
+
+/// Converts the payload into the service payload, serializing the task list parameters.
+///
+/// Cursors are short-lived, so saved filters only store page based parameters.
+///
+/// # Arguments
+///
+/// * `view` - The payload sent by the client.
+/// * `user_id` - The ID of the user owning the saved filter.
+///
+/// # Returns
+///
+/// A `Result` with the `SavedFilterPayload`, or an `ApiError::Validation` if cursor parameters are used.
+///
+fn saved_filter_payload(view: ManageView, user_id: i32) -> Result<SavedFilterPayload, ApiError> {
+    let filters = view.filters;
+
+    if filters.after.is_some() || filters.before.is_some() || filters.limit.0.is_some() {
+        return Err(ApiError::Validation(
+            "Saved filters cannot use cursor pagination.".to_string(),
+        ));
+    }
+
+    // Serialize the parameters, leaving out the missing ones
+    let mut filters = to_value(filters)
+        .map_err(|_| ApiError::Internal("An internal error occurred.".to_string()))?;
+    if let Value::Object(members) = &mut filters {
+        members.retain(|_, value| !value.is_null());
+    }
+
+    Ok(SavedFilterPayload {
+        name: view.name.trim().to_owned(),
+        filters,
+        user_id,
+    })
+}
+
+/// Endpoint for listing the views of the current user.
+///
+/// This function handles the HTTP GET request returning the built-in views
+/// (Today, Upcoming, Overdue, No due date) followed by the saved filters of the user.
+///
+/// # Arguments
+///
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<TaskView>>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/task/views",
+    tag = "View",
+    responses(
+        (status = 200, description = "Views of the current user", body = ResponseRequest<Vec<TaskView>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn get_views(user: JWT, conn: Connection<'_, Db>) -> ApiResponse<Vec<TaskView>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Fetch the built-in and saved views
+    let views = SavedFilterQueries::get_views(user.claims.sub, db).await?;
+
+    // Return a successful response with the views
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: views,
+        }),
+    ))
+}
+
+/// Endpoint for creating a new saved filter.
+///
+/// This function handles the HTTP POST request to save a filter under a name.
+/// It expects a form or JSON payload `ManageView`, the filter query is validated before being saved.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageView` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<SavedFilter::Model>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task/views",
+    tag = "View",
+    request_body(content(
+        (ManageView = "application/json"),
+        (ManageView = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "View created successfully", body = ResponseRequest<SavedFilter::Model>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid view fields or filter query", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/create", data = "<form>")]
+pub async fn create_view(
+    form: FormOrJson<ManageView>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<SavedFilter::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to save the filter
+    let payload = saved_filter_payload(form.into_inner(), user.claims.sub)?;
+    let created_view = SavedFilterMutation::create(payload, db).await?;
+
+    // Return a successful response with the saved filter
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("View created successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: created_view,
+        }),
+    ))
+}
+
+/// Endpoint for updating a saved filter.
+///
+/// This function handles the HTTP PATCH request to replace the name and parameters
+/// of the saved filter identified by `id`. Built-in views cannot be updated.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageView` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `id` - The ID of the saved filter to be updated.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<SavedFilter::Model>`) with status `200 OK` on success,
+/// `404 Not Found` if the saved filter is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task/views",
+    tag = "View",
+    request_body(content(
+        (ManageView = "application/json"),
+        (ManageView = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "View updated successfully", body = ResponseRequest<SavedFilter::Model>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "View not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid view fields or filter query", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[patch("/update/<id>", data = "<form>")]
+pub async fn update_view(
+    form: FormOrJson<ManageView>,
+    user: JWT,
+    id: i32,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<SavedFilter::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to update the saved filter
+    let payload = saved_filter_payload(form.into_inner(), user.claims.sub)?;
+    let updated_view = SavedFilterMutation::update(payload, id, db).await?;
+
+    // Return a successful response with the updated saved filter
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("View updated successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: updated_view,
+        }),
+    ))
+}
+
+/// Endpoint for deleting a saved filter.
+///
+/// This function handles the HTTP DELETE request to delete the saved filter identified by `id`.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the saved filter to be deleted.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<u64>`) with status `200 OK` on success,
+/// `404 Not Found` if the saved filter is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task/views",
+    tag = "View",
+    responses(
+        (status = 200, description = "View deleted successfully", body = ResponseRequest<u64>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "View not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/delete/<id>")]
+pub async fn delete_view(id: i32, user: JWT, conn: Connection<'_, Db>) -> ApiResponse<u64> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to delete the saved filter
+    let deleted_view = SavedFilterMutation::delete(id, user.claims.sub, db).await?;
+
+    // Return a successful response with the number of rows affected
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("View deleted successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: deleted_view.rows_affected,
+        }),
+    ))
+}
+
+/// Struct representing the page of a view to fetch, overriding the stored page and size.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ViewPage {
+    /// Page number, overrides the stored one.
+    #[field(validate = validate_min_params(String::from("page")))]
+    #[param(value_type = Option<i32>)]
+    pub page: QueryInt,
+    /// Page size, overrides the stored one.
+    #[field(validate = validate_min_params(String::from("size")))]
+    #[param(value_type = Option<i32>)]
+    pub size: QueryInt,
+}
+
+/// Endpoint for fetching the tasks of a view.
+///
+/// This function handles the HTTP GET request running the stored parameters of the view
+/// identified by `id` (a saved filter ID or a built-in view slug) through `TaskQueries::get_tasks`.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the saved filter, or the slug of a built-in view.
+/// * `pagination` - Optional page number and size, overriding the stored ones (`ViewPage`).
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<GetAllTasks>`) with status `200 OK` on success,
+/// `404 Not Found` if the view is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/task/views",
+    tag = "View",
+    params(
+        ("id" = String, Path, description = "Saved filter ID, or `today`, `upcoming`, `overdue` or `no-due-date`"),
+        ViewPage
+    ),
+    responses(
+        (status = 200, description = "Tasks of the view", body = ResponseRequest<GetAllTasks>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "View not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid page or size", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/<id>")]
+pub async fn get_view_tasks(
+    id: &str,
+    pagination: QueryForm<ViewPage>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<GetAllTasks> {
+    // Extract database connection and query parameters
+    let db = conn.into_inner();
+    let pagination = pagination.into_inner();
+
+    // Find the view and decode its stored parameters
+    let view = SavedFilterQueries::get_view(id, user.claims.sub, db).await?;
+    let filter: FilterTasks = from_value(view.filters)
+        .map_err(|_| ApiError::Internal("The stored view parameters are invalid.".to_string()))?;
+
+    // Run the stored parameters through the task list query
+    let tasks = TaskQueries::get_tasks(
+        PaginationPayload {
+            page: pagination.page.0.or(filter.page.0).unwrap_or(1) as u64,
+            size: pagination.size.0.or(filter.size.0).unwrap_or(10) as u64,
+            query: filter.query,
+            filter: filter.q,
+            created_after: filter.created_after.as_deref().and_then(parse_timestamp),
+            updated_since: filter.updated_since.as_deref().and_then(parse_timestamp),
+            sort: filter.sort.unwrap_or_default(),
+            user_id: user.claims.sub,
+        },
+        db,
+    )
+    .await?;
+
+    // Return a successful response with the tasks of the view
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: tasks,
+        }),
+    ))
+}
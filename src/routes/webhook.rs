@@ -0,0 +1,387 @@
+use crate::routes::payload::{FormOrJson, QueryForm, QueryInt};
+use crate::routes::task::validate_min_params;
+use crate::routes::{ApiResponse, ResponseRequest};
+use database::entities::webhook as Webhook;
+use database::entities::webhook_delivery as WebhookDelivery;
+use database::Db;
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{delete, get, patch, post, FromForm};
+use sea_orm_rocket::Connection;
+use services::auth::jwt::JWT;
+use services::error::FieldError;
+use services::webhook::models::webhook::WebhookDeliveries;
+use services::webhook::mutations::delivery::WebhookDeliveryMutation;
+use services::webhook::mutations::webhook::{WebhookMutation, WebhookPayload};
+use services::webhook::queries::webhook::WebhookQueries;
+use utoipa::{IntoParams, ToSchema};
+
+/// Struct representing the payload for managing a webhook.
+///
+/// This struct is used for handling form data and validating the input for creating or updating a webhook.
+///
+#[derive(Deserialize, Serialize, FromForm, ToSchema)]
+pub struct ManageWebhook {
+    /// The `http` or `https` URL the deliveries are posted to, which must point to a public address.
+    #[field(validate = len(1..=2048).or_else(msg!("The URL must be between 1 to 2048 characters long.")))]
+    pub url: String,
+    /// The events the webhook subscribes to: `task.created`, `task.updated` or `task.deleted`.
+    pub events: Vec<String>,
+    /// The secret signing the deliveries (16 to 255 characters), required on creation and kept when missing on update.
+    pub secret: Option<String>,
+    /// Whether deliveries are sent. Defaults to `true` on creation.
+    pub is_active: Option<bool>,
+}
+
+impl ManageWebhook {
+    /// Converts the validated form into the service payload.
+    fn into_payload(self, user_id: i32) -> WebhookPayload {
+        WebhookPayload {
+            url: self.url.trim().to_owned(),
+            events: self.events,
+            secret: self.secret,
+            is_active: self.is_active,
+            user_id,
+        }
+    }
+}
+
+/// Endpoint for listing the webhooks of the current user.
+///
+/// This function handles the HTTP GET request returning the webhooks of the user, without their secrets.
+///
+/// # Arguments
+///
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Vec<Webhook::Model>>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "Webhook",
+    responses(
+        (status = 200, description = "Webhooks of the current user", body = ResponseRequest<Vec<Webhook::Model>>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/")]
+pub async fn get_webhooks(user: JWT, conn: Connection<'_, Db>) -> ApiResponse<Vec<Webhook::Model>> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Fetch the webhooks of the user
+    let webhooks = WebhookQueries::get_webhooks(user.claims.sub, db).await?;
+
+    // Return a successful response with the webhooks
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: webhooks,
+        }),
+    ))
+}
+
+/// Endpoint for creating a new webhook.
+///
+/// This function handles the HTTP POST request subscribing a URL to task events.
+/// It expects a form or JSON payload `ManageWebhook`, every delivery is signed with its secret.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageWebhook` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Webhook::Model>`) with status `200 OK` on success or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/webhooks",
+    tag = "Webhook",
+    request_body(content(
+        (ManageWebhook = "application/json"),
+        (ManageWebhook = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Webhook created successfully", body = ResponseRequest<Webhook::Model>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid or non-public URL, invalid events or secret", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/create", data = "<form>")]
+pub async fn create_webhook(
+    form: FormOrJson<ManageWebhook>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Webhook::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to create the webhook
+    let payload = form.into_inner().into_payload(user.claims.sub);
+    let created_webhook = WebhookMutation::create(payload, db).await?;
+
+    // Return a successful response with the created webhook
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Webhook created successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: created_webhook,
+        }),
+    ))
+}
+
+/// Endpoint for updating a webhook.
+///
+/// This function handles the HTTP PATCH request to replace the URL and events of the webhook
+/// identified by `id`, and optionally its secret and active flag.
+///
+/// # Arguments
+///
+/// * `form` - Form or JSON payload containing `ManageWebhook` data.
+/// * `user` - JWT token representing the authenticated user.
+/// * `id` - The ID of the webhook to be updated.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<Webhook::Model>`) with status `200 OK` on success,
+/// `404 Not Found` if the webhook is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/webhooks",
+    tag = "Webhook",
+    request_body(content(
+        (ManageWebhook = "application/json"),
+        (ManageWebhook = "application/x-www-form-urlencoded")
+    )),
+    responses(
+        (status = 200, description = "Webhook updated successfully", body = ResponseRequest<Webhook::Model>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Webhook not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid or non-public URL, invalid events or secret", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[patch("/update/<id>", data = "<form>")]
+pub async fn update_webhook(
+    form: FormOrJson<ManageWebhook>,
+    user: JWT,
+    id: i32,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<Webhook::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to update the webhook
+    let payload = form.into_inner().into_payload(user.claims.sub);
+    let updated_webhook = WebhookMutation::update(payload, id, db).await?;
+
+    // Return a successful response with the updated webhook
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Webhook updated successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: updated_webhook,
+        }),
+    ))
+}
+
+/// Endpoint for deleting a webhook.
+///
+/// This function handles the HTTP DELETE request to delete the webhook identified by `id`,
+/// along with its pending deliveries and delivery log.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the webhook to be deleted.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<u64>`) with status `200 OK` on success,
+/// `404 Not Found` if the webhook is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/webhooks",
+    tag = "Webhook",
+    responses(
+        (status = 200, description = "Webhook deleted successfully", body = ResponseRequest<u64>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Webhook not found", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/delete/<id>")]
+pub async fn delete_webhook(id: i32, user: JWT, conn: Connection<'_, Db>) -> ApiResponse<u64> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Attempt to delete the webhook
+    let deleted_webhook = WebhookMutation::delete(id, user.claims.sub, db).await?;
+
+    // Return a successful response with the number of rows affected
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: Some("Webhook deleted successfully".to_string()),
+            status: Status::Ok,
+            code: None,
+            data: deleted_webhook.rows_affected,
+        }),
+    ))
+}
+
+/// Struct representing the page of the delivery log to fetch.
+#[derive(FromForm, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryPage {
+    /// The page number for pagination. Defaults to `1`.
+    #[field(default = QueryInt(Some(1)), validate = validate_min_params(String::from("page")))]
+    #[param(value_type = Option<i32>)]
+    pub page: QueryInt,
+    /// The number of deliveries per page. Defaults to `10`.
+    #[field(default = QueryInt(Some(10)), validate = validate_min_params(String::from("size")))]
+    #[param(value_type = Option<i32>)]
+    pub size: QueryInt,
+}
+
+/// Endpoint for fetching the delivery log of a webhook.
+///
+/// This function handles the HTTP GET request returning the deliveries of the webhook identified
+/// by `id`, the latest first, with their status, number of attempts and last receiver response.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the webhook.
+/// * `pagination` - Struct containing the page number and size (`DeliveryPage`).
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<WebhookDeliveries>`) with status `200 OK` on success,
+/// `404 Not Found` if the webhook is not found or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/webhooks",
+    tag = "Webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook ID"),
+        DeliveryPage
+    ),
+    responses(
+        (status = 200, description = "Deliveries of the webhook", body = ResponseRequest<WebhookDeliveries>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Webhook not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 422, description = "Invalid page or size", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/<id>/deliveries")]
+pub async fn get_webhook_deliveries(
+    id: i32,
+    pagination: QueryForm<DeliveryPage>,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<WebhookDeliveries> {
+    // Extract database connection and query parameters
+    let db = conn.into_inner();
+    let pagination = pagination.into_inner();
+
+    // Fetch the requested page of the delivery log
+    let deliveries = WebhookQueries::get_deliveries(
+        id,
+        user.claims.sub,
+        pagination.page.0.unwrap_or(1) as u64,
+        pagination.size.0.unwrap_or(10) as u64,
+        db,
+    )
+    .await?;
+
+    // Return a successful response with the deliveries
+    Ok(Custom(
+        Status::Ok,
+        Json(ResponseRequest {
+            message: None,
+            status: Status::Ok,
+            code: None,
+            data: deliveries,
+        }),
+    ))
+}
+
+/// Endpoint for sending a delivery of a webhook again.
+///
+/// This function handles the HTTP POST request queuing a new delivery of the payload of the
+/// delivery identified by `delivery_id`, such as one that failed while the receiver was down.
+///
+/// # Arguments
+///
+/// * `id` - The ID of the webhook.
+/// * `delivery_id` - The ID of the delivery to send again.
+/// * `user` - JWT token representing the authenticated user.
+/// * `conn` - SeaORM database connection (`Connection<'_, Db>`).
+///
+/// # Returns
+///
+/// A custom response (`ApiResponse<WebhookDelivery::Model>`) with the new delivery and status `202 Accepted`,
+/// `404 Not Found` if the webhook or delivery is not found, `409 Conflict` if the delivery is still pending
+/// or an `ApiError` on failure.
+///
+#[utoipa::path(
+    context_path = "/webhooks",
+    tag = "Webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook ID"),
+        ("delivery_id" = i32, Path, description = "ID of the delivery to send again")
+    ),
+    responses(
+        (status = 202, description = "Redelivery queued", body = ResponseRequest<WebhookDelivery::Model>),
+        (status = 401, description = "Missing or invalid auth token", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 404, description = "Webhook or delivery not found", body = ResponseRequest<Option<Vec<FieldError>>>),
+        (status = 409, description = "The delivery is still pending", body = ResponseRequest<Option<Vec<FieldError>>>)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/<id>/deliveries/<delivery_id>/redeliver")]
+pub async fn redeliver_webhook(
+    id: i32,
+    delivery_id: i32,
+    user: JWT,
+    conn: Connection<'_, Db>,
+) -> ApiResponse<WebhookDelivery::Model> {
+    // Extract database connection
+    let db = conn.into_inner();
+
+    // Queue a new delivery of the same payload
+    let delivery = WebhookDeliveryMutation::redeliver(id, delivery_id, user.claims.sub, db).await?;
+
+    // Return an accepted response with the queued delivery
+    Ok(Custom(
+        Status::Accepted,
+        Json(ResponseRequest {
+            message: Some("Redelivery queued".to_string()),
+            status: Status::Accepted,
+            code: None,
+            data: delivery,
+        }),
+    ))
+}